@@ -3,7 +3,7 @@ use failure::Error;
 use crate::db::DBBackend;
 use crate::task::Task;
 
-use super::Subcommand;
+use super::{Subcommand, CommandOutput};
 
 // TODO I changed the parsing code to be part of structopt but now I feel like the actual code is
 // more fragile, though really it was fragile the whole time because the main problem is that I
@@ -16,7 +16,7 @@ use super::Subcommand;
 // using the assert_cmd/assert_cli crate.
 
 /// Parse a nonempty string from a command line argument
-fn is_str_nonempty(arg: &str) -> Result<String, Error> {
+pub(super) fn is_str_nonempty(arg: &str) -> Result<String, Error> {
     let s: String = arg.parse().map_err(|e| format_err!("Unable to parse {} as valid string: {}", arg, e))?;
     if s.is_empty() {
         return Err(format_err!("Task description cannot be empty."));
@@ -26,7 +26,7 @@ fn is_str_nonempty(arg: &str) -> Result<String, Error> {
 }
 
 /// Parse a nonzero u32 from a command line argument
-fn is_u32_nonzero(arg: &str) -> Result<u32, Error> {
+pub(super) fn is_u32_nonzero(arg: &str) -> Result<u32, Error> {
     let p: u32 = arg.parse().map_err(|e| format_err!("Unable to parse {} as valid integer: {}", arg, e))?;
     if p == 0 {
         return Err(format_err!("Task priority cannot be 0 because it would never be selected."));
@@ -49,15 +49,13 @@ pub struct Add {
 }
 
 impl Subcommand for Add {
-    fn run(&self, tx: &impl DBBackend) -> Result<Vec<String>, Error> {
+    fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
         let task = Task::new_from_parts(self.task.clone(), self.priority, self.reward)
             .map_err(|e| format_err!("Task input was invalid: {}", e))?;
 
         tx.add_task(&task)
             .map_err(|e| format_err!("Could not add task to database. {}", e))?;
 
-        Ok(vec![
-           format!("Task \"{}\" added to task list.", self.task),
-        ])
+        Ok(CommandOutput::Added(task))
     }
 }
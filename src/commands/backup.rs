@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use failure::Error;
+
+use crate::config::Config;
+use crate::db::SqliteBackend;
+
+use super::CommandOutput;
+
+#[derive(StructOpt, Debug)]
+pub struct Backup {
+    #[structopt(parse(from_os_str))]
+    /// The file to write the backup to. It must not already exist.
+    pub path: PathBuf,
+}
+
+impl Backup {
+    /// Copy the database with SQLite's online backup API, which is safe to run while other
+    /// commands are using the database. Doesn't run in a transaction.
+    pub fn dispatch(&self, config: &Config) -> Result<CommandOutput, Error> {
+        config.db()?.backup_to(&self.path)?;
+
+        Ok(CommandOutput::Message(format!("Backed up database to {}.", self.path.display())))
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub struct Restore {
+    #[structopt(parse(from_os_str))]
+    /// The backup file written by `backup` to restore.
+    pub path: PathBuf,
+}
+
+impl Restore {
+    /// Check the backup and swap it in for the database, then open it so that it is migrated if it
+    /// was written by an older version. Doesn't run in a transaction.
+    pub fn dispatch(&self, config: &Config) -> Result<CommandOutput, Error> {
+        let metadata = SqliteBackend::restore(&config.db_path, &self.path)?;
+        config.db()?;
+
+        Ok(CommandOutput::Message(format!("Restored database from {}, created {}.",
+                                          self.path.display(), metadata.date_created.format("%Y-%m-%d %H:%M:%S UTC"))))
+    }
+}
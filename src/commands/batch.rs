@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+
+use failure::Error;
+use structopt::StructOpt;
+
+use crate::config::Config;
+use crate::db::DBBackend;
+use crate::hooks::Hooks;
+use crate::selection::SelectionStrategy;
+use crate::shell::split_words;
+
+use super::{CommandOutput, TKZArgs, TKZCmd, DEFAULT_COMMAND, read_input};
+
+/// Each command in a batch runs inside this savepoint, so that a failing command can be undone on
+/// its own.
+const SAVEPOINT: &str = "batch_command";
+
+/// Parse a line of a batch into a command. A line is either written like a command line without
+/// the leading `tkzr`, or is a JSON array of the arguments, e.g. `["add", "a task", "3"]`. Blank
+/// lines and lines starting with `#` are skipped.
+pub fn parse_line(line: &str) -> Result<Option<TKZCmd>, Error> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    let words: Vec<String> = if line.starts_with('[') {
+        serde_json::from_str(line).map_err(|e| format_err!("Invalid JSON arguments: {}", e))?
+    }
+    else {
+        split_words(line)?
+    };
+
+    let args = TKZArgs::from_iter_safe(std::iter::once("tkzr".to_string()).chain(words))
+        // clap starts its messages with "error: "
+        .map_err(|e| format_err!("{}", e.message.trim_start_matches("error: ")))?;
+    let cmd = args.cmd.unwrap_or(DEFAULT_COMMAND);
+
+    if cmd.manages_transactions() {
+        return Err(format_err!("This command can't be run in a batch."));
+    }
+
+    Ok(Some(cmd))
+}
+
+#[derive(StructOpt, Debug)]
+pub struct Batch {
+    #[structopt(parse(from_os_str))]
+    /// The file to read commands from. If it is not given or is "-", commands are read from stdin.
+    pub file: Option<PathBuf>,
+    #[structopt(long = "continue-on-error")]
+    /// Skip commands that fail and apply the rest, instead of applying none of them.
+    pub continue_on_error: bool,
+}
+
+impl Batch {
+    /// Run a single command in its own savepoint, undoing its changes if it fails or is read-only.
+    fn run_command(cmd: &TKZCmd, tx: &impl DBBackend, selector: &mut dyn SelectionStrategy, hooks: Option<&Hooks>)
+        -> Result<CommandOutput, Error> {
+        tx.savepoint(SAVEPOINT)?;
+
+        match cmd.run_with_hooks(tx, selector, hooks) {
+            Ok(output) => {
+                if cmd.is_read_only() {
+                    tx.rollback_to_savepoint(SAVEPOINT)?;
+                }
+                else {
+                    tx.release_savepoint(SAVEPOINT)?;
+                }
+                Ok(output)
+            }
+            Err(e) => {
+                tx.rollback_to_savepoint(SAVEPOINT)?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Run every command in a single transaction. Without `--continue-on-error`, the first error
+    /// rolls back the whole transaction. Hooks run as each command is applied, so they may have
+    /// already run for commands that end up being rolled back.
+    pub fn dispatch(&self, config: &Config) -> Result<CommandOutput, Error> {
+        let input = read_input(self.file.as_ref(), "commands")?;
+
+        let mut db = config.db()?;
+        let tx = db.transaction()?;
+        let mut selector = config.selector();
+        let hooks = config.hooks();
+
+        let mut outputs = Vec::new();
+        for (i, line) in input.lines().enumerate() {
+            let res = parse_line(line).and_then(|cmd| match cmd {
+                Some(cmd) => Batch::run_command(&cmd, &tx, &mut *selector, hooks.as_ref()).map(Some),
+                None => Ok(None),
+            });
+
+            match res {
+                Ok(Some(output)) => outputs.push(output),
+                Ok(None) => {}
+                Err(e) if self.continue_on_error => {
+                    outputs.push(CommandOutput::Failed { line: i + 1, command: line.trim().to_string(), error: e.to_string() });
+                }
+                Err(e) => {
+                    return Err(format_err!("Error on line {} ({}), no commands were applied: {}",
+                                           i + 1, line.trim(), e));
+                }
+            }
+        }
+
+        tx.finish()?;
+
+        Ok(CommandOutput::Sequence(outputs))
+    }
+}
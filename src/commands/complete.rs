@@ -1,23 +1,36 @@
 use failure::Error;
 
 use crate::db::DBBackend;
-use crate::commands::Subcommand;
+use crate::commands::{Subcommand, CommandOutput};
 
-#[derive(Debug)]
-pub struct Complete;
+#[derive(StructOpt, Debug, Default)]
+pub struct Complete {
+    #[structopt(long = "note", short = "n")]
+    /// A note about the task, e.g. how it went. It is kept with the task in the trash and searched
+    /// along with its description.
+    pub note: Option<String>,
+}
 
 impl Subcommand for Complete {
-    fn run(&self, tx: &impl DBBackend) -> Result<Vec<String>, Error> {
+    fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
+        if let Some(note) = &self.note {
+            if note.trim().is_empty() {
+                return Err(format_err!("The note can't be empty."));
+            }
+        }
+
         let res = tx.complete_current_task()
             .map_err(|e| format_err!("Could not complete current task. {}", e))?;
        
         if let Some(completed_current) = res {
-            return Ok(vec![
-                      format!("Task \"{}\" completed.\n", completed_current.task()),
-            ]);
+            if let Some(note) = &self.note {
+                tx.set_completion_note(completed_current.uuid(), note.trim())
+                    .map_err(|e| format_err!("Could not add note to completed task. {}", e))?;
+            }
+            return Ok(CommandOutput::Completed(completed_current));
         }
         else {
-            return Ok(vec!["No tasks.".to_string()]);
+            return Ok(CommandOutput::NoTasks);
         }
     }
 }
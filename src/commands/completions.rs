@@ -0,0 +1,143 @@
+use failure::Error;
+use structopt::StructOpt;
+use structopt::clap::Shell;
+
+use crate::config::Config;
+use crate::db::DBBackend;
+use crate::task::Task;
+
+use super::{CommandOutput, TKZArgs, TKZCmd};
+
+/// The name of the binary that completions are generated for.
+const BIN_NAME: &str = "tkzr";
+
+/// The hidden command that completion scripts run to complete task ids. clap's bash completion
+/// generator can't handle subcommand names containing "__", so it isn't a `TKZCmd`.
+pub const COMPLETE_IDS: &str = "__complete-ids";
+
+/// Completion for the task id argument of `TKZCmd::TASK_ID_COMMANDS`, appended to the script
+/// generated by clap. Each script asks `tkzr __complete-ids COMMAND` for the ids when the words
+/// before the one being completed are one of the commands, and falls back to the generated
+/// completion for everything else.
+pub(super) fn task_id_completion(shell: Shell, commands: &[&[&str]]) -> String {
+    if commands.is_empty() {
+        return String::new();
+    }
+
+    let patterns = commands.iter()
+        .map(|command| format!("\"{}\"", command.join(" ")))
+        .collect::<Vec<_>>()
+        .join("|");
+    match shell {
+        Shell::Bash => format!(r#"
+_tkzr_task_ids() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local command="${{COMP_WORDS[*]:1:COMP_CWORD-1}}"
+    case "${{command}}" in
+        {patterns})
+            if [[ "${{cur}}" != -* ]]; then
+                COMPREPLY=( $(tkzr __complete-ids "${{command}}" "${{cur}}" 2>/dev/null | cut -f1) )
+                return 0
+            fi
+            ;;
+    esac
+    _tkzr "$@"
+}}
+
+complete -F _tkzr_task_ids -o bashdefault -o default tkzr
+"#, patterns = patterns),
+        Shell::Zsh => format!(r#"
+_tkzr_task_ids() {{
+    local command="${{(j: :)words[2,CURRENT-1]}}"
+    case "$command" in
+        ({patterns})
+            if [[ "$PREFIX" != -* ]]; then
+                local -a ids
+                ids=(${{(f)"$(tkzr __complete-ids "$command" "$PREFIX" 2>/dev/null | sed -e 's/:/\\:/g' -e 's/\t/:/')"}})
+                _describe 'task id' ids
+                return
+            fi
+            ;;
+    esac
+    _tkzr "$@"
+}}
+
+compdef _tkzr_task_ids tkzr
+_tkzr_task_ids "$@"
+"#, patterns = patterns),
+        Shell::Fish => commands.iter()
+            .map(|command| {
+                let seen: Vec<String> = command.iter().map(|word| format!("__fish_seen_subcommand_from {}", word)).collect();
+                format!("complete -c tkzr -n \"{}\" -f -a \"(tkzr __complete-ids '{}')\"\n",
+                        seen.join("; and "), command.join(" "))
+            })
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub struct Completions {
+    #[structopt(raw(possible_values = r#"&["bash", "zsh", "fish"]"#))]
+    /// The shell to generate completions for.
+    pub shell: Shell,
+}
+
+impl Completions {
+    /// Generate the completion script from the command line definition. This doesn't use the
+    /// database.
+    pub fn dispatch(&self) -> Result<CommandOutput, Error> {
+        let mut script = Vec::new();
+        TKZArgs::clap().gen_completions_to(BIN_NAME, self.shell, &mut script);
+
+        let mut script = String::from_utf8(script)
+            .map_err(|e| format_err!("Generated completions were not valid UTF-8: {}", e))?;
+
+        let completion = task_id_completion(self.shell, TKZCmd::TASK_ID_COMMANDS);
+        let call = format!("_{} \"$@\"", BIN_NAME);
+        match script.rfind(&call) {
+            // the generated zsh script ends by calling its completion function. the task id
+            // completion calls its own function instead, which wraps that one.
+            Some(idx) if matches!(self.shell, Shell::Zsh) && !completion.is_empty() => {
+                script.replace_range(idx..idx + call.len(), completion.trim_start());
+            }
+            _ => script.push_str(&completion),
+        }
+
+        Ok(CommandOutput::Message(script))
+    }
+}
+
+/// The tasks whose ids can be given to `command`, one of `TKZCmd::TASK_ID_COMMANDS` with its words
+/// separated by spaces.
+pub(crate) fn task_id_candidates(tx: &impl DBBackend, command: &str) -> Result<Vec<Task>, Error> {
+    match command {
+        "edit" | "remove" => tx.fetch_all_tasks()
+            .map_err(|e| format_err!("Could not get tasks from database. {}", e)),
+        "trash restore" => {
+            let trash = tx.fetch_trash()
+                .map_err(|e| format_err!("Could not get trash from database. {}", e))?;
+            Ok(trash.into_iter().map(|trashed| trashed.task).collect())
+        }
+        _ => Err(format_err!("\"{}\" doesn't take a task id.", command)),
+    }
+}
+
+/// List the UUID and description of each task whose UUID starts with `prefix` and can be given to
+/// `command`, separated by a tab. This is run by the completion scripts as
+/// `tkzr __complete-ids COMMAND [PREFIX]`.
+pub fn complete_ids(config: &Config, command: &str, prefix: &str) -> Result<Vec<String>, Error> {
+    let mut db = config.db()?;
+    // nothing is changed, so the transaction is rolled back when it's dropped
+    let tx = db.transaction()?;
+    let tasks = task_id_candidates(&tx, command)?;
+
+    Ok(tasks.iter()
+        .filter(|task| task.uuid().to_string().starts_with(prefix))
+        .map(|task| {
+            // the completion scripts split on tabs and lines
+            let description: String = task.task().chars().map(|c| if c.is_control() { ' ' } else { c }).collect();
+            format!("{}\t{}", task.uuid(), description)
+        })
+        .collect())
+}
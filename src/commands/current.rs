@@ -1,8 +1,9 @@
+use chrono::Utc;
 use failure::Error;
 
 use crate::db::DBBackend;
 
-use super::Subcommand;
+use super::{Subcommand, CommandOutput};
 
 #[derive(StructOpt, Debug)]
 pub struct Current {
@@ -12,19 +13,21 @@ pub struct Current {
 }
 
 impl Subcommand for Current {
-    fn run(&self, tx: &impl DBBackend) -> Result<Vec<String>, Error> {
+    fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
         let res = tx.fetch_current_task()
             .map_err(|e| format_err!("Could not get current task from database. {}", e))?;
        
         if let Some(current) = res {
-            return Ok(vec![
-                      format!("{}\n", current.task()),
-                      format!("Category: {}", current.category_str()),
-                      format!("Priority: {}", current.priority().to_string()),
-            ]);
+            let timer = tx.fetch_timer()
+                .map_err(|e| format_err!("Could not get timer from database. {}", e))?;
+            let timer_remaining = timer
+                .filter(|timer| &timer.task_uuid == current.uuid())
+                .and_then(|timer| timer.remaining(Utc::now()));
+
+            return Ok(CommandOutput::Current { task: current, timer_remaining });
         }
         else {
-            return Ok(vec!["No tasks.".to_string()]);
+            return Ok(CommandOutput::NoTasks);
         }
     }
 }
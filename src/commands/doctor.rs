@@ -0,0 +1,42 @@
+use failure::Error;
+
+use crate::config::Config;
+use crate::db::DBBackend;
+
+use super::CommandOutput;
+
+#[derive(StructOpt, Debug)]
+pub struct Doctor {
+    #[structopt(long = "fix")]
+    /// Repair the problems that can be fixed, by removing invalid and orphaned rows and selecting a
+    /// new current task if there isn't one.
+    pub fix: bool,
+}
+
+impl Doctor {
+    /// Check the database in its own transaction, which is only committed with `--fix`. This
+    /// doesn't run in the transaction opened by `TKZCmd::dispatch`, because that reads the current
+    /// task afterwards, which fails on some of the problems being checked for.
+    pub fn dispatch(&self, config: &Config) -> Result<CommandOutput, Error> {
+        let mut db = config.db()?;
+        let tx = db.transaction()?;
+        let mut selector = config.selector();
+
+        let problems = tx.check_database()?;
+        if !self.fix || !problems.iter().any(|problem| problem.fixable) {
+            return Ok(CommandOutput::Doctor { problems, repaired: false });
+        }
+
+        tx.repair_database(&mut *selector)?;
+        let remaining: Vec<String> = tx.check_database()?.into_iter()
+            .filter(|problem| problem.fixable)
+            .map(|problem| problem.description)
+            .collect();
+        if !remaining.is_empty() {
+            return Err(format_err!("Repairing the database failed, no changes were made: {}", remaining.join(", ")));
+        }
+        tx.finish()?;
+
+        Ok(CommandOutput::Doctor { problems, repaired: true })
+    }
+}
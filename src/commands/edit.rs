@@ -0,0 +1,59 @@
+use failure::Error;
+
+use crate::db::{DBBackend, RemovalSource};
+use crate::task::Task;
+
+use super::{CommandOutput, find_by_id};
+use super::add::{is_str_nonempty, is_u32_nonzero};
+
+#[derive(StructOpt, Debug)]
+pub struct Edit {
+    /// The UUID of the task, or enough of the start of it to tell it apart from the other tasks.
+    pub id: String,
+
+    #[structopt(long = "task", parse(try_from_str = "is_str_nonempty"))]
+    /// The new description.
+    pub task: Option<String>,
+
+    #[structopt(long = "priority", parse(try_from_str = "is_u32_nonzero"))]
+    /// The new priority.
+    pub priority: Option<u32>,
+}
+
+#[derive(StructOpt, Debug)]
+pub struct Remove {
+    /// The UUID of the task, or enough of the start of it to tell it apart from the other tasks.
+    pub id: String,
+}
+
+/// The task on the task list whose UUID starts with `id`.
+fn find_task(tx: &impl DBBackend, id: &str) -> Result<Task, Error> {
+    let tasks = tx.fetch_all_tasks()
+        .map_err(|e| format_err!("Could not get tasks from database. {}", e))?;
+    find_by_id(&tasks, Task::uuid, id, "on the task list").cloned()
+}
+
+impl Edit {
+    pub fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
+        let task = find_task(tx, &self.id)?;
+
+        let description = self.task.clone().unwrap_or_else(|| task.task().to_string());
+        let priority = self.priority.unwrap_or_else(|| task.priority());
+        let edited = Task::from_parts(description, priority, task.is_break(), *task.uuid())?;
+        tx.update_task(&edited)
+            .map_err(|e| format_err!("Could not update task. {}", e))?;
+
+        Ok(CommandOutput::Edited(edited))
+    }
+}
+
+impl Remove {
+    pub fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
+        let task = find_task(tx, &self.id)?;
+
+        tx.remove_task_by_uuid(task.uuid(), &RemovalSource::Local)
+            .map_err(|e| format_err!("Could not remove task. {}", e))?;
+
+        Ok(CommandOutput::Removed(task))
+    }
+}
@@ -0,0 +1,50 @@
+use std::fs;
+use std::path::PathBuf;
+
+use failure::Error;
+
+use crate::db::DBBackend;
+use crate::interchange::{DatabaseExport, FileFormat};
+
+use super::{Subcommand, CommandOutput};
+
+#[derive(StructOpt, Debug)]
+pub struct Export {
+    #[structopt(long = "as")]
+    /// The file format: "json", "todotxt", "markdown", or "ical". Defaults to todotxt for
+    /// .txt files, markdown for .md files, ical for .ics files, and json otherwise. Only JSON
+    /// includes the current task and sync state.
+    pub file_format: Option<FileFormat>,
+    #[structopt(long = "ical", conflicts_with = "file_format")]
+    /// Use iCalendar, with a VTODO for each task. This is the same as `--as ical`.
+    pub ical: bool,
+    #[structopt(parse(from_os_str))]
+    /// The file to write to. If it is not given or is "-", the export is written to stdout.
+    pub file: Option<PathBuf>,
+}
+
+impl Export {
+    fn format(&self) -> FileFormat {
+        if self.ical {
+            return FileFormat::ICal;
+        }
+        self.file_format
+            .unwrap_or_else(|| self.file.as_ref().map_or(FileFormat::Json, |path| FileFormat::from_path(path)))
+    }
+}
+
+impl Subcommand for Export {
+    fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
+        let export = DatabaseExport::from_db(tx)?;
+        let format = self.format();
+
+        match &self.file {
+            Some(path) if path.to_str() != Some("-") => {
+                fs::write(path, format.write(&export)?)
+                    .map_err(|e| format_err!("Could not write export to {}: {}", path.display(), e))?;
+                Ok(CommandOutput::Message(format!("Exported {} tasks to {}.", export.tasks.len(), path.display())))
+            }
+            _ => Ok(CommandOutput::Export { format, export }),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use failure::Error;
+
+use crate::db::DBBackend;
+use crate::interchange::FileFormat;
+use crate::interchange::taskwarrior::{self, TaskwarriorWeight};
+
+use super::{Subcommand, CommandOutput, read_input};
+
+#[derive(StructOpt, Debug)]
+pub struct Import {
+    #[structopt(long = "as")]
+    /// The file format: "json", "todotxt", "markdown", or "ical". Defaults to todotxt for
+    /// .txt files, markdown for .md files, ical for .ics files, and json otherwise.
+    pub file_format: Option<FileFormat>,
+    #[structopt(long = "ical", conflicts_with = "file_format")]
+    /// Use iCalendar, with a VTODO for each task. This is the same as `--as ical`.
+    pub ical: bool,
+    #[structopt(long = "taskwarrior", raw(conflicts_with_all = r#"&["file_format", "ical"]"#))]
+    /// Read the output of Taskwarrior's `task export`. Tasks keep their Taskwarrior UUIDs, tasks
+    /// tagged "break" are breaks, and completed and deleted tasks are skipped.
+    pub taskwarrior: bool,
+    #[structopt(long = "weight-from", requires = "taskwarrior",
+                raw(possible_values = r#"&["priority", "urgency"]"#))]
+    /// The Taskwarrior field used for the priority: "priority" (the default), where no priority
+    /// is 1, L is 2, M is 3 and H is 4, or "urgency", rounded. Either is multiplied by `--scale`,
+    /// and the result is at least 1.
+    pub weight_from: Option<TaskwarriorWeight>,
+    #[structopt(long = "scale", requires = "taskwarrior", parse(try_from_str = "parse_scale"))]
+    /// The number the Taskwarrior priority or urgency is multiplied by. Defaults to 1.
+    pub scale: Option<f64>,
+    #[structopt(parse(from_os_str))]
+    /// The file written by `export`. If it is not given or is "-", it is read from stdin.
+    pub file: Option<PathBuf>,
+    #[structopt(long = "replace")]
+    /// Remove every task, replica and unsynced operation before importing, instead of merging
+    /// with the tasks already in the database.
+    pub replace: bool,
+    #[structopt(long = "dry-run", short = "n")]
+    /// Show what would be imported without changing the database.
+    pub dry_run: bool,
+}
+
+/// Parse the multiplier for Taskwarrior priorities, which must be greater than 0.
+fn parse_scale(arg: &str) -> Result<f64, Error> {
+    let scale: f64 = arg.parse().map_err(|e| format_err!("Unable to parse scale \"{}\": {}", arg, e))?;
+    if !(scale > 0.0 && scale.is_finite()) {
+        return Err(format_err!("Scale must be greater than 0."));
+    }
+
+    Ok(scale)
+}
+
+impl Import {
+    fn format(&self) -> FileFormat {
+        if self.ical {
+            return FileFormat::ICal;
+        }
+        self.file_format
+            .unwrap_or_else(|| self.file.as_ref().map_or(FileFormat::Json, |path| FileFormat::from_path(path)))
+    }
+}
+
+impl Subcommand for Import {
+    fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
+        let input = read_input(self.file.as_ref(), "tasks")?;
+        let (export, skipped) = if self.taskwarrior {
+            taskwarrior::read(&input, self.weight_from.unwrap_or(TaskwarriorWeight::Priority), self.scale.unwrap_or(1.0))?
+        }
+        else {
+            (self.format().read(&input)?, Vec::new())
+        };
+
+        let summary = export.import(tx, self.replace)?;
+        Ok(CommandOutput::Imported { summary, tasks: export.tasks, skipped, dry_run: self.dry_run })
+    }
+}
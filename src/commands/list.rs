@@ -1,26 +1,38 @@
 use failure::Error;
 
 use crate::db::DBBackend;
+use crate::selection::SelectionStrategy;
 //use crate::task::Task;
 
-use super::Subcommand;
+use super::CommandOutput;
 
-#[derive(Debug)]
-pub struct List;
+#[derive(StructOpt, Debug)]
+pub struct List {
+    #[structopt(long = "effective")]
+    /// Also display the weight each task is currently given by the selection strategy, which may
+    /// differ from its priority (e.g. when tasks age).
+    pub effective: bool,
+}
 
-impl Subcommand for List {
-    fn run(&self, tx: &impl DBBackend) -> Result<Vec<String>, Error> {
+impl List {
+    pub fn run(&self, tx: &impl DBBackend, selector: &mut dyn SelectionStrategy) -> Result<CommandOutput, Error> {
 
         let tasks = tx.fetch_all_tasks()
             .map_err(|e| format_err!("Could not get tasks from database. {}", e))?;
+        let current = tx.fetch_current_task()
+            .map_err(|e| format_err!("Could not get current task from database. {}", e))?
+            .map(|task| *task.uuid());
+
+        if !self.effective {
+            return Ok(CommandOutput::TaskList { tasks, effective_weights: None, current });
+        }
+
+        let context = tx.fetch_selection_context()
+            .map_err(|e| format_err!("Could not get selection context from database. {}", e))?;
+        selector.set_context(&context);
 
-        let mut output = vec!["Priority \t Task".to_string(),];
-        output.extend(
-            tasks.iter().map(|task| {
-                task.format_row(4)
-            })
-        );
+        let effective_weights = tasks.iter().map(|task| selector.effective_weight(task)).collect();
 
-        Ok(output)
+        Ok(CommandOutput::TaskList { tasks, effective_weights: Some(effective_weights), current })
     }
 }
@@ -1,11 +1,19 @@
-use failure::{Error, err_msg};
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use failure::Error;
 
 use structopt::StructOpt;
+use uuid::Uuid;
 
-use crate::db::DBBackend;
+use crate::db::{DBBackend, SqliteTransaction};
 use crate::config::Config;
-use crate::selection::WeightedRandom;
+use crate::hooks::{Hooks, HookEvent, TaskSnapshot};
+use crate::journal::{self, JournalSnapshot};
+use crate::render::{ColorChoice, OutputFormat};
 use crate::selection::SelectionStrategy;
+use crate::task::Task;
 
 /// Default command when none is given: display the current selected task.
 const DEFAULT_COMMAND: TKZCmd = TKZCmd::Current(Current{top: false});
@@ -13,7 +21,7 @@ const DEFAULT_COMMAND: TKZCmd = TKZCmd::Current(Current{top: false});
 // subcommand trait
 
 trait Subcommand {
-    fn run(&self, tx: &impl DBBackend) -> Result<Vec<String>, Error>;
+    fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error>;
 }
 
 #[derive(StructOpt, Debug)]
@@ -28,6 +36,15 @@ pub struct TKZArgs {
     /// If no command is given, defaults to current - show the current task
     #[structopt(subcommand)]
     pub cmd: Option<TKZCmd>,
+
+    /// How to display the output: "plain", "color", "json", or "tsv".
+    #[structopt(long = "format", default_value = "plain", raw(global = "true"))]
+    pub format: OutputFormat,
+
+    /// When to use colors in plain output: "never", "always", or "auto" to use them when writing to
+    /// a terminal. Setting the NO_COLOR environment variable turns off "auto" colors.
+    #[structopt(long = "color", default_value = "auto", raw(global = "true"))]
+    pub color: ColorChoice,
 }
 
 impl TKZArgs {
@@ -58,7 +75,7 @@ pub enum TKZCmd {
 
     #[structopt(name = "list")]
     /// List all tasks.
-    List,
+    List(List),
 
     #[structopt(name = "current")]
     /// Display the current task.
@@ -66,55 +83,334 @@ pub enum TKZCmd {
 
     #[structopt(name = "complete")]
     /// Mark the current task as completed.
-    Complete,
+    Complete(Complete),
 
     #[structopt(name = "skip")]
     /// Skip the current task and choose a new one. If there is only one task in the database, it
     /// will be chosen again.
     Skip,
+
+    #[structopt(name = "simulate")]
+    /// Simulate selecting tasks many times and display how often each task and category would be
+    /// chosen. The database is not modified.
+    Simulate(Simulate),
+
+    #[structopt(name = "why")]
+    /// Explain how the next task will be selected: display the probability of choosing a break and
+    /// the probability of choosing each task.
+    Why,
+
+    #[structopt(name = "timer")]
+    /// Start a timer for the current task and wait for it to end, then run the notification
+    /// command from the config file.
+    Timer(Timer),
+
+    #[structopt(name = "pause")]
+    /// Stop tracking time spent on the current task. Time tracking resumes when a new task is
+    /// selected, or with `resume`.
+    Pause,
+
+    #[structopt(name = "resume")]
+    /// Resume tracking time spent on the current task after `pause`.
+    Resume,
+
+    #[structopt(name = "report")]
+    /// Display the total time spent on each task and each category.
+    Report(Report),
+
+    #[structopt(name = "tui")]
+    /// Open a full-screen terminal UI showing the current task and the task list.
+    Tui,
+
+    #[structopt(name = "shell")]
+    /// Start an interactive shell that runs commands one per line, each in its own transaction.
+    Shell,
+
+    #[structopt(name = "batch")]
+    /// Run commands from a file or stdin, one per line, all in a single transaction.
+    Batch(Batch),
+
+    #[structopt(name = "q")]
+    /// Quickly add a task written with inline markers for its priority, category, tags, due date
+    /// and estimate, e.g. "call dentist !5 #break +phone due:fri est:10m".
+    Quick(Quick),
+
+    #[structopt(name = "export")]
+    /// Write all tasks, the current task, and sync state to a JSON file, for backups or moving to
+    /// another machine.
+    Export(Export),
+
+    #[structopt(name = "import")]
+    /// Read a file written by `export`, merging its tasks with the ones in the database.
+    Import(Import),
+
+    #[structopt(name = "completions")]
+    /// Print a completion script for bash, zsh or fish, e.g. `tkzr completions bash >
+    /// /etc/bash_completion.d/tkzr`.
+    Completions(Completions),
+
+    #[structopt(name = "backup")]
+    /// Copy the database to a file. This is safe to run while other commands are using the
+    /// database.
+    Backup(Backup),
+
+    #[structopt(name = "restore")]
+    /// Replace the database with a file written by `backup`, after checking that it is a valid
+    /// taskerizer database.
+    Restore(Restore),
+
+    #[structopt(name = "doctor")]
+    /// Check the database for corruption and for data that the other commands can't read, and
+    /// repair what can be repaired with `--fix`.
+    Doctor(Doctor),
+
+    #[structopt(name = "undo")]
+    /// Undo the last command that added, completed, skipped or changed tasks, or the last `n`
+    /// commands, restoring the previous current task.
+    Undo(Undo),
+
+    #[structopt(name = "trash")]
+    /// List, restore or permanently remove tasks that were completed or removed, including tasks
+    /// removed by sync.
+    Trash(Trash),
+
+    #[structopt(name = "edit")]
+    /// Change the description or priority of a task on the task list, keeping its tags, due date
+    /// and estimate.
+    Edit(Edit),
+
+    #[structopt(name = "remove")]
+    /// Remove a task from the task list without completing it, moving it to the trash.
+    Remove(Remove),
+
+    #[structopt(name = "search")]
+    /// Search the descriptions of tasks, including completed and removed tasks in the trash, and
+    /// list the results by relevance. A result can be picked to make it current, remove it or edit
+    /// it.
+    Search(Search),
 }
 
 impl TKZCmd {
-    pub fn dispatch(&self, config: &Config) -> Result<Vec<String>, Error> {
+    /// The names of all subcommands, used for completion.
+    pub const NAMES: &'static [&'static str] = &[
+        "add", "break", "list", "current", "complete", "skip", "simulate", "why", "timer", "pause",
+        "resume", "report", "tui", "shell", "batch", "q",
+        "export", "import", "completions", "backup", "restore", "doctor", "undo", "trash", "edit",
+        "remove", "search",
+    ];
+
+    /// The subcommands, as the words that name them, whose positional argument is a task UUID,
+    /// which shell completions complete from the database.
+    pub const TASK_ID_COMMANDS: &'static [&'static [&'static str]] = &[&["edit"], &["remove"], &["trash", "restore"]];
+
+    pub fn dispatch(&self, config: &Config) -> Result<CommandOutput, Error> {
+        self.dispatch_with_progress(config, &mut |_| ())
+    }
+
+    /// Like `dispatch`, but output that is ready before the command finishes, like a timer
+    /// starting, is passed to `progress` as soon as it is.
+    pub fn dispatch_with_progress(&self, config: &Config, progress: &mut dyn FnMut(&CommandOutput))
+        -> Result<CommandOutput, Error> {
+        match self {
+            TKZCmd::Timer(timer) => return timer.dispatch(config, progress),
+            TKZCmd::Tui => {let t = Tui; return t.dispatch(config)},
+            TKZCmd::Shell => {let s = Shell; return s.dispatch(config)},
+            TKZCmd::Batch(batch) => return batch.dispatch(config),
+            TKZCmd::Completions(completions) => return completions.dispatch(),
+            TKZCmd::Backup(backup) => return backup.dispatch(config),
+            TKZCmd::Restore(restore) => return restore.dispatch(config),
+            TKZCmd::Doctor(doctor) => return doctor.dispatch(config),
+            // the task is parsed once, so the task that is added is the one that was confirmed
+            TKZCmd::Quick(quick) => {
+                let (task, details) = quick.parse()?;
+                if !quick.confirm(&task, &details)? {
+                    return Ok(CommandOutput::Message("Task not added.".to_string()));
+                }
+                return self.dispatch_in_transaction(config, |tx, selector| {
+                    let output = quick.add(tx, task, details);
+                    select_if_no_current_task(tx, selector)?;
+                    output
+                });
+            }
+            _ => {}
+        }
+
+        self.dispatch_in_transaction(config, |tx, selector| self.run(tx, selector))
+    }
+
+    /// Open a transaction and run the command in it with `run`, along with the journal and hooks.
+    fn dispatch_in_transaction(&self, config: &Config,
+                               run: impl FnOnce(&SqliteTransaction, &mut dyn SelectionStrategy) -> Result<CommandOutput, Error>)
+        -> Result<CommandOutput, Error> {
         let mut db = config.db()?;
         let tx = db.transaction()?;
-        let break_cutoff = config.break_cutoff;
-        let mut selector = WeightedRandom::new(break_cutoff);
+        let mut selector = config.selector();
+        let hooks = config.hooks();
         // get other stuff from config, etc...
 
-        let res = self.run(&tx, &mut selector);
-        if res.is_ok() {
+        // a failing hook returns early, so the transaction is rolled back
+        let output = self.run_with_hooks_using(&tx, &mut *selector, hooks.as_ref(), run)?;
+
+        // read-only commands are rolled back when the transaction is dropped
+        if !self.is_read_only() {
             tx.finish()?;
         }
 
-        res
+        Ok(output)
     }
 
-    fn run(&self, tx: &impl DBBackend, selector: &mut dyn SelectionStrategy) -> Result<Vec<String>, Error> {
+    /// Whether the command opens its own transactions, or doesn't use the database, instead of
+    /// running in a transaction opened by `dispatch`. These commands can't be run inside another
+    /// transaction, e.g. in a batch.
+    fn manages_transactions(&self) -> bool {
+        matches!(self, TKZCmd::Timer(_) | TKZCmd::Tui | TKZCmd::Shell | TKZCmd::Batch(_) | TKZCmd::Completions(_)
+                 | TKZCmd::Backup(_) | TKZCmd::Restore(_) | TKZCmd::Doctor(_))
+    }
+
+    /// Run the command in an existing transaction, record the changes it made in the journal, then
+    /// run hooks for them.
+    fn run_with_hooks(&self, tx: &impl DBBackend, selector: &mut dyn SelectionStrategy, hooks: Option<&Hooks>)
+        -> Result<CommandOutput, Error> {
+        self.run_with_hooks_using(tx, selector, hooks, |tx, selector| self.run(tx, selector))
+    }
+
+    /// Like `run_with_hooks`, but the command is run by calling `run`, e.g. with arguments that
+    /// were already parsed.
+    fn run_with_hooks_using<D: DBBackend>(&self, tx: &D, selector: &mut dyn SelectionStrategy, hooks: Option<&Hooks>,
+                                          run: impl FnOnce(&D, &mut dyn SelectionStrategy) -> Result<CommandOutput, Error>)
+        -> Result<CommandOutput, Error> {
+        let before = hooks.map(|_| TaskSnapshot::capture(tx)).transpose()?;
+        let journal_name = self.journal_name().filter(|_| !self.is_read_only());
+        let journal_before = journal_name.map(|_| JournalSnapshot::capture(tx)).transpose()?;
+
+        let mut output = run(tx, selector)?;
+
+        if let (Some(name), Some(journal_before)) = (journal_name, journal_before) {
+            journal::record(tx, name, &journal_before)?;
+        }
+
+        if let (Some(hooks), Some(before)) = (hooks, before) {
+            let after = TaskSnapshot::capture(tx)?;
+            let events = self.hook_events(&output, &before, &after);
+            output = output.with_hook_output(hooks.run_all(&events)?);
+        }
+
+        Ok(output)
+    }
+
+    /// The hook events for what the command did, in the order add, complete, skip, select. Only
+    /// commands that add, complete or skip tasks report those events, so e.g. removing a task
+    /// doesn't count as completing it. Every skip selects a task, even if it selects the task that
+    /// was skipped again; otherwise a task was selected if the current task changed.
+    fn hook_events(&self, output: &CommandOutput, before: &TaskSnapshot, after: &TaskSnapshot) -> Vec<(HookEvent, Task)> {
+        let mut events: Vec<(HookEvent, Task)> = match (self, output) {
+            (_, CommandOutput::Added(task)) | (_, CommandOutput::Restored(task))
+                | (_, CommandOutput::QuickAdded { task, added: true, .. }) => vec![(HookEvent::Add, task.clone())],
+            (TKZCmd::Import(_), CommandOutput::Imported { summary, .. }) => summary.added.iter()
+                .map(|task| (HookEvent::Add, task.clone()))
+                .collect(),
+            (TKZCmd::Complete(_), CommandOutput::Completed(task)) => vec![(HookEvent::Complete, task.clone())],
+            (TKZCmd::Skip, CommandOutput::Skipped(_)) | (TKZCmd::Break(_), CommandOutput::Skipped(_)) => before.current.iter()
+                .map(|task| (HookEvent::Skip, task.clone()))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let selected = match self {
+            TKZCmd::Skip | TKZCmd::Break(_) => after.current.clone(),
+            _ => before.selected(after),
+        };
+        events.extend(selected.map(|task| (HookEvent::Select, task)));
+
+        events
+    }
+
+    /// The name the command is recorded under in the journal, or `None` if it isn't recorded and so
+    /// can't be undone.
+    fn journal_name(&self) -> Option<&'static str> {
+        match self {
+            TKZCmd::Add(_) => Some("add"),
+            TKZCmd::Quick(_) => Some("q"),
+            TKZCmd::Complete(_) => Some("complete"),
+            TKZCmd::Skip => Some("skip"),
+            TKZCmd::Break(_) => Some("break"),
+            TKZCmd::Import(_) => Some("import"),
+            TKZCmd::Trash(Trash { cmd: TrashCmd::Restore { .. } }) => Some("trash restore"),
+            TKZCmd::Edit(_) => Some("edit"),
+            TKZCmd::Remove(_) => Some("remove"),
+            TKZCmd::Search(search) if search.changes_task() => Some("search"),
+            _ => None,
+        }
+    }
+
+    /// Whether the command should leave the database unchanged. The transaction for a read-only
+    /// command is always rolled back instead of committed.
+    fn is_read_only(&self) -> bool {
+        match self {
+            TKZCmd::Simulate(_) | TKZCmd::Export(_) => true,
+            TKZCmd::Quick(quick) => quick.dry_run,
+            TKZCmd::Import(import) => import.dry_run,
+            TKZCmd::Search(search) => !search.changes_task(),
+            _ => false,
+        }
+    }
+
+    fn run(&self, tx: &impl DBBackend, selector: &mut dyn SelectionStrategy) -> Result<CommandOutput, Error> {
         let output = match self {
             TKZCmd::Add(add) => add.run(tx),
-            TKZCmd::List => {let l = List; l.run(tx)},
+            TKZCmd::Quick(quick) => quick.run(tx),
+            TKZCmd::Export(export) => export.run(tx),
+            TKZCmd::Import(import) => import.run(tx),
+            TKZCmd::List(list) => list.run(tx, selector),
             TKZCmd::Current(current) => current.run(tx),
-            TKZCmd::Complete => {let c = Complete; c.run(tx)},
+            TKZCmd::Complete(complete) => complete.run(tx),
             TKZCmd::Skip => {let s = Skip; s.run(tx, selector)},
-            _ => unimplemented!(),
+            TKZCmd::Break(take_break) => take_break.run(tx, selector),
+            TKZCmd::Simulate(simulate) => simulate.run(tx, selector),
+            TKZCmd::Why => {let w = Why; w.run(tx, selector)},
+            TKZCmd::Pause => {let p = Pause; p.run(tx)},
+            TKZCmd::Resume => {let r = Resume; r.run(tx)},
+            TKZCmd::Report(report) => report.run(tx),
+            TKZCmd::Undo(undo) => undo.run(tx, selector),
+            TKZCmd::Trash(trash) => trash.run(tx),
+            TKZCmd::Search(search) => search.run(tx),
+            TKZCmd::Edit(edit) => edit.run(tx),
+            TKZCmd::Remove(remove) => remove.run(tx),
+            cmd @ TKZCmd::Timer(_) | cmd @ TKZCmd::Tui | cmd @ TKZCmd::Shell | cmd @ TKZCmd::Batch(_)
+                | cmd @ TKZCmd::Completions(_) | cmd @ TKZCmd::Backup(_) | cmd @ TKZCmd::Restore(_)
+                | cmd @ TKZCmd::Doctor(_) => unreachable!("{:?} is dispatched separately", cmd),
         };
 
-        let current_task = tx.fetch_current_task()
-            .map_err(|err| format_err!("Error getting current task while choosing new task after executing command: {}", err))?;
-        // if there is no current task, choose a new one.
-        if current_task.is_none() {
-            tx.select_current_task(selector)?;
-        }
-
+        select_if_no_current_task(tx, selector)?;
         output
     }
 }
 
+/// Choose a new current task after running a command, if there is no current task.
+fn select_if_no_current_task(tx: &impl DBBackend, selector: &mut dyn SelectionStrategy) -> Result<(), Error> {
+    let current_task = tx.fetch_current_task()
+        .map_err(|err| format_err!("Error getting current task while choosing new task after executing command: {}", err))?;
+    if current_task.is_none() {
+        tx.select_current_task(selector)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 pub mod test_utils;
 #[cfg(test)]
 mod test_dispatch;
+#[cfg(test)]
+mod test_quick;
+#[cfg(test)]
+mod test_completions;
+#[cfg(test)]
+mod test_search;
+#[cfg(test)]
+mod test_trash;
+
+mod output;
+pub use self::output::{CommandOutput, Simulation};
 
 // --- subcommand parameter structs
 
@@ -133,22 +429,96 @@ pub use self::complete::Complete;
 mod skip;
 pub use self::skip::Skip;
 
-#[derive(StructOpt, Debug)]
-pub struct Break {
-    /// The probability as a decimal to select a task from break. Must be less than 1.0.
-    #[structopt(short = "p", long = "probability", parse(try_from_str = "parse_prob"))]
-    pub p: Option<f32>,
-}
+mod take_break;
+pub use self::take_break::Break;
+
+mod simulate;
+pub use self::simulate::Simulate;
 
-/// Parse a float representing a probability from a string for the change-prob command
-fn parse_prob(arg: &str) -> Result<f32, Error> {
-    let p: f32 = arg.parse().map_err(|e| format_err!("unable to parse \"{}\": {}", arg, e))?;
-    if p >= 1.0 {
-        return Err(err_msg("probability must be less than 1"));
+mod why;
+pub use self::why::Why;
+
+mod timer;
+pub use self::timer::Timer;
+
+mod pause;
+pub use self::pause::Pause;
+
+mod resume;
+pub use self::resume::Resume;
+
+mod report;
+pub use self::report::Report;
+
+mod tui;
+pub use self::tui::Tui;
+
+mod shell;
+pub use self::shell::Shell;
+
+mod batch;
+pub use self::batch::Batch;
+
+mod quick;
+pub use self::quick::{Quick, parse_due, parse_quick_add};
+
+mod export;
+pub use self::export::Export;
+
+mod import;
+pub use self::import::Import;
+
+mod completions;
+pub use self::completions::{Completions, COMPLETE_IDS, complete_ids};
+pub(crate) use self::completions::task_id_candidates;
+
+mod backup;
+pub use self::backup::{Backup, Restore};
+
+mod doctor;
+pub use self::doctor::Doctor;
+
+mod undo;
+pub use self::undo::Undo;
+
+mod trash;
+pub use self::trash::{Trash, TrashCmd};
+
+mod search;
+pub use self::search::Search;
+
+mod edit;
+pub use self::edit::{Edit, Remove};
+
+/// Find the item whose UUID starts with `id`, ignoring case. `place` says where the items are, e.g.
+/// "in the trash", for error messages. It is an error if `id` is empty, or if no item or more than
+/// one item matches.
+fn find_by_id<'i, T>(items: &'i [T], uuid: impl Fn(&T) -> &Uuid, id: &str, place: &str) -> Result<&'i T, Error> {
+    if id.trim().is_empty() {
+        return Err(format_err!("The task id can't be empty."));
     }
-    if p < 0.0 {
-        return Err(err_msg("probability must be greater than 0"));
+
+    let prefix = id.to_lowercase();
+    let mut matches = items.iter().filter(|item| uuid(item).to_string().starts_with(&prefix));
+
+    match (matches.next(), matches.count()) {
+        (Some(item), 0) => Ok(item),
+        (None, _) => Err(format_err!("There is no task {} with an id starting with \"{}\".", place, id)),
+        (Some(_), others) => Err(format_err!("{} tasks {} have an id starting with \"{}\".", others + 1, place, id)),
     }
+}
 
-    Ok(p)
+/// Read the whole file at `path`, or stdin if it is `None` or "-". `what` names the contents in
+/// error messages.
+fn read_input(path: Option<&PathBuf>, what: &str) -> Result<String, Error> {
+    match path {
+        Some(path) if path.to_str() != Some("-") => fs::read_to_string(path)
+            .map_err(|e| format_err!("Could not read {} from {}: {}", what, path.display(), e)),
+        _ => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)
+                .map_err(|e| format_err!("Could not read {} from stdin: {}", what, e))?;
+            Ok(input)
+        }
+    }
 }
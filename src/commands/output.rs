@@ -0,0 +1,133 @@
+use chrono::Duration;
+use uuid::Uuid;
+
+use crate::db::{DBProblem, SearchResult, TrashedTask};
+use crate::render::{Renderer, Plain};
+use crate::interchange::{DatabaseExport, FileFormat, ImportSummary};
+use crate::interchange::taskwarrior::SkippedTask;
+use crate::selection::SelectionProbabilities;
+use crate::task::{Task, TaskDetails};
+use crate::tracking::TimeReport;
+
+/// The result of `simulate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    /// The number of selections simulated.
+    pub selections: usize,
+    /// The number of selections that chose a break.
+    pub breaks: usize,
+    /// Each task along with the number of times it was selected.
+    pub counts: Vec<(Task, usize)>,
+    /// If completions were simulated, the tasks in the order they were completed.
+    pub completion_order: Option<Vec<Task>>,
+}
+
+/// The result of running a command. Commands return structured results rather than text, which
+/// are turned into text by a `Renderer`, or used directly as a library.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandOutput {
+    /// There were no tasks for the command to act on.
+    NoTasks,
+    /// A message with no other data.
+    Message(String),
+    /// A task was added.
+    Added(Task),
+    /// A task was parsed by quick add, and added unless it was a dry run.
+    QuickAdded {
+        task: Task,
+        details: TaskDetails,
+        added: bool,
+    },
+    /// All tasks, and optionally the weight the selection strategy gives each of them.
+    TaskList {
+        tasks: Vec<Task>,
+        effective_weights: Option<Vec<f64>>,
+        /// The UUID of the current task, if there is one.
+        current: Option<Uuid>,
+    },
+    /// The current task, and the time remaining on its timer if one is running.
+    Current {
+        task: Task,
+        timer_remaining: Option<Duration>,
+    },
+    /// A task was completed.
+    Completed(Task),
+    /// The current task was skipped, and this is the new current task.
+    Skipped(Task),
+    /// The result of `simulate`.
+    Simulation(Simulation),
+    /// The probability of selecting each task.
+    Probabilities(SelectionProbabilities),
+    /// A timer started. This is passed to the progress callback of `TKZCmd::dispatch_with_progress`
+    /// rather than returned, since the command only returns when the timer ends.
+    TimerStarted { task: Task, length: Duration },
+    /// A timer ended.
+    TimerFinished {
+        /// The task the timer was for.
+        task: Task,
+        /// The new current task, if a break was selected when the timer ended.
+        current: Option<Task>,
+        /// Why the notification command failed, if it did.
+        notify_error: Option<String>,
+    },
+    /// Time tracking was paused on a task.
+    Paused(Task),
+    /// Time tracking was resumed on a task.
+    Resumed(Task),
+    /// Time spent per task and category.
+    Report(TimeReport),
+    /// The contents of the database, written to stdout by `export`.
+    Export { format: FileFormat, export: DatabaseExport },
+    /// What was changed by `import`, the tasks that were imported, and the tasks in the file that
+    /// were not. If `dry_run` is true, the changes were not saved.
+    Imported { summary: ImportSummary, tasks: Vec<Task>, skipped: Vec<SkippedTask>, dry_run: bool },
+    /// The problems found by `doctor`. If `repaired` is true, the fixable ones were fixed.
+    Doctor { problems: Vec<DBProblem>, repaired: bool },
+    /// The commands that `undo` undid, newest first, and the current task afterwards.
+    Undone { commands: Vec<String>, current: Option<Task> },
+    /// The tasks in the trash, most recently removed first.
+    Trash(Vec<TrashedTask>),
+    /// A task was restored from the trash.
+    Restored(Task),
+    /// A task's description or priority was changed. This is the task after the changes.
+    Edited(Task),
+    /// A task was removed and moved to the trash.
+    Removed(Task),
+    /// The trash was emptied, permanently removing this many tasks.
+    TrashEmptied(usize),
+    /// The tasks that matched a search, most relevant first.
+    SearchResults(Vec<SearchResult>),
+    /// A task picked from search results was made current ("current"), removed ("removed") or
+    /// edited ("edited"). If it was edited, this is the task after the changes.
+    Picked { task: Task, action: &'static str },
+    /// A command in a batch failed and was skipped.
+    Failed {
+        /// The line number of the command, starting from 1.
+        line: usize,
+        /// The command as it was written.
+        command: String,
+        error: String,
+    },
+    /// Lines printed by hooks.
+    HookOutput(Vec<String>),
+    /// Several outputs one after another.
+    Sequence(Vec<CommandOutput>),
+}
+
+impl CommandOutput {
+    /// Follow this output with the output of hooks, if there was any.
+    pub fn with_hook_output(self, hook_output: Vec<String>) -> CommandOutput {
+        if hook_output.is_empty() {
+            self
+        }
+        else {
+            CommandOutput::Sequence(vec![self, CommandOutput::HookOutput(hook_output)])
+        }
+    }
+
+    /// Render the output as lines of text meant to be read by people. This is the same as rendering
+    /// with `Plain`.
+    pub fn lines(&self) -> Vec<String> {
+        Plain.render(self)
+    }
+}
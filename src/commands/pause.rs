@@ -0,0 +1,29 @@
+use failure::Error;
+
+use crate::db::DBBackend;
+use crate::commands::{Subcommand, CommandOutput};
+
+#[derive(Debug)]
+pub struct Pause;
+
+impl Subcommand for Pause {
+    fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
+        let current = tx.fetch_current_task()
+            .map_err(|e| format_err!("Could not get current task from database. {}", e))?;
+
+        let current = match current {
+            Some(current) => current,
+            None => return Ok(CommandOutput::NoTasks),
+        };
+
+        let paused = tx.pause_session()
+            .map_err(|e| format_err!("Could not pause time tracking. {}", e))?;
+
+        if paused {
+            Ok(CommandOutput::Paused(current))
+        }
+        else {
+            Ok(CommandOutput::Message("Time tracking is already paused.".to_string()))
+        }
+    }
+}
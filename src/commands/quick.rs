@@ -0,0 +1,180 @@
+use std::io::{self, Write};
+
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use failure::Error;
+
+use crate::db::DBBackend;
+use crate::render::quick_add_summary;
+use crate::task::{Task, TaskDetails};
+use crate::timer::parse_duration;
+
+use super::add::{is_str_nonempty, is_u32_nonzero};
+use super::{Subcommand, CommandOutput};
+
+/// The most days from today that a relative due date like "3d" can be.
+const MAX_DUE_DAYS: i64 = 100_000_000;
+
+/// Parse a due date relative to `today`: "today", "tomorrow", a weekday (the next one, or today if
+/// it is that day), a number of days or weeks from today like "3d" or "2w", or a date like
+/// "2019-06-30".
+pub fn parse_due(arg: &str, today: NaiveDate) -> Result<NaiveDate, Error> {
+    let lower = arg.to_lowercase();
+
+    let weekday = match lower.as_str() {
+        "today" => return Ok(today),
+        "tomorrow" | "tom" => return Ok(today.succ()),
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    };
+    let error = || format_err!("Unable to parse due date \"{}\": expected today, tomorrow, a weekday, a number of days or weeks like 3d or 2w, or a date like 2019-06-30.", arg);
+    if let Some(weekday) = weekday {
+        let days = (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+        return today.checked_add_signed(Duration::days(i64::from(days))).ok_or_else(error);
+    }
+
+    let relative = match lower.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) if idx > 0 => match lower.split_at(idx) {
+            (n, "d") => Some(n.parse::<i64>().ok()),
+            (n, "w") => Some(n.parse::<i64>().ok().and_then(|n| n.checked_mul(7))),
+            _ => None,
+        },
+        _ => None,
+    };
+    if let Some(days) = relative {
+        // larger offsets overflow `Duration`, and are past the last date chrono supports anyway
+        return days.filter(|&days| days <= MAX_DUE_DAYS)
+            .and_then(|days| today.checked_add_signed(Duration::days(days)))
+            .ok_or_else(error);
+    }
+
+    NaiveDate::parse_from_str(arg, "%Y-%m-%d").map_err(|_| error())
+}
+
+/// Parse a task written with inline markers, e.g. `call dentist !5 #break +phone due:fri est:10m`:
+///
+/// - `!N` sets the priority,
+/// - `#break` or `#task` sets the category,
+/// - `+tag` adds a tag,
+/// - `due:DATE` sets the due date (see `parse_due`),
+/// - `est:DURATION` sets the estimate, e.g. "10m" or "1h".
+///
+/// Every other word is part of the description. A word starting with a backslash is always part
+/// of the description, without the backslash, e.g. `\#1` for "#1".
+pub fn parse_quick_add(text: &str, today: NaiveDate) -> Result<(Task, TaskDetails), Error> {
+    let mut words = Vec::new();
+    let mut priority = None;
+    let mut reward = None;
+    let mut details = TaskDetails::default();
+
+    for word in text.split_whitespace() {
+        if let Some(literal) = word.strip_prefix('\\') {
+            words.push(literal);
+        }
+        else if let Some(p) = word.strip_prefix('!').filter(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit())) {
+            if priority.is_some() {
+                return Err(format_err!("Priority was given more than once."));
+            }
+            priority = Some(is_u32_nonzero(p)?);
+        }
+        else if word.eq_ignore_ascii_case("#break") || word.eq_ignore_ascii_case("#task") {
+            if reward.is_some() {
+                return Err(format_err!("Category was given more than once."));
+            }
+            reward = Some(word.eq_ignore_ascii_case("#break"));
+        }
+        else if let Some(tag) = word.strip_prefix('+').filter(|tag| !tag.is_empty()) {
+            details.add_tag(tag);
+        }
+        else if let Some(due) = word.strip_prefix("due:").filter(|due| !due.is_empty()) {
+            if details.due.is_some() {
+                return Err(format_err!("Due date was given more than once."));
+            }
+            details.due = Some(parse_due(due, today)?);
+        }
+        else if let Some(estimate) = word.strip_prefix("est:").filter(|estimate| !estimate.is_empty()) {
+            if details.estimate.is_some() {
+                return Err(format_err!("Estimate was given more than once."));
+            }
+            details.estimate = Some(parse_duration(estimate)?);
+        }
+        else {
+            words.push(word);
+        }
+    }
+
+    let description = is_str_nonempty(&words.join(" "))?;
+    let task = Task::new_from_parts(description, priority.unwrap_or(1), reward.unwrap_or(false))
+        .map_err(|e| format_err!("Task input was invalid: {}", e))?;
+
+    Ok((task, details))
+}
+
+#[derive(StructOpt, Debug)]
+pub struct Quick {
+    #[structopt(required = true)]
+    /// The task with inline markers, e.g. "call dentist !5 #break +phone due:fri est:10m". `!N` sets
+    /// the priority, `#break` or `#task` the category, `+tag` adds a tag, `due:` takes today,
+    /// tomorrow, a weekday, 3d, 2w or 2019-06-30, and `est:` takes a duration like 10m or 1h.
+    pub text: Vec<String>,
+    #[structopt(long = "dry-run", short = "n")]
+    /// Show how the task would be added without adding it.
+    pub dry_run: bool,
+    #[structopt(long = "yes", short = "y")]
+    /// Add the task without asking for confirmation.
+    pub yes: bool,
+}
+
+impl Quick {
+    /// Parse the task. Each call makes a task with a new UUID, so the task should only be parsed
+    /// once and passed to `confirm` and `add`.
+    pub fn parse(&self) -> Result<(Task, TaskDetails), Error> {
+        parse_quick_add(&self.text.join(" "), Local::today().naive_local())
+    }
+
+    /// Show the parsed task and ask whether to add it. There is only a prompt when stdin and
+    /// stdout are terminals and neither `--yes` nor `--dry-run` was given; otherwise this returns
+    /// true.
+    pub fn confirm(&self, task: &Task, details: &TaskDetails) -> Result<bool, Error> {
+        if self.yes || self.dry_run || !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stdout) {
+            return Ok(true);
+        }
+
+        print!("Add task \"{}\" ({})? [Y/n] ", task.task(), quick_add_summary(task, details));
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)
+            .map_err(|e| format_err!("Could not read confirmation: {}", e))?;
+
+        Ok(matches!(answer.trim().to_lowercase().as_str(), "" | "y" | "yes"))
+    }
+}
+
+impl Quick {
+    /// Add a task returned by `parse`, unless this is a dry run.
+    pub fn add(&self, tx: &impl DBBackend, task: Task, details: TaskDetails) -> Result<CommandOutput, Error> {
+        if !self.dry_run {
+            tx.add_task(&task)
+                .map_err(|e| format_err!("Could not add task to database. {}", e))?;
+            if !details.is_empty() {
+                tx.set_task_details(task.uuid(), &details)
+                    .map_err(|e| format_err!("Could not add task details to database. {}", e))?;
+            }
+        }
+
+        Ok(CommandOutput::QuickAdded { task, details, added: !self.dry_run })
+    }
+}
+
+impl Subcommand for Quick {
+    fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
+        let (task, details) = self.parse()?;
+        self.add(tx, task, details)
+    }
+}
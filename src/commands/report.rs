@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use failure::Error;
+
+use crate::db::DBBackend;
+use crate::tracking::TimeReport;
+
+use super::{Subcommand, CommandOutput};
+
+/// Parse a date in the form YYYY-MM-DD from a command line argument
+fn parse_date(arg: &str) -> Result<NaiveDate, Error> {
+    NaiveDate::parse_from_str(arg, "%Y-%m-%d")
+        .map_err(|e| format_err!("Unable to parse date \"{}\", expected YYYY-MM-DD: {}", arg, e))
+}
+
+#[derive(StructOpt, Debug)]
+pub struct Report {
+    #[structopt(long = "from", parse(try_from_str = "parse_date"))]
+    /// Only count time on or after this date, in the form YYYY-MM-DD. Dates are in UTC.
+    pub from: Option<NaiveDate>,
+    #[structopt(long = "to", parse(try_from_str = "parse_date"))]
+    /// Only count time on or before this date, in the form YYYY-MM-DD. Dates are in UTC.
+    pub to: Option<NaiveDate>,
+}
+
+impl Report {
+    /// The start and end of the report's range. The end date is included in the range.
+    fn range(&self, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let from = self.from.map_or_else(|| Utc.timestamp(0, 0), |date| Utc.from_utc_date(&date).and_hms(0, 0, 0));
+        let to = self.to.map_or(now, |date| Utc.from_utc_date(&date).and_hms(0, 0, 0) + Duration::days(1));
+
+        (from, to)
+    }
+}
+
+impl Subcommand for Report {
+    fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(format_err!("The start date {} is after the end date {}.", from, to));
+            }
+        }
+
+        let sessions = tx.fetch_sessions()
+            .map_err(|e| format_err!("Could not get work sessions from database. {}", e))?;
+
+        // completed and removed tasks keep their tags in the trash
+        let mut tags = HashMap::new();
+        for trashed in tx.fetch_trash().map_err(|e| format_err!("Could not get trash from database. {}", e))? {
+            tags.insert(*trashed.task.uuid(), trashed.details.tags);
+        }
+        for task in tx.fetch_all_tasks().map_err(|e| format_err!("Could not get tasks from database. {}", e))? {
+            let details = tx.fetch_task_details(task.uuid())
+                .map_err(|e| format_err!("Could not get task details from database. {}", e))?;
+            tags.insert(*task.uuid(), details.tags);
+        }
+
+        let now = Utc::now();
+        let (from, to) = self.range(now);
+        let report = TimeReport::new(&sessions, &tags, from, to, now);
+
+        Ok(CommandOutput::Report(report))
+    }
+}
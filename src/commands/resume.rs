@@ -0,0 +1,29 @@
+use failure::Error;
+
+use crate::db::DBBackend;
+use crate::commands::{Subcommand, CommandOutput};
+
+#[derive(Debug)]
+pub struct Resume;
+
+impl Subcommand for Resume {
+    fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
+        let current = tx.fetch_current_task()
+            .map_err(|e| format_err!("Could not get current task from database. {}", e))?;
+
+        let current = match current {
+            Some(current) => current,
+            None => return Ok(CommandOutput::NoTasks),
+        };
+
+        let resumed = tx.resume_session()
+            .map_err(|e| format_err!("Could not resume time tracking. {}", e))?;
+
+        if resumed {
+            Ok(CommandOutput::Resumed(current))
+        }
+        else {
+            Ok(CommandOutput::Message("Time tracking is not paused.".to_string()))
+        }
+    }
+}
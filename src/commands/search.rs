@@ -0,0 +1,106 @@
+use failure::Error;
+
+use crate::db::{DBBackend, RemovalSource};
+use crate::task::Task;
+
+use super::CommandOutput;
+
+#[derive(StructOpt, Debug)]
+pub struct Search {
+    #[structopt(raw(required = "true"))]
+    /// The words to search for. A task matches if its description contains every word, or a word
+    /// starting with it, ignoring case and accents. Completed and removed tasks in the trash are
+    /// searched too.
+    pub query: Vec<String>,
+
+    #[structopt(long = "limit", default_value = "20")]
+    /// The maximum number of results to show.
+    pub limit: usize,
+
+    #[structopt(long = "pick")]
+    /// The number of the result to act on, as listed by the same search.
+    pub pick: Option<usize>,
+
+    #[structopt(long = "current", raw(requires = r#""pick""#, conflicts_with_all = r#"&["remove", "edit", "priority"]"#))]
+    /// Make the picked task the current task.
+    pub current: bool,
+
+    #[structopt(long = "remove", raw(requires = r#""pick""#, conflicts_with_all = r#"&["edit", "priority"]"#))]
+    /// Remove the picked task, moving it to the trash.
+    pub remove: bool,
+
+    #[structopt(long = "edit", raw(requires = r#""pick""#))]
+    /// Replace the description of the picked task.
+    pub edit: Option<String>,
+
+    #[structopt(long = "priority", raw(requires = r#""pick""#))]
+    /// Change the priority of the picked task.
+    pub priority: Option<u32>,
+}
+
+/// Turn the words of a search into an FTS5 query that matches descriptions containing each of
+/// them as a prefix of a word. Each word is quoted, so characters that are part of the FTS5 query
+/// syntax are searched for as text. Words without any letters or digits are left out, since they
+/// can't match anything.
+pub(super) fn fts_query(words: &[String]) -> Result<String, Error> {
+    let terms: Vec<String> = words.iter()
+        .flat_map(|word| word.split_whitespace())
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        return Err(format_err!("There are no words to search for in \"{}\".", words.join(" ")));
+    }
+    Ok(terms.join(" "))
+}
+
+impl Search {
+    /// Whether the search changes the picked task, rather than only listing results.
+    pub fn changes_task(&self) -> bool {
+        self.current || self.remove || self.edit.is_some() || self.priority.is_some()
+    }
+
+    pub fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
+        let query = fts_query(&self.query)?;
+        let results = tx.search_tasks(&query, self.limit)
+            .map_err(|e| format_err!("Could not search tasks. {}", e))?;
+
+        let pick = match self.pick {
+            Some(pick) => pick,
+            None => return Ok(CommandOutput::SearchResults(results)),
+        };
+        let result = match pick.checked_sub(1).and_then(|i| results.get(i)) {
+            Some(result) => result.clone(),
+            None => return Err(format_err!("Can't pick result {}: there are {} results.", pick, results.len())),
+        };
+
+        if !self.changes_task() {
+            return Ok(CommandOutput::SearchResults(vec![result]));
+        }
+        if let Some(source) = result.removed {
+            let id = result.task.uuid().to_string();
+            return Err(format_err!("Task \"{}\" is in the trash ({}). Restore it first with `trash restore {}`.",
+                                   result.task.task(), source, &id[..8]));
+        }
+        let task = result.task;
+
+        if self.current {
+            tx.set_current_task_by_uuid(task.uuid())
+                .map_err(|e| format_err!("Could not make task current. {}", e))?;
+            return Ok(CommandOutput::Picked { task, action: "current" });
+        }
+        if self.remove {
+            tx.remove_task_by_uuid(task.uuid(), &RemovalSource::Local)
+                .map_err(|e| format_err!("Could not remove task. {}", e))?;
+            return Ok(CommandOutput::Picked { task, action: "removed" });
+        }
+
+        let description = self.edit.clone().unwrap_or_else(|| task.task().to_string());
+        let priority = self.priority.unwrap_or_else(|| task.priority());
+        let edited = Task::from_parts(description, priority, task.is_break(), *task.uuid())?;
+        tx.update_task(&edited)
+            .map_err(|e| format_err!("Could not update task. {}", e))?;
+        Ok(CommandOutput::Picked { task: edited, action: "edited" })
+    }
+}
@@ -0,0 +1,18 @@
+use failure::Error;
+
+use crate::config::Config;
+
+use super::CommandOutput;
+
+#[derive(Debug)]
+pub struct Shell;
+
+impl Shell {
+    /// Run the interactive shell. Each command in the shell is dispatched in its own transaction.
+    pub fn dispatch(&self, config: &Config) -> Result<CommandOutput, Error> {
+        crate::shell::run(config)?;
+
+        // each command's output was already displayed in the shell
+        Ok(CommandOutput::Sequence(Vec::new()))
+    }
+}
@@ -0,0 +1,96 @@
+use chrono::Duration;
+use failure::Error;
+
+use crate::db::DBBackend;
+use crate::selection::{SelectionContext, SelectionStrategy, select_category_and_task};
+use crate::task::{Category, Task};
+
+use super::{CommandOutput, Simulation};
+
+/// Parse a nonzero usize from a command line argument
+fn is_usize_nonzero(arg: &str) -> Result<usize, Error> {
+    let n: usize = arg.parse().map_err(|e| format_err!("Unable to parse {} as valid integer: {}", arg, e))?;
+    if n == 0 {
+        return Err(format_err!("Number of simulated selections cannot be 0."));
+    }
+
+    Ok(n)
+}
+
+#[derive(StructOpt, Debug)]
+pub struct Simulate {
+    #[structopt(short = "n", default_value = "10000", parse(try_from_str = "is_usize_nonzero"))]
+    /// The number of selections to simulate.
+    pub n: usize,
+    #[structopt(long = "completions")]
+    /// Also simulate completing this many tasks in a row, and display the order they were
+    /// completed in. Each task is assumed to take as long as its estimate, if it has one.
+    pub completions: Option<usize>,
+}
+
+impl Simulate {
+    /// Simulate selecting tasks with `selector`. Nothing is written to the database.
+    pub fn run(&self, tx: &impl DBBackend, selector: &mut dyn SelectionStrategy) -> Result<CommandOutput, Error> {
+        let all_tasks = tx.fetch_all_tasks()
+            .map_err(|e| format_err!("Could not get tasks from database. {}", e))?;
+
+        if all_tasks.is_empty() {
+            return Ok(CommandOutput::NoTasks);
+        }
+
+        let context = tx.fetch_selection_context()
+            .map_err(|e| format_err!("Could not get selection context from database. {}", e))?;
+        selector.set_context(&context);
+
+        let tasks: Vec<&Task> = all_tasks.iter().filter(|t| !t.is_break()).collect();
+        let breaks: Vec<&Task> = all_tasks.iter().filter(|t| t.is_break()).collect();
+
+        // counts are in the same order as `all_tasks`, which is tasks followed by breaks
+        let mut counts = vec![0usize; all_tasks.len()];
+        let mut break_count = 0;
+        for _ in 0..self.n {
+            match select_category_and_task(selector, &tasks, &breaks) {
+                Some((Category::Task, idx)) => counts[idx] += 1,
+                Some((Category::Break, idx)) => {
+                    counts[tasks.len() + idx] += 1;
+                    break_count += 1;
+                }
+                None => unreachable!("No tasks were selected even though we checked there were some"),
+            }
+        }
+
+        let completion_order = self.completions
+            .map(|completions| simulate_completions(tx, selector, context, tasks, breaks, completions))
+            .transpose()?;
+
+        Ok(CommandOutput::Simulation(Simulation {
+            selections: self.n,
+            breaks: break_count,
+            counts: all_tasks.iter().cloned().zip(counts).collect(),
+            completion_order,
+        }))
+    }
+}
+
+/// Select and remove up to `n` tasks one after another, returning them in the order they were
+/// selected. The selector's context is updated after each one as if it had been completed.
+fn simulate_completions(tx: &impl DBBackend, selector: &mut dyn SelectionStrategy, mut context: SelectionContext,
+                        mut tasks: Vec<&Task>, mut breaks: Vec<&Task>, n: usize) -> Result<Vec<Task>, Error> {
+    let mut output = Vec::new();
+    for _ in 0..n {
+        selector.set_context(&context);
+        let completed = match select_category_and_task(selector, &tasks, &breaks) {
+            Some((Category::Task, idx)) => tasks.remove(idx),
+            Some((Category::Break, idx)) => breaks.remove(idx),
+            None => break,
+        };
+
+        let estimate = tx.fetch_task_details(completed.uuid())
+            .map_err(|e| format_err!("Could not get task details from database. {}", e))?
+            .estimate;
+        context.record_completion(completed, estimate.unwrap_or_else(Duration::zero));
+        output.push(completed.clone());
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,50 @@
+use failure::Error;
+
+use crate::db::DBBackend;
+use crate::selection::{ForceBreak, SelectionStrategy};
+
+use super::CommandOutput;
+
+/// Parse a probability from a command line argument, which must be at least 0 and less than 1.
+fn parse_prob(arg: &str) -> Result<f32, Error> {
+    let p: f32 = arg.parse().map_err(|e| format_err!("Unable to parse probability \"{}\": {}", arg, e))?;
+    if !(0.0..1.0).contains(&p) {
+        return Err(format_err!("Probability must be at least 0 and less than 1."));
+    }
+
+    Ok(p)
+}
+
+#[derive(StructOpt, Debug, Default)]
+pub struct Break {
+    #[structopt(short = "p", long = "probability", parse(try_from_str = "parse_prob"))]
+    /// The probability as a decimal to select a task from break rather than a regular task. Must
+    /// be less than 1.0. Without it, a break is always selected.
+    pub p: Option<f32>,
+}
+
+impl Break {
+    /// Skip the current task and select a break instead, or a task if there are no breaks. With a
+    /// probability, a task may be selected instead.
+    pub fn run(&self, tx: &impl DBBackend, selector: &mut dyn SelectionStrategy) -> Result<CommandOutput, Error> {
+        let original_task_opt = tx.fetch_current_task()
+            .map_err(|e| format_err!("Could not fetch current task. {}", e))?;
+
+        let mut selector = match self.p {
+            Some(p) => ForceBreak::with_probability(selector, f64::from(p)),
+            None => ForceBreak::new(selector),
+        };
+        tx.skip_current_task(&mut selector)
+            .map_err(|e| format_err!("Could not skip current task. {}", e))?;
+
+        let opt = tx.fetch_current_task()
+            .map_err(|e| format_err!("Could not fetch current task. {}", e))?;
+
+        // if the skipped task was the only one, it is selected again
+        match (opt, original_task_opt) {
+            (Some(current_task), _) => Ok(CommandOutput::Skipped(current_task)),
+            (None, Some(original_task)) => Ok(CommandOutput::Skipped(original_task)),
+            (None, None) => Ok(CommandOutput::NoTasks),
+        }
+    }
+}
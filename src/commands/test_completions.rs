@@ -0,0 +1,88 @@
+use structopt::clap::Shell;
+
+use crate::config::Config;
+use crate::task::Task;
+
+use structopt::StructOpt;
+
+use super::{CommandOutput, Completions, TKZArgs, TKZCmd, complete_ids};
+use super::completions::task_id_completion;
+use super::test_utils::add_from_task;
+
+fn add(config: &Config, description: &str) {
+    let task = Task::new_from_parts(description.to_string(), 1, false).unwrap();
+    add_from_task(&task).dispatch(config).expect("Adding task failed");
+}
+
+fn complete(config: &Config) {
+    let args = TKZArgs::from_iter_safe(&["tkzr", "complete"]).unwrap();
+    args.cmd().dispatch(config).expect("Completing task failed");
+}
+
+fn script(shell: Shell) -> String {
+    match (Completions { shell }).dispatch().expect("Generating completions failed") {
+        CommandOutput::Message(script) => script,
+        output => panic!("Wrong output from completions: {:?}", output),
+    }
+}
+
+#[test]
+fn test_completions() {
+    for &shell in &[Shell::Bash, Shell::Zsh, Shell::Fish] {
+        let script = script(shell);
+        for name in TKZCmd::NAMES {
+            assert!(script.contains(name), "{:?} completions don't include {}", shell, name);
+        }
+    }
+}
+
+#[test]
+/// The task id completion is only added for subcommands that take a task id.
+fn test_completions_task_ids() {
+    for &shell in &[Shell::Bash, Shell::Zsh, Shell::Fish] {
+        assert_eq!(task_id_completion(shell, &[]), "");
+
+        let completion = task_id_completion(shell, &[&["delete"], &["trash", "restore"]]);
+        assert!(completion.contains("tkzr __complete-ids"), "No task id completion for {:?}", shell);
+        assert!(completion.contains("delete") && completion.contains("trash") && completion.contains("restore"));
+    }
+}
+
+#[test]
+/// The generated scripts complete task ids for every subcommand that takes one.
+fn test_completions_script_task_ids() {
+    for &shell in &[Shell::Bash, Shell::Zsh, Shell::Fish] {
+        let script = script(shell);
+        assert!(!TKZCmd::TASK_ID_COMMANDS.is_empty());
+        for command in TKZCmd::TASK_ID_COMMANDS {
+            let command = command.join(" ");
+            let call = match shell {
+                Shell::Fish => format!("tkzr __complete-ids '{}'", command),
+                _ => format!("\"{}\"", command),
+            };
+            assert!(script.contains("tkzr __complete-ids"), "{:?} completions don't complete task ids", shell);
+            assert!(script.contains(&call), "{:?} completions don't complete task ids for {}", shell, command);
+        }
+    }
+}
+
+#[test]
+/// Only the ids that the command takes are completed: `trash restore` takes tasks in the trash.
+fn test_complete_ids() {
+    let (_dir, config) = Config::test_config();
+    assert!(complete_ids(&config, "trash restore", "").expect("Completing ids failed").is_empty());
+    assert!(complete_ids(&config, "current", "").is_err());
+
+    add(&config, "first\ttask");
+    assert!(complete_ids(&config, "trash restore", "").expect("Completing ids failed").is_empty());
+    complete(&config);
+    add(&config, "second task");
+    complete(&config);
+
+    let ids = complete_ids(&config, "trash restore", "").expect("Completing ids failed");
+    assert_eq!(ids.len(), 2);
+    assert!(ids[1].ends_with("\tfirst task"), "Control characters weren't replaced: {:?}", ids[1]);
+
+    let uuid = ids[1].split('\t').next().unwrap();
+    assert_eq!(complete_ids(&config, "trash restore", &uuid[..8]).expect("Completing ids failed"), vec![ids[1].clone()]);
+}
@@ -0,0 +1,116 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::db::DBBackend;
+use crate::db::tests::open_test_db;
+use crate::selection::Top;
+use crate::task::TaskDetails;
+
+use super::{CommandOutput, Quick, TKZCmd, parse_due, parse_quick_add};
+
+/// A Wednesday.
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd(2019, 6, 26)
+}
+
+#[test]
+fn test_parse_due() {
+    assert_eq!(parse_due("today", today()).unwrap(), today());
+    assert_eq!(parse_due("Tomorrow", today()).unwrap(), NaiveDate::from_ymd(2019, 6, 27));
+    assert_eq!(parse_due("fri", today()).unwrap(), NaiveDate::from_ymd(2019, 6, 28));
+    assert_eq!(parse_due("wednesday", today()).unwrap(), today());
+    assert_eq!(parse_due("tue", today()).unwrap(), NaiveDate::from_ymd(2019, 7, 2));
+    assert_eq!(parse_due("3d", today()).unwrap(), NaiveDate::from_ymd(2019, 6, 29));
+    assert_eq!(parse_due("2w", today()).unwrap(), NaiveDate::from_ymd(2019, 7, 10));
+    assert_eq!(parse_due("2019-12-31", today()).unwrap(), NaiveDate::from_ymd(2019, 12, 31));
+
+    assert!(parse_due("someday", today()).is_err());
+    assert!(parse_due("3x", today()).is_err());
+    assert!(parse_due("2019-02-30", today()).is_err());
+}
+
+#[test]
+fn test_parse_quick_add() {
+    let (task, details) = parse_quick_add("call dentist !5 #break +phone due:fri est:10m +home", today())
+        .expect("Parsing failed");
+    assert_eq!(task.task(), "call dentist");
+    assert_eq!(task.priority(), 5);
+    assert!(task.is_break());
+    assert_eq!(details, TaskDetails {
+        tags: vec!["home".to_string(), "phone".to_string()],
+        due: Some(NaiveDate::from_ymd(2019, 6, 28)),
+        estimate: Some(Duration::minutes(10)),
+    });
+
+    // markers can go anywhere, and anything that isn't a marker is part of the description
+    let (task, details) = parse_quick_add("!2 fix bug #1 in the wow! parser \\+x \\!3", today())
+        .expect("Parsing failed");
+    assert_eq!(task.task(), "fix bug #1 in the wow! parser +x !3");
+    assert_eq!(task.priority(), 2);
+    assert!(!task.is_break());
+    assert!(details.is_empty());
+}
+
+#[test]
+fn test_parse_quick_add_invalid() {
+    let err = parse_quick_add("!3 #break +tag", today()).unwrap_err();
+    assert!(err.to_string().contains("description cannot be empty"), "Wrong error: {}", err);
+
+    let err = parse_quick_add("task !0", today()).unwrap_err();
+    assert!(err.to_string().contains("priority cannot be 0"), "Wrong error: {}", err);
+
+    assert!(parse_quick_add("task !1 !2", today()).is_err());
+    assert!(parse_quick_add("task #task #break", today()).is_err());
+    assert!(parse_quick_add("task due:never", today()).is_err());
+    assert!(parse_quick_add("task est:0m", today()).is_err());
+    // too far away for chrono
+    assert!(parse_quick_add("task due:99999999999d", today()).is_err());
+    assert!(parse_quick_add("task due:9223372036854775807w", today()).is_err());
+    assert!(parse_quick_add("task due:99999999d", today()).is_err());
+    assert!(parse_quick_add("task est:999999999999999h", today()).is_err());
+}
+
+#[test]
+fn test_runcmd_quick_add() {
+    let mut selector = Top::new();
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    let cmd = TKZCmd::Quick(Quick {
+        text: vec!["water plants".to_string(), "!3 +home est:5m".to_string()],
+        dry_run: false,
+        yes: true,
+    });
+    let output = cmd.run(&tx, &mut selector).expect("Quick add failed");
+
+    let task = match output {
+        CommandOutput::QuickAdded { task, added: true, .. } => task,
+        output => panic!("Wrong output from quick add: {:?}", output),
+    };
+    assert_eq!(tx.fetch_all_tasks().unwrap(), vec![task.clone()]);
+    assert_eq!(tx.fetch_task_details(task.uuid()).unwrap(), TaskDetails {
+        tags: vec!["home".to_string()],
+        due: None,
+        estimate: Some(Duration::minutes(5)),
+    });
+}
+
+#[test]
+fn test_runcmd_quick_add_dry_run() {
+    let mut selector = Top::new();
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    let cmd = TKZCmd::Quick(Quick {
+        text: vec!["water plants !3".to_string()],
+        dry_run: true,
+        yes: false,
+    });
+    assert!(cmd.is_read_only());
+
+    let output = cmd.run(&tx, &mut selector).expect("Quick add failed");
+    match output {
+        CommandOutput::QuickAdded { task, added: false, .. } => assert_eq!(task.priority(), 3),
+        output => panic!("Wrong output from quick add: {:?}", output),
+    }
+    assert!(tx.fetch_all_tasks().unwrap().is_empty());
+}
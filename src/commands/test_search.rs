@@ -0,0 +1,71 @@
+use structopt::StructOpt;
+
+use crate::db::{DBBackend, RemovalSource};
+use crate::db::tests::open_test_db;
+use crate::task::test_utils::{example_task_1, example_task_3};
+
+use super::{CommandOutput, Search};
+use super::search::fts_query;
+
+fn words(query: &str) -> Vec<String> {
+    query.split(' ').map(String::from).collect()
+}
+
+fn search(argv: &[&str]) -> Search {
+    Search::from_iter_safe(argv).expect("Parsing search failed")
+}
+
+#[test]
+fn test_fts_query() {
+    assert_eq!(fts_query(&words("call dentist")).unwrap(), "\"call\"* \"dentist\"*");
+    // query syntax is searched for as text
+    assert_eq!(fts_query(&words("NOT a\"b")).unwrap(), "\"NOT\"* \"a\"\"b\"*");
+    assert_eq!(fts_query(&["two words".to_string(), "-".to_string()]).unwrap(), "\"two\"* \"words\"*");
+    assert!(fts_query(&words("- *")).is_err());
+}
+
+#[test]
+fn test_search_args() {
+    assert!(Search::from_iter_safe(&["search"]).is_err());
+    assert!(Search::from_iter_safe(&["search", "task", "--current"]).is_err());
+    assert!(Search::from_iter_safe(&["search", "task", "--pick", "1", "--current", "--remove"]).is_err());
+    assert!(!search(&["search", "task", "--pick", "1"]).changes_task());
+    assert!(search(&["search", "task", "--pick", "1", "--edit", "new", "--priority", "3"]).changes_task());
+}
+
+#[test]
+/// Picked tasks are made current, edited or removed, but tasks in the trash aren't changed.
+fn test_search_pick() {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+    tx.add_task(&example_task_1()).expect("Failed adding task");
+    tx.add_task(&example_task_3()).expect("Failed adding task");
+
+    match search(&["search", "task"]).run(&tx).expect("Search failed") {
+        CommandOutput::SearchResults(results) => assert_eq!(results.len(), 2),
+        output => panic!("Unexpected output {:?}", output),
+    }
+    assert!(search(&["search", "task", "--pick", "3", "--current"]).run(&tx).is_err());
+    assert!(search(&["search", "task", "--pick", "0", "--current"]).run(&tx).is_err());
+
+    let output = search(&["search", "another", "--pick", "1", "--current"]).run(&tx).expect("Search failed");
+    assert_eq!(output, CommandOutput::Picked { task: example_task_3(), action: "current" });
+    assert_eq!(tx.fetch_current_task().unwrap(), Some(example_task_3()));
+
+    let output = search(&["search", "another", "--pick", "1", "--edit", "yet another task", "--priority", "7"])
+        .run(&tx).expect("Search failed");
+    let edited = match output {
+        CommandOutput::Picked { task, action: "edited" } => task,
+        output => panic!("Unexpected output {:?}", output),
+    };
+    assert_eq!((edited.task(), edited.priority(), edited.uuid()), ("yet another task", 7, example_task_3().uuid()));
+    assert_eq!(tx.fetch_current_task().unwrap(), Some(edited.clone()));
+
+    let output = search(&["search", "yet", "--pick", "1", "--remove"]).run(&tx).expect("Search failed");
+    assert_eq!(output, CommandOutput::Picked { task: edited.clone(), action: "removed" });
+    assert_eq!(tx.fetch_all_tasks().unwrap(), vec![example_task_1()]);
+    assert_eq!(tx.fetch_trash().unwrap()[0].source, RemovalSource::Local);
+
+    let res = search(&["search", "yet", "--pick", "1", "--current"]).run(&tx);
+    assert!(res.unwrap_err().to_string().contains("is in the trash"));
+}
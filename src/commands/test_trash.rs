@@ -0,0 +1,39 @@
+use crate::db::DBBackend;
+use crate::db::tests::open_test_db;
+use crate::sync::{USetOp, apply_all_uset_ops};
+use crate::sync::test_utils::{example_replica_1, example_replica_2};
+use crate::task::test_utils::{example_task_1, example_task_2};
+
+use super::{Trash, TrashCmd};
+
+fn restore(tx: &impl DBBackend, id: &str) {
+    let cmd = Trash { cmd: TrashCmd::Restore { id: id.to_string() } };
+    cmd.run(tx).expect("Restoring task failed");
+}
+
+#[test]
+/// Restoring a task removed by sync sends it to every replica again, but restoring a task that was
+/// completed here doesn't, since the replicas still have it.
+fn test_trash_restore_uset_ops() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    tx.store_replica_client(&example_replica_1()).expect("Storing replica failed");
+    tx.store_replica_client(&example_replica_2()).expect("Storing replica failed");
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.add_task(&example_task_2()).expect("Adding task failed");
+
+    tx.set_current_task_by_uuid(example_task_1().uuid()).expect("Setting current task failed");
+    tx.complete_current_task().expect("Completing task failed");
+    restore(&tx, &example_task_1().uuid().to_string());
+    for replica in &[example_replica_1(), example_replica_2()] {
+        assert_eq!(tx.fetch_uset_op_msgs(replica).unwrap(), Vec::new());
+    }
+
+    apply_all_uset_ops(&tx, &example_replica_1(), &[USetOp::Remove(*example_task_2().uuid())])
+        .expect("Applying remove failed");
+    restore(&tx, &example_task_2().uuid().to_string());
+    for replica in &[example_replica_1(), example_replica_2()] {
+        let ops: Vec<USetOp> = tx.fetch_uset_op_msgs(replica).unwrap().into_iter().map(|msg| msg.op).collect();
+        assert_eq!(ops, vec![USetOp::Add(example_task_2())]);
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::Duration;
+use failure::Error;
+
+use rand::prelude::*;
+
+use crate::config::Config;
+use crate::db::DBBackend;
+use crate::selection::ForceBreak;
+use crate::timer::{TimerState, format_duration, parse_duration, run_notify_command};
+
+use super::CommandOutput;
+
+#[derive(StructOpt, Debug)]
+pub struct Timer {
+    #[structopt(default_value = "25m", parse(try_from_str = "parse_duration"))]
+    /// The length of the timer, e.g. "25m", "90s", or "1h". A number without a unit is in minutes.
+    pub length: Duration,
+    #[structopt(long = "max", parse(try_from_str = "parse_duration"))]
+    /// Choose the length of the timer randomly between the given length and this one.
+    pub max: Option<Duration>,
+    #[structopt(long = "break")]
+    /// When the timer ends, select a break as the new current task.
+    pub select_break: bool,
+}
+
+impl Timer {
+    /// Choose the length of the timer, randomly if a maximum was given.
+    fn choose_length(&self) -> Result<Duration, Error> {
+        match self.max {
+            None => Ok(self.length),
+            Some(max) if max < self.length => {
+                Err(format_err!("Maximum timer length {} is less than the minimum {}.",
+                                format_duration(max), format_duration(self.length)))
+            }
+            Some(max) => {
+                let seconds = thread_rng().gen_range(self.length.num_seconds(), max.num_seconds() + 1);
+                Ok(Duration::seconds(seconds))
+            }
+        }
+    }
+
+    /// Run the timer. Unlike other commands this does not run in a single transaction, since the
+    /// database would be locked while the timer is running. The timer is stored in one transaction
+    /// so that other invocations can see it, and cleared in another when it ends.
+    ///
+    /// `progress` gets `CommandOutput::TimerStarted` when the timer starts, since the output that
+    /// is returned only comes when it ends.
+    pub fn dispatch(&self, config: &Config, progress: &mut dyn FnMut(&CommandOutput)) -> Result<CommandOutput, Error> {
+        let length = self.choose_length()?;
+        let mut db = config.db()?;
+        let mut selector = config.selector();
+
+        let (task, timer) = {
+            let tx = db.transaction()?;
+
+            let mut current_opt = tx.fetch_current_task()
+                .map_err(|e| format_err!("Could not get current task from database. {}", e))?;
+            if current_opt.is_none() {
+                tx.select_current_task(&mut *selector)?;
+                current_opt = tx.fetch_current_task()
+                    .map_err(|e| format_err!("Could not get current task from database. {}", e))?;
+            }
+
+            let task = match current_opt {
+                Some(task) => task,
+                None => return Ok(CommandOutput::NoTasks),
+            };
+
+            let timer = TimerState::start(&task, length);
+            tx.store_timer(&timer)
+                .map_err(|e| format_err!("Could not store timer in database. {}", e))?;
+            tx.finish()?;
+
+            (task, timer)
+        };
+
+        progress(&CommandOutput::TimerStarted { task: task.clone(), length });
+        std::thread::sleep(length.to_std().expect("Timer length is always positive"));
+
+        let tx = db.transaction()?;
+        // another timer may have been started while this one was running
+        let stored = tx.fetch_timer()
+            .map_err(|e| format_err!("Could not get timer from database. {}", e))?;
+        if stored == Some(timer) {
+            tx.clear_timer()
+                .map_err(|e| format_err!("Could not clear timer from database. {}", e))?;
+        }
+
+        let still_current = tx.fetch_current_task()
+            .map_err(|e| format_err!("Could not get current task from database. {}", e))?
+            .is_some_and(|current| current.uuid() == task.uuid());
+
+        let mut current = None;
+        if self.select_break && still_current {
+            tx.skip_current_task(&mut ForceBreak::new(&mut *selector))
+                .map_err(|e| format_err!("Could not select a break. {}", e))?;
+
+            // if the task was the only one in the database it is not current after skipping
+            if tx.fetch_current_task()?.is_none() {
+                tx.select_current_task(&mut *selector)?;
+            }
+            current = tx.fetch_current_task()?;
+        }
+        tx.finish()?;
+
+        let command = &config.timer.notify_command;
+        let notify_error = if command.is_empty() {
+            None
+        }
+        else {
+            run_notify_command(command, &task).err().map(|e| e.to_string())
+        };
+
+        Ok(CommandOutput::TimerFinished { task, current, notify_error })
+    }
+}
+
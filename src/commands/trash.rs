@@ -0,0 +1,63 @@
+use failure::Error;
+
+use crate::db::{DBBackend, RemovalSource};
+use crate::sync::{self, USetOp};
+
+use super::{CommandOutput, find_by_id};
+
+#[derive(StructOpt, Debug)]
+pub struct Trash {
+    #[structopt(subcommand)]
+    pub cmd: TrashCmd,
+}
+
+#[derive(StructOpt, Debug)]
+pub enum TrashCmd {
+    #[structopt(name = "list")]
+    /// List the tasks in the trash, most recently removed first.
+    List,
+
+    #[structopt(name = "restore")]
+    /// Add a task in the trash back to the task list, along with its tags, due date and estimate.
+    Restore {
+        /// The UUID of the task, or enough of the start of it to tell it apart from the other tasks
+        /// in the trash.
+        id: String,
+    },
+
+    #[structopt(name = "empty")]
+    /// Permanently remove every task in the trash.
+    Empty,
+}
+
+impl Trash {
+    pub fn run(&self, tx: &impl DBBackend) -> Result<CommandOutput, Error> {
+        match &self.cmd {
+            TrashCmd::List => {
+                let trash = tx.fetch_trash()
+                    .map_err(|e| format_err!("Could not get trash from database. {}", e))?;
+                Ok(CommandOutput::Trash(trash))
+            }
+            TrashCmd::Restore { id } => {
+                let trash = tx.fetch_trash()
+                    .map_err(|e| format_err!("Could not get trash from database. {}", e))?;
+                let uuid = *find_by_id(&trash, |trashed| trashed.task.uuid(), id, "in the trash")?.task.uuid();
+
+                let trashed = tx.restore_from_trash(&uuid)?
+                    .ok_or_else(|| format_err!("Task {} is no longer in the trash.", uuid))?;
+                // a task removed by sync was removed on the other replicas too, so it is sent to them
+                // again. Tasks removed here were never removed there.
+                if let RemovalSource::Replica(_) = trashed.source {
+                    sync::queue_for_all_replicas(tx, &[USetOp::Add(trashed.task.clone())])?;
+                }
+
+                Ok(CommandOutput::Restored(trashed.task))
+            }
+            TrashCmd::Empty => {
+                let removed = tx.empty_trash(None)
+                    .map_err(|e| format_err!("Could not empty trash. {}", e))?;
+                Ok(CommandOutput::TrashEmptied(removed))
+            }
+        }
+    }
+}
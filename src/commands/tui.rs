@@ -0,0 +1,19 @@
+use failure::Error;
+
+use crate::config::Config;
+
+use super::CommandOutput;
+
+#[derive(Debug)]
+pub struct Tui;
+
+impl Tui {
+    /// Run the terminal UI. Like the timer, this manages its own transactions: one for each action
+    /// the user takes.
+    pub fn dispatch(&self, config: &Config) -> Result<CommandOutput, Error> {
+        crate::tui::run(config)?;
+
+        // everything was already displayed in the terminal UI
+        Ok(CommandOutput::Sequence(Vec::new()))
+    }
+}
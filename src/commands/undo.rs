@@ -0,0 +1,38 @@
+use failure::Error;
+
+use crate::db::DBBackend;
+use crate::journal;
+use crate::selection::SelectionStrategy;
+
+use super::CommandOutput;
+
+#[derive(StructOpt, Debug)]
+pub struct Undo {
+    #[structopt(default_value = "1", parse(try_from_str = "parse_count"))]
+    /// The number of commands to undo.
+    pub count: usize,
+}
+
+/// Parse the number of commands to undo, which must be at least 1.
+fn parse_count(s: &str) -> Result<usize, Error> {
+    let count: usize = s.parse().map_err(|e| format_err!("Invalid number of commands \"{}\": {}", s, e))?;
+    if count == 0 {
+        return Err(format_err!("The number of commands to undo must be at least 1."));
+    }
+    Ok(count)
+}
+
+impl Undo {
+    pub fn run(&self, tx: &impl DBBackend, selector: &mut dyn SelectionStrategy) -> Result<CommandOutput, Error> {
+        let undone = journal::undo(tx, self.count)?;
+
+        // undoing the first add leaves no current task, but there may still be other tasks
+        if tx.fetch_current_task()?.is_none() {
+            tx.select_current_task(selector)?;
+        }
+        let current = tx.fetch_current_task()
+            .map_err(|e| format_err!("Could not fetch current task. {}", e))?;
+
+        Ok(CommandOutput::Undone { commands: undone.into_iter().map(|entry| entry.command).collect(), current })
+    }
+}
@@ -0,0 +1,28 @@
+use failure::Error;
+
+use crate::db::DBBackend;
+use crate::selection::{SelectionStrategy, selection_probabilities};
+
+use super::CommandOutput;
+
+#[derive(Debug)]
+pub struct Why;
+
+impl Why {
+    pub fn run(&self, tx: &impl DBBackend, selector: &mut dyn SelectionStrategy) -> Result<CommandOutput, Error> {
+        let tasks = tx.fetch_all_tasks()
+            .map_err(|e| format_err!("Could not get tasks from database. {}", e))?;
+
+        if tasks.is_empty() {
+            return Ok(CommandOutput::NoTasks);
+        }
+
+        let context = tx.fetch_selection_context()
+            .map_err(|e| format_err!("Could not get selection context from database. {}", e))?;
+        selector.set_context(&context);
+
+        let probabilities = selection_probabilities(selector, &tasks);
+
+        Ok(CommandOutput::Probabilities(probabilities))
+    }
+}
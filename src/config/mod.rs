@@ -8,12 +8,20 @@ use directories::ProjectDirs;
 
 use failure::Error;
 
-use crate::db::SqliteBackend;
+use crate::db::{DBBackend, SqliteBackend};
+use crate::hooks::Hooks;
+use crate::selection::{SelectionStrategy, WeightedRandom, Aging, NoRepeat, Pomodoro};
 
 #[cfg(test)]
 mod tests;
 
 const DEFAULT_BREAK_CUTOFF: f32 = 0.35;
+const DEFAULT_AGING_HALF_LIFE_HOURS: f64 = 72.0;
+const DEFAULT_NO_REPEAT_WINDOW: usize = 3;
+const DEFAULT_NO_REPEAT_DECAY: f64 = 0.0;
+const DEFAULT_POMODORO_TASKS_PER_BREAK: u32 = 4;
+const DEFAULT_POMODORO_WORK_MINUTES: u32 = 120;
+const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
 
 /// Configuration parameters.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -22,6 +30,87 @@ pub struct Config {
     pub db_path: PathBuf,
     /// The probability of choosing a break when choosing a new task.
     pub break_cutoff: f32,
+    /// Location of the hooks directory. See `Hooks` for the executables run from it. Defaults to
+    /// a `hooks` directory next to the config file.
+    // values have to come before tables when serializing to toml
+    #[serde(default = "Config::default_hooks_path")]
+    pub hooks_path: Option<PathBuf>,
+    /// How new current tasks are selected.
+    #[serde(default)]
+    pub selection: SelectionConfig,
+    /// Settings for the `timer` command.
+    #[serde(default)]
+    pub timer: TimerConfig,
+    /// Settings for automatic database backups.
+    #[serde(default)]
+    pub backup: BackupConfig,
+    /// Settings for the trash, which keeps removed tasks.
+    #[serde(default)]
+    pub trash: TrashConfig,
+}
+
+/// Which `SelectionStrategy` is used to select new current tasks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    /// Select tasks randomly, weighted by priority. See `WeightedRandom`.
+    WeightedRandom,
+    /// Like `WeightedRandom`, but weights grow as tasks get older. See `Aging`.
+    Aging,
+    /// Like `WeightedRandom`, but recently selected tasks are penalized. See `NoRepeat`.
+    NoRepeat,
+    /// Like `WeightedRandom`, but breaks are scheduled by time and tasks completed. See `Pomodoro`.
+    Pomodoro,
+}
+
+/// Configuration parameters for task selection.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SelectionConfig {
+    /// The selection strategy to use.
+    pub strategy: StrategyKind,
+    /// The number of hours it takes for a task's weight to double when using the aging strategy.
+    pub aging_half_life_hours: f64,
+    /// The number of recent selections penalized when using the no-repeat strategy.
+    pub no_repeat_window: usize,
+    /// How much recently selected tasks are penalized when using the no-repeat strategy, between 0
+    /// and 1. 0 excludes them entirely.
+    pub no_repeat_decay: f64,
+    /// The number of completed tasks after which a break is always chosen when using the pomodoro
+    /// strategy. 0 disables this.
+    pub pomodoro_tasks_per_break: u32,
+    /// The number of minutes spent working on tasks since the last break after which a break is
+    /// always chosen when using the pomodoro strategy. Paused time doesn't count. 0 disables this.
+    pub pomodoro_work_minutes: u32,
+}
+
+/// Configuration parameters for the `timer` command.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TimerConfig {
+    /// A shell command run with `sh -c` when a timer ends, e.g. `notify-send "$TKZR_TASK"`. The
+    /// task's description and category are passed in the `TKZR_TASK` and `TKZR_CATEGORY`
+    /// environment variables. If it is empty, no command is run.
+    pub notify_command: String,
+}
+
+/// Configuration parameters for automatic database backups.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// The number of backups to keep that are made before migrating the database to a new schema
+    /// version. They are written to a `backups` directory in the database directory, and the
+    /// oldest are removed. 0 disables them.
+    pub keep_before_migration: usize,
+}
+
+/// Configuration parameters for the trash.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TrashConfig {
+    /// The number of days removed tasks are kept in the trash before they are removed permanently.
+    /// 0 keeps them until the trash is emptied with `trash empty`.
+    pub retention_days: u32,
 }
 
 // creation and acquisition functions
@@ -80,6 +169,11 @@ impl Config {
         Config::default_config_dir().join("config.toml")
     }
 
+    /// The default location of the hooks directory, inside the config directory.
+    pub fn default_hooks_path() -> Option<PathBuf> {
+        Some(Config::default_config_dir().join("hooks"))
+    }
+
     /// Create config and data directory and write out default config.
     fn write_default_config() -> Result<Config, Error> {
         let default_config = Config::default();
@@ -118,9 +212,47 @@ impl Config {
 // getters
 impl Config {
     /// Get a connection to the database at the location specified by the config file.
+    /// Tasks that have been in the trash for longer than the retention period are removed.
     pub fn db(&self) -> Result<SqliteBackend, Error> {
-        SqliteBackend::open(&self.db_path)
-            .map_err(|e| format_err!("Could not acquire database connection. {}", e))
+        let mut db = SqliteBackend::open_with_backups(&self.db_path, self.backup.keep_before_migration)
+            .map_err(|e| format_err!("Could not acquire database connection. {}", e))?;
+
+        if self.trash.retention_days > 0 {
+            let removed_before = chrono::Utc::now() - chrono::Duration::days(i64::from(self.trash.retention_days));
+            let tx = db.transaction()?;
+            tx.empty_trash(Some(removed_before))
+                .map_err(|e| format_err!("Could not remove old tasks from trash. {}", e))?;
+            tx.finish()?;
+        }
+
+        Ok(db)
+    }
+
+    /// Get the `Hooks` in the hooks directory specified by the config file, if there is one.
+    pub fn hooks(&self) -> Option<Hooks> {
+        self.hooks_path.as_ref().map(Hooks::new)
+    }
+
+    /// Get the `SelectionStrategy` specified by the config file.
+    pub fn selector(&self) -> Box<dyn SelectionStrategy> {
+        match self.selection.strategy {
+            StrategyKind::WeightedRandom => Box::new(WeightedRandom::new(self.break_cutoff)),
+            StrategyKind::Aging => {
+                let millis = (self.selection.aging_half_life_hours * 60.0 * 60.0 * 1000.0) as i64;
+                let half_life = chrono::Duration::milliseconds(std::cmp::max(millis, 1));
+                Box::new(Aging::new(self.break_cutoff, half_life))
+            }
+            StrategyKind::NoRepeat => {
+                Box::new(NoRepeat::new(self.break_cutoff, self.selection.no_repeat_window, self.selection.no_repeat_decay))
+            }
+            StrategyKind::Pomodoro => {
+                let work_time = match self.selection.pomodoro_work_minutes {
+                    0 => None,
+                    minutes => Some(chrono::Duration::minutes(i64::from(minutes))),
+                };
+                Box::new(Pomodoro::new(self.break_cutoff, self.selection.pomodoro_tasks_per_break, work_time))
+            }
+        }
     }
 }
 
@@ -138,6 +270,12 @@ impl FromStr for Config {
         if config.break_cutoff > 1.0 {
             return Err(format_err!("Parsed break probability was greater than 1: {}", config.break_cutoff));
         }
+        if config.selection.aging_half_life_hours <= 0.0 || config.selection.aging_half_life_hours.is_nan() {
+            return Err(format_err!("Parsed aging half-life was not positive: {}", config.selection.aging_half_life_hours));
+        }
+        if !(0.0..=1.0).contains(&config.selection.no_repeat_decay) {
+            return Err(format_err!("Parsed no-repeat decay was not between 0 and 1: {}", config.selection.no_repeat_decay));
+        }
 
         Ok(config)
     }
@@ -151,6 +289,32 @@ impl Default for Config {
         Config {
             db_path,
             break_cutoff,
+            selection: SelectionConfig::default(),
+            hooks_path: Config::default_hooks_path(),
+            timer: TimerConfig::default(),
+            backup: BackupConfig::default(),
+            trash: TrashConfig::default(),
+        }
+    }
+}
+
+impl Default for SelectionConfig {
+    fn default() -> SelectionConfig {
+        SelectionConfig {
+            strategy: StrategyKind::WeightedRandom,
+            aging_half_life_hours: DEFAULT_AGING_HALF_LIFE_HOURS,
+            no_repeat_window: DEFAULT_NO_REPEAT_WINDOW,
+            no_repeat_decay: DEFAULT_NO_REPEAT_DECAY,
+            pomodoro_tasks_per_break: DEFAULT_POMODORO_TASKS_PER_BREAK,
+            pomodoro_work_minutes: DEFAULT_POMODORO_WORK_MINUTES,
+        }
+    }
+}
+
+impl Default for TrashConfig {
+    fn default() -> TrashConfig {
+        TrashConfig {
+            retention_days: DEFAULT_TRASH_RETENTION_DAYS,
         }
     }
 }
@@ -180,6 +344,11 @@ impl Config {
         (temp_dir, Config {
             db_path,
             break_cutoff,
+            selection: SelectionConfig::default(),
+            hooks_path: None,
+            timer: TimerConfig::default(),
+            backup: BackupConfig::default(),
+            trash: TrashConfig::default(),
         })
     }
 }
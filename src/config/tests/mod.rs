@@ -33,6 +33,48 @@ fn test_config_parse_bad_prob_greater_than_1() {
     assert!(err.to_string().contains("Parsed break probability was greater than 1"));
 }
 
+#[test]
+/// Migration backups are disabled unless `[backup]` sets how many to keep.
+fn test_config_parse_backup() {
+    let s = r#"
+        db_path = "/tmp/nowhere"
+        break_cutoff = 0.5
+    "#;
+    let config = Config::from_str(s).expect("Config without backup section failed to parse");
+    assert_eq!(config.backup.keep_before_migration, 0);
+
+    let s = r#"
+        db_path = "/tmp/nowhere"
+        break_cutoff = 0.5
+
+        [backup]
+        keep_before_migration = 3
+    "#;
+    let config = Config::from_str(s).expect("Config with backup section failed to parse");
+    assert_eq!(config.backup.keep_before_migration, 3);
+}
+
+#[test]
+/// Removed tasks are kept in the trash for 30 days unless `[trash]` sets the retention period.
+fn test_config_parse_trash() {
+    let s = r#"
+        db_path = "/tmp/nowhere"
+        break_cutoff = 0.5
+    "#;
+    let config = Config::from_str(s).expect("Config without trash section failed to parse");
+    assert_eq!(config.trash.retention_days, 30);
+
+    let s = r#"
+        db_path = "/tmp/nowhere"
+        break_cutoff = 0.5
+
+        [trash]
+        retention_days = 0
+    "#;
+    let config = Config::from_str(s).expect("Config with trash section failed to parse");
+    assert_eq!(config.trash.retention_days, 0);
+}
+
 #[test]
 /// Make a TempHome, create a config in default directory, check config file is there.
 fn test_config_new_in_default() {
@@ -3,12 +3,17 @@ use std::path::PathBuf;
 
 use tempfile::{tempdir, TempDir};
 
-use super::super::Config;
+use super::super::{Config, SelectionConfig, TimerConfig, BackupConfig, TrashConfig};
 
 pub fn example_custom_config() -> Config {
     Config {
         db_path: PathBuf::from("/tmp/nowhere"),
         break_cutoff: 0.1,
+        hooks_path: None,
+        selection: SelectionConfig::default(),
+        timer: TimerConfig::default(),
+        backup: BackupConfig::default(),
+        trash: TrashConfig::default(),
     }
 }
 
@@ -1,16 +1,20 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use failure::Error;
-use rusqlite::NO_PARAMS;
+use rusqlite::{NO_PARAMS, OptionalExtension};
 use rusqlite::Result as SQLResult;
 use uuid::Uuid;
 
-use crate::db::DBMetadata;
+use crate::db::{DBMetadata, DBProblem, RemovalSource, SearchResult, TrashedTask};
 use crate::db::{SqliteTransaction, DBTransaction};
-use crate::db::transaction::SqlBlobUuid;
+use crate::db::transaction::{SqlBlobUuid, session_from_row};
 
-use crate::selection::SelectionStrategy;
+use crate::journal::{JournalEntry, JournalState, JOURNAL_LENGTH};
+use crate::selection::{SelectionStrategy, SelectionContext, TaskStats, BreakStats, select_category_and_task};
 
 use crate::sync::{USetOp, USetOpMsg, ReplicaUuid};
-use crate::task::{Category, Task};
+use crate::timer::TimerState;
+use crate::tracking::Session;
+use crate::task::{Category, Task, TaskDetails};
 
 
 pub trait DBBackend {
@@ -27,6 +31,10 @@ pub trait DBBackend {
     /// database.  This function should never return None if there are tasks in the database.
     fn fetch_current_task(&self) -> Result<Option<Task>, Error>;
 
+    /// Returns the `SelectionContext` describing the current state of the database, which is
+    /// passed to a `SelectionStrategy` before it selects a task.
+    fn fetch_selection_context(&self) -> Result<SelectionContext, Error>;
+
     /// Select a new current task according to the `SelectionStrategy` passed in as `selector`. If
     /// there are no tasks of one type, it will use the other. If there are both, the
     /// `SelectionStrategy` will choose one. If there are no tasks in the database, do nothing.
@@ -39,11 +47,77 @@ pub trait DBBackend {
     /// leave the database without a current task.
     fn complete_current_task(&self) -> Result<Option<Task>, Error>;
 
-    /// Remove a task from the database. If the task was set as the current task, it is unset as
-    /// the current task and returned. Otherwise, the result is `Ok(None)`.
+    /// Add a note to a completed task in the trash, e.g. how it went, which is searched along with
+    /// its description. Returns false if the task isn't in the trash.
+    fn set_completion_note(&self, uuid: &Uuid, note: &str) -> Result<bool, Error>;
+
+    /// Remove a task from the database, moving it to the trash with the reason it was removed. If
+    /// the task was set as the current task, it is unset as the current task and returned.
+    /// Otherwise, the result is `Ok(None)`.
     ///
-    /// This is used for network sync and isn't exposed in a CLI command currently.
-    fn remove_task_by_uuid(&self, uuid: &Uuid) -> Result<Option<Task>, Error>;
+    /// This is used for network sync, and by commands that remove a task without completing it,
+    /// like `remove`.
+    fn remove_task_by_uuid(&self, uuid: &Uuid, source: &RemovalSource) -> Result<Option<Task>, Error>;
+
+    /// Permanently remove a task from the database without moving it to the trash, unsetting it
+    /// as the current task if it is. This is used when undoing the command that added it.
+    fn delete_task_by_uuid(&self, uuid: &Uuid) -> Result<(), Error>;
+
+    /// Replace the description, priority and category of the task with the same UUID as `task`,
+    /// keeping its statistics and whether it is the current task. Returns false if there is no
+    /// such task.
+    fn update_task(&self, task: &Task) -> Result<bool, Error>;
+
+    /// Replace the details of the task with the given UUID. Details are removed along with their
+    /// task.
+    fn set_task_details(&self, uuid: &Uuid, details: &TaskDetails) -> Result<(), Error>;
+
+    /// Return the details of the task with the given UUID. They are empty if none were set.
+    fn fetch_task_details(&self, uuid: &Uuid) -> Result<TaskDetails, Error>;
+
+    /// Make the task with the given UUID the current task without using a selection strategy,
+    /// e.g. when restoring a database. Returns false if there is no such task.
+    fn set_current_task_by_uuid(&self, uuid: &Uuid) -> Result<bool, Error>;
+
+    /// Remove every task along with the current task, the timer, task details, replicas and
+    /// unsynced operations. Selection history, break stats, work sessions and the journal are kept.
+    fn clear_all(&self) -> Result<(), Error>;
+
+    /// Run SQLite's integrity and foreign key checks, and check the invariants the rest of the
+    /// database code relies on, e.g. that there is a current task if there are tasks. Returns the
+    /// problems found.
+    fn check_database(&self) -> Result<Vec<DBProblem>, Error>;
+
+    /// Fix the fixable problems found by `check_database` by removing invalid and orphaned rows,
+    /// then selecting a new current task if there isn't one.
+    fn repair_database(&self, selector: &mut dyn SelectionStrategy) -> Result<(), Error>;
+
+    /// Return the tasks in the trash, most recently removed first.
+    fn fetch_trash(&self) -> Result<Vec<TrashedTask>, Error>;
+
+    /// Add the task with the given UUID back from the trash along with its details, and remove it
+    /// from the trash. Returns `None` if it isn't in the trash.
+    fn restore_from_trash(&self, uuid: &Uuid) -> Result<Option<TrashedTask>, Error>;
+
+    /// Put a task back in the trash as it was before it was restored, with its original reason
+    /// and date of removal, replacing any other copy of it in the trash.
+    fn return_to_trash(&self, trashed: &TrashedTask) -> Result<(), Error>;
+
+    /// Permanently remove tasks from the trash that were removed before `removed_before`, or every
+    /// task in the trash if it is `None`. Returns the number of tasks removed.
+    fn empty_trash(&self, removed_before: Option<DateTime<Utc>>) -> Result<usize, Error>;
+
+    /// Search the descriptions of the tasks on the task list and in the trash with an FTS5 query,
+    /// e.g. `"call"* dentist`. Returns at most `limit` results, most relevant first.
+    fn search_tasks(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, Error>;
+
+    /// Record a command that can be undone in the journal. Only the most recent `JOURNAL_LENGTH`
+    /// entries are kept.
+    fn store_journal_entry(&self, entry: &JournalEntry) -> Result<(), Error>;
+
+    /// Remove the most recent entry from the journal and return it, or `None` if the journal is
+    /// empty.
+    fn pop_journal_entry(&self) -> Result<Option<JournalEntry>, Error>;
 
     /// Store an unsynced `USetOpMsg` in the database to transmit later.
     fn store_uset_op_msg(&self, uset_op_msg: &USetOpMsg) -> Result<(), Error>;
@@ -61,18 +135,73 @@ pub trait DBBackend {
     /// Add a server to the replica set. This does not do any network communication; it just stores
     /// the data.
     // TODO maybe use an actual url type for the url parameter?
-    fn store_replica_server(&mut self, replica_id: &ReplicaUuid, api_url: &str) -> Result<(), Error>;
+    fn store_replica_server(&self, replica_id: &ReplicaUuid, api_url: &str) -> Result<(), Error>;
 
     /// Fetch all replicas from db. Returns a Vec of `(replica_id, Option<api_url>)`.
     // TODO maybe use an actual url type for the urls, use ReplicaServer type instead of tuple
     fn fetch_replicas(&self) -> Result<Vec<(ReplicaUuid, Option<String>)>, Error>;
 
+    /// Store the running timer, replacing any existing one.
+    fn store_timer(&self, timer: &TimerState) -> Result<(), Error>;
+
+    /// Fetch the running timer, if there is one. The timer may have already ended.
+    fn fetch_timer(&self) -> Result<Option<TimerState>, Error>;
+
+    /// Remove the running timer, if there is one.
+    fn clear_timer(&self) -> Result<(), Error>;
+
+    /// Stop tracking time on the current task without changing the current task. Returns false if
+    /// time was not being tracked.
+    fn pause_session(&self) -> Result<bool, Error>;
+
+    /// Start tracking time on the current task again after `pause_session`. Returns false if time
+    /// was already being tracked or there is no current task.
+    fn resume_session(&self) -> Result<bool, Error>;
+
+    /// Return all work sessions, oldest first. The last session may still be running.
+    fn fetch_sessions(&self) -> Result<Vec<Session>, Error>;
+
+    /// Start a savepoint named `name` within the transaction. Changes made after it can be undone
+    /// with `rollback_to_savepoint` without affecting the rest of the transaction.
+    fn savepoint(&self, name: &str) -> Result<(), Error>;
+
+    /// Keep the changes made since the savepoint `name` as part of the transaction, and forget the
+    /// savepoint.
+    fn release_savepoint(&self, name: &str) -> Result<(), Error>;
+
+    /// Undo the changes made since the savepoint `name`, and forget the savepoint.
+    fn rollback_to_savepoint(&self, name: &str) -> Result<(), Error>;
+
     /// Finish database operations, committing to the database. If this is not called, the
     /// transaction is rolled back.
     fn finish(self) -> Result<(), Error>;
 
 }
 
+impl<'conn> SqliteTransaction<'conn> {
+    /// Unset the current task and move it to the trash, returning it. This is the part of
+    /// completing a task that is shared with removing the current task during sync.
+    fn remove_current_task(&self, source: &RemovalSource) -> Result<Option<Task>, Error> {
+        let tx = self;
+
+        let current_opt = tx.pop_current_task()
+            .map_err(|e| format_err!("Failed to pop current task during transaction: {}", e))?;
+        let (current_task_id, current_task) = match current_opt {
+            Some((id, task)) => (id, task),
+            None => return Ok(None),
+        };
+        tx.stop_session()
+            .map_err(|e| format_err!("Failed to stop session during transaction: {}", e))?;
+
+        tx.move_to_trash(&current_task, source)
+            .map_err(|e| format_err!("Failed to move task to trash during transaction: {}", e))?;
+        tx.remove_task(&current_task_id)
+            .map_err(|e| format_err!("Failed to remove task during transaction: {}", e))?;
+
+        Ok(Some(current_task))
+    }
+}
+
 // TODO currently we just format_err into essentially error strings because all we will do is
 // display the error string to the user anyway, but it may be useful at some point (eg syncing over
 // the network) to make a db error type so that we can distinguish them - eg retrying a network
@@ -112,14 +241,18 @@ impl<'conn> DBBackend for SqliteTransaction<'conn> {
         let uuid_bytes: &[u8] = task.uuid().as_bytes();
 
         tx.execute_named(
-            "INSERT INTO tasks (task, priority, category, uuid) VALUES (:task, :priority, :category, :uuid)",
+            "INSERT INTO tasks (task, priority, category, uuid, date_added)
+            VALUES (:task, :priority, :category, :uuid, :date_added)",
             &[(":task", &task.task()),
               (":priority", &task.priority()),
               (":category", &task.is_break()),
-              (":uuid", &uuid_bytes)
+              (":uuid", &uuid_bytes),
+              (":date_added", &Utc::now()),
             ],
         ).map_err(|e| format_err!("Error inserting task into database: {}", e))?;
-        Ok(())
+
+        // a task that is added again, e.g. by undo or sync, is no longer in the trash
+        self.delete_from_trash(task.uuid())
     }
 
     fn fetch_all_tasks(&self) -> Result<Vec<Task>, Error> {
@@ -176,6 +309,30 @@ impl<'conn> DBBackend for SqliteTransaction<'conn> {
         Ok(Some(current_task))
     }
 
+    fn fetch_selection_context(&self) -> Result<SelectionContext, Error> {
+        let task_stats = self.fetch_task_stats()
+            .map_err(|e| format_err!("Failed to get task stats during transaction: {}", e))?;
+        let recent_selections = self.fetch_recent_selections()
+            .map_err(|e| format_err!("Failed to get selection history during transaction: {}", e))?;
+        let break_stats = self.fetch_break_stats()
+            .map_err(|e| format_err!("Failed to get break stats during transaction: {}", e))?;
+
+        let now = Utc::now();
+        let work_since_break = self.fetch_sessions_since(&break_stats.last_break)
+            .map_err(|e| format_err!("Failed to get work sessions during transaction: {}", e))?
+            .iter()
+            .filter(|session| session.category == Category::Task)
+            .fold(Duration::zero(), |total, session| total + session.duration_within(break_stats.last_break, now, now));
+
+        Ok(SelectionContext {
+            now,
+            task_stats,
+            recent_selections,
+            break_stats,
+            work_since_break,
+        })
+    }
+
     fn select_current_task(&self, selector: &mut dyn SelectionStrategy) -> Result<(), Error> {
         let tx = self;
 
@@ -185,34 +342,28 @@ impl<'conn> DBBackend for SqliteTransaction<'conn> {
         let breaks = tx.fetch_breaks()
             .map_err(|e| format_err!("Failed to get break tasks during transaction: {}", e))?;
 
+        let context = tx.fetch_selection_context()
+            .map_err(|e| format_err!("Failed to get selection context during transaction: {}", e))?;
+        selector.set_context(&context);
+
+        let tasks_refs: Vec<&Task> = tasks.iter().map(|t| &t.1).collect();
+        let breaks_refs: Vec<&Task> = breaks.iter().map(|t| &t.1).collect();
+
         // If there are no tasks or no breaks, must select the other unless there are none
         // If there are some of both, use the selection strategy
-        let selected_tasks = match (tasks.len(), breaks.len()) {
+        let (selected_task_id, selected_task) = match select_category_and_task(selector, &tasks_refs, &breaks_refs) {
             // None of either => no tasks in db, so there cannot be a current task.
-            (0, 0) => return Ok(()),
-            // no tasks, use breaks
-            (0, _) => breaks,
-            (_, 0) => tasks,
-            (_, _) => {
-                let category = selector.select_category();
-                match category {
-                    Category::Break => {
-                        breaks
-                    },
-                    Category::Task => {
-                        tasks
-                    }
-                }
-            }
+            None => return Ok(()),
+            Some((Category::Break, idx)) => &breaks[idx],
+            Some((Category::Task, idx)) => &tasks[idx],
         };
 
-        let tasks_refs: Vec<&Task> = selected_tasks.iter().map(|t| &t.1).collect();
-
-        let selected_task_idx = selector.select_task(&tasks_refs);
-
-        tx.set_current_task(&selected_tasks[selected_task_idx].0)
+        tx.set_current_task(selected_task_id)
             .map_err(|e| format_err!("Failed to set current task during transaction: {}", e))?;
-
+        tx.record_selection(selected_task.uuid())
+            .map_err(|e| format_err!("Failed to record selection during transaction: {}", e))?;
+        tx.start_session(selected_task)
+            .map_err(|e| format_err!("Failed to start session during transaction: {}", e))?;
 
         Ok(())
     }
@@ -228,6 +379,21 @@ impl<'conn> DBBackend for SqliteTransaction<'conn> {
             Some((id, task)) => (id, task),
             None => return Ok(()),
         };
+        tx.stop_session()
+            .map_err(|e| format_err!("Failed to stop session during transaction: {}", e))?;
+
+        // Keep the date the task was added when we put it back, and record that it was skipped.
+        let old_stats = tx.fetch_task_stats()
+            .map_err(|e| format_err!("Failed to get task stats during transaction: {}", e))?
+            .remove(old_current_task.uuid())
+            .ok_or_else(|| format_err!("Current task has no task stats."))?;
+        let new_stats = TaskStats {
+            last_skipped: Some(Utc::now()),
+            ..old_stats
+        };
+        // Removing the task removes its details too, so they are put back along with it.
+        let details = tx.fetch_task_details(old_current_task.uuid())
+            .map_err(|e| format_err!("Failed to get task details during transaction: {}", e))?;
 
         // The reason we don't just call `complete_current_task` here is because at some point I
         // want to make that add tasks to a `completed` db table, but obviously I don't here.
@@ -239,6 +405,10 @@ impl<'conn> DBBackend for SqliteTransaction<'conn> {
 
         SqliteTransaction::add_task(self, &old_current_task)
             .map_err(|e| format_err!("Failed to add original task back to the db during transaction: {}", e))?;
+        tx.set_task_stats(old_current_task.uuid(), &new_stats)
+            .map_err(|e| format_err!("Failed to update skipped task's stats during transaction: {}", e))?;
+        tx.set_task_details(old_current_task.uuid(), &details)
+            .map_err(|e| format_err!("Failed to restore skipped task's details during transaction: {}", e))?;
 
         Ok(())
     }
@@ -248,37 +418,280 @@ impl<'conn> DBBackend for SqliteTransaction<'conn> {
     fn complete_current_task(&self) -> Result<Option<Task>, Error> {
         let tx = self;
 
-        let current_opt = tx.pop_current_task()
-            .map_err(|e| format_err!("Failed to pop current task during transaction: {}", e))?;
-        let (current_task_id, current_task) = match current_opt {
-            Some((id, task)) => (id, task),
+        let current_task = match tx.remove_current_task(&RemovalSource::Completed)? {
+            Some(task) => task,
             None => return Ok(None),
         };
 
-        tx.remove_task(&current_task_id)
-            .map_err(|e| format_err!("Failed to remove task during transaction: {}", e))?;
+        // Completing a break resets the break counters, and completing a task counts towards the
+        // next break.
+        let break_stats = tx.fetch_break_stats()
+            .map_err(|e| format_err!("Failed to get break stats during transaction: {}", e))?;
+        let break_stats = if current_task.is_break() {
+            BreakStats { tasks_completed: 0, last_break: Utc::now() }
+        }
+        else {
+            BreakStats { tasks_completed: break_stats.tasks_completed.saturating_add(1), ..break_stats }
+        };
+        tx.set_break_stats(&break_stats)
+            .map_err(|e| format_err!("Failed to update break stats during transaction: {}", e))?;
 
         Ok(Some(current_task))
     }
 
-    fn remove_task_by_uuid(&self, uuid: &Uuid) -> Result<Option<Task>, Error> {
+    fn set_completion_note(&self, uuid: &Uuid, note: &str) -> Result<bool, Error> {
+        self.set_trash_note(uuid, note)
+    }
+
+    fn remove_task_by_uuid(&self, uuid: &Uuid, source: &RemovalSource) -> Result<Option<Task>, Error> {
         let tx = self;
 
         let current_opt = DBBackend::fetch_current_task(tx)
             .map_err(|e| format_err!("Failed to get current task during USet remove operation: {}", e))?;
 
-        // If the task we're removing is the current task, use the complete operation
+        // If the task we're removing is the current task, unset it as the current task as well.
+        // It wasn't completed here, so the break stats are not updated.
         if let Some(current_task) = current_opt {
             if current_task.uuid() == uuid {
-                return tx.complete_current_task()
-                    .map_err(|e| format_err!("Failed to complete current task when removing task: {}", e));
+                return tx.remove_current_task(source)
+                    .map_err(|e| format_err!("Failed to remove current task when removing task: {}", e));
             }
         }
 
         // If there is no current task, remove task normally
+        let task = tx.fetch_tasks()?.into_iter().chain(tx.fetch_breaks()?).find(|(_, task)| task.uuid() == uuid);
+        if let Some((_, task)) = task {
+            tx.move_to_trash(&task, source)
+                .map_err(|e| format_err!("Failed to move task to trash when removing task: {}", e))?;
+        }
         DBTransaction::try_remove_task_by_uuid(tx, uuid).map(|_| None)
     }
 
+    fn delete_task_by_uuid(&self, uuid: &Uuid) -> Result<(), Error> {
+        let tx = self;
+
+        let current_opt = DBBackend::fetch_current_task(tx)
+            .map_err(|e| format_err!("Failed to get current task when deleting task: {}", e))?;
+        if current_opt.as_ref().map(|task| task.uuid()) == Some(uuid) {
+            tx.pop_current_task()
+                .map_err(|e| format_err!("Failed to pop current task when deleting task: {}", e))?;
+            tx.stop_session()
+                .map_err(|e| format_err!("Failed to stop session when deleting task: {}", e))?;
+        }
+
+        DBTransaction::try_remove_task_by_uuid(tx, uuid)
+    }
+
+    fn update_task(&self, task: &Task) -> Result<bool, Error> {
+        let tx = &self.transaction;
+        let uuid_bytes: &[u8] = task.uuid().as_bytes();
+
+        let rows = tx.execute_named(
+            "UPDATE tasks SET task = :task, priority = :priority, category = :category
+            WHERE uuid = :uuid",
+            &[(":task", &task.task()),
+              (":priority", &task.priority()),
+              (":category", &task.is_break()),
+              (":uuid", &uuid_bytes),
+            ],
+        ).map_err(|e| format_err!("Error updating task in database: {}", e))?;
+
+        Ok(rows > 0)
+    }
+
+    fn set_task_details(&self, uuid: &Uuid, details: &TaskDetails) -> Result<(), Error> {
+        let tx = &self.transaction;
+        let uuid_bytes: &[u8] = uuid.as_bytes();
+
+        for table in &["task_details", "task_tags"] {
+            tx.execute_named(
+                &format!("DELETE FROM {} WHERE task_uuid = :task_uuid", table),
+                &[(":task_uuid", &uuid_bytes)])
+                .map_err(|e| format_err!("Error clearing task details: {}", e))?;
+        }
+
+        if details.due.is_some() || details.estimate.is_some() {
+            tx.execute_named(
+                "INSERT INTO task_details (task_uuid, due, estimate)
+                VALUES (:task_uuid, :due, :estimate)",
+                &[(":task_uuid", &uuid_bytes),
+                  (":due", &details.due),
+                  (":estimate", &details.estimate.map(|e| e.num_seconds())),
+                ],
+            ).map_err(|e| format_err!("Error storing task details: {}", e))?;
+        }
+
+        for tag in &details.tags {
+            tx.execute_named(
+                "INSERT OR IGNORE INTO task_tags (task_uuid, tag) VALUES (:task_uuid, :tag)",
+                &[(":task_uuid", &uuid_bytes), (":tag", tag)],
+            ).map_err(|e| format_err!("Error storing task tag: {}", e))?;
+        }
+
+        Ok(())
+    }
+
+    fn fetch_task_details(&self, uuid: &Uuid) -> Result<TaskDetails, Error> {
+        let tx = &self.transaction;
+        let uuid_bytes: &[u8] = uuid.as_bytes();
+
+        let mut stmt = tx.prepare_cached(
+            "SELECT due, estimate FROM task_details WHERE task_uuid = :task_uuid")
+            .map_err(|e| format_err!("Error preparing task details query: {}", e))?;
+        let rows: Vec<SQLResult<(Option<NaiveDate>, Option<i64>)>> = stmt
+            .query_map_named(&[(":task_uuid", &uuid_bytes)], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format_err!("Error executing task details query: {}", e))?
+            .collect();
+        let (due, estimate) = match rows.into_iter().next() {
+            Some(row) => row.map_err(|e| format_err!("Error deserializing task details: {}", e))?,
+            None => (None, None),
+        };
+
+        let mut stmt = tx.prepare_cached(
+            "SELECT tag FROM task_tags WHERE task_uuid = :task_uuid ORDER BY tag")
+            .map_err(|e| format_err!("Error preparing task tags query: {}", e))?;
+        let tags = stmt
+            .query_map_named(&[(":task_uuid", &uuid_bytes)], |row| row.get(0))
+            .map_err(|e| format_err!("Error executing task tags query: {}", e))?
+            .collect::<SQLResult<Vec<String>>>()
+            .map_err(|e| format_err!("Error deserializing task tags: {}", e))?;
+
+        Ok(TaskDetails {
+            tags,
+            due,
+            estimate: estimate.map(Duration::seconds),
+        })
+    }
+
+    fn set_current_task_by_uuid(&self, uuid: &Uuid) -> Result<bool, Error> {
+        let tx = self;
+
+        let task = match tx.fetch_tasks()?.into_iter().chain(tx.fetch_breaks()?).find(|(_, task)| task.uuid() == uuid) {
+            Some(task) => task,
+            None => return Ok(false),
+        };
+
+        if tx.pop_current_task()
+            .map_err(|e| format_err!("Failed to pop current task during transaction: {}", e))?
+            .is_some() {
+            tx.stop_session()
+                .map_err(|e| format_err!("Failed to stop session during transaction: {}", e))?;
+        }
+
+        tx.set_current_task(&task.0)
+            .map_err(|e| format_err!("Failed to set current task during transaction: {}", e))?;
+        tx.start_session(&task.1)
+            .map_err(|e| format_err!("Failed to start session during transaction: {}", e))?;
+
+        Ok(true)
+    }
+
+    fn clear_all(&self) -> Result<(), Error> {
+        self.stop_session()
+            .map_err(|e| format_err!("Failed to stop session during transaction: {}", e))?;
+
+        // current refers to tasks and servers refers to replicas, so they are cleared first
+        self.transaction.execute_batch(
+            "DELETE FROM current;
+             DELETE FROM timer;
+             DELETE FROM task_details;
+             DELETE FROM task_tags;
+             DELETE FROM tasks;
+             DELETE FROM unsynced_ops;
+             DELETE FROM servers;
+             DELETE FROM replicas;")
+            .map_err(|e| format_err!("Error clearing database: {}", e))?;
+
+        Ok(())
+    }
+
+    fn check_database(&self) -> Result<Vec<DBProblem>, Error> {
+        self.find_problems()
+    }
+
+    fn repair_database(&self, selector: &mut dyn SelectionStrategy) -> Result<(), Error> {
+        self.repair_problems(selector)
+    }
+
+    fn fetch_trash(&self) -> Result<Vec<TrashedTask>, Error> {
+        self.fetch_trashed_tasks()
+    }
+
+    fn restore_from_trash(&self, uuid: &Uuid) -> Result<Option<TrashedTask>, Error> {
+        let trashed = match self.fetch_trashed_tasks()?.into_iter().find(|trashed| trashed.task.uuid() == uuid) {
+            Some(trashed) => trashed,
+            None => return Ok(None),
+        };
+
+        // adding the task removes it from the trash
+        self.add_task(&trashed.task)
+            .map_err(|e| format_err!("Failed to add task back from trash: {}", e))?;
+        self.set_task_details(uuid, &trashed.details)
+            .map_err(|e| format_err!("Failed to restore details of task from trash: {}", e))?;
+
+        Ok(Some(trashed))
+    }
+
+    fn return_to_trash(&self, trashed: &TrashedTask) -> Result<(), Error> {
+        self.insert_into_trash(&trashed.task, &trashed.details, &trashed.source, &trashed.date_removed,
+                               trashed.note.as_deref())
+    }
+
+    fn empty_trash(&self, removed_before: Option<DateTime<Utc>>) -> Result<usize, Error> {
+        self.delete_trashed_tasks(removed_before)
+    }
+
+    fn search_tasks(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, Error> {
+        self.search(query, limit)
+    }
+
+    fn store_journal_entry(&self, entry: &JournalEntry) -> Result<(), Error> {
+        let tx = &self.transaction;
+
+        tx.execute_named(
+            "INSERT INTO journal (command, date, before, after) VALUES (:command, :date, :before, :after)",
+            &[(":command", &entry.command),
+              (":date", &entry.date),
+              (":before", &entry.before.to_json()?),
+              (":after", &entry.after.to_json()?)])
+            .map_err(|e| format_err!("Error recording command in journal: {}", e))?;
+
+        // ids are monotonically increasing, so this removes everything but the most recent
+        tx.execute_named(
+            "DELETE FROM journal
+            WHERE
+                id <= (SELECT MAX(id) FROM journal) - :length",
+            &[(":length", &JOURNAL_LENGTH)])
+            .map_err(|e| format_err!("Error pruning journal: {}", e))?;
+
+        Ok(())
+    }
+
+    fn pop_journal_entry(&self) -> Result<Option<JournalEntry>, Error> {
+        let tx = &self.transaction;
+
+        let row = tx.query_row(
+            "SELECT id, command, date, before, after FROM journal ORDER BY id DESC LIMIT 1",
+            NO_PARAMS,
+            |row| Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?, row.get::<_, String>(3)?, row.get::<_, String>(4)?)))
+            .optional()
+            .map_err(|e| format_err!("Error reading journal: {}", e))?;
+
+        let (id, command, date, before, after) = match row {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+        tx.execute("DELETE FROM journal WHERE id = ?1", &[&id])
+            .map_err(|e| format_err!("Error removing entry from journal: {}", e))?;
+
+        Ok(Some(JournalEntry {
+            command,
+            date,
+            before: JournalState::from_json(&before)?,
+            after: JournalState::from_json(&after)?,
+        }))
+    }
+
     fn store_uset_op_msg(&self, uset_op_msg: &USetOpMsg) -> Result<(), Error> {
         let tx = &self.transaction;
         let replica_uuid_bytes: &[u8] = uset_op_msg.deliver_to.as_bytes();
@@ -388,28 +801,26 @@ impl<'conn> DBBackend for SqliteTransaction<'conn> {
         Ok(())
     }
 
-    fn store_replica_server(&mut self, replica_id: &ReplicaUuid, api_url: &str) -> Result<(), Error> {
-        let tx = &mut self.transaction;
+    fn store_replica_server(&self, replica_id: &ReplicaUuid, api_url: &str) -> Result<(), Error> {
         // NOTE: if inserting into the servers table fails, we want to roll back the insert into
         // the replica table as well. so we use a savepoint here.
-        let mut sp = tx.savepoint()?;
+        self.savepoint("store_replica_server")?;
 
         let uuid_bytes: &[u8] = replica_id.as_bytes();
-        sp.execute_named(
+        let res = self.transaction.execute_named(
             "INSERT INTO replicas (replica_uuid) VALUES (:replica_uuid)",
             &[(":replica_uuid", &uuid_bytes),
             ],
-        ).map_err(|e| format_err!("Error inserting server id into database: {}", e))?;
-
-        let res = sp.execute_named(
+        ).map_err(|e| format_err!("Error inserting server id into database: {}", e))
+        .and_then(|_| self.transaction.execute_named(
             "INSERT INTO servers (api_url, replica_id) VALUES (:api_url, last_insert_rowid())",
             &[(":api_url", &api_url),
             ],
-        ).map_err(|e| format_err!("Error inserting server url into database: {}", e));
+        ).map_err(|e| format_err!("Error inserting server url into database: {}", e)));
 
         match res {
-            Ok(_) => {sp.commit()?; return Ok(())},
-            Err(e) => {sp.rollback()?; return Err(e)},
+            Ok(_) => self.release_savepoint("store_replica_server"),
+            Err(e) => {self.rollback_to_savepoint("store_replica_server")?; Err(e)},
         }
     }
 
@@ -439,6 +850,120 @@ impl<'conn> DBBackend for SqliteTransaction<'conn> {
         Ok(replicas)
     }
 
+    fn store_timer(&self, timer: &TimerState) -> Result<(), Error> {
+        let tx = &self.transaction;
+        let uuid_bytes: &[u8] = timer.task_uuid.as_bytes();
+
+        tx.execute_named(
+            "REPLACE INTO timer (id, task_uuid, started, ends)
+            VALUES (1, :task_uuid, :started, :ends)",
+            &[(":task_uuid", &uuid_bytes),
+              (":started", &timer.started),
+              (":ends", &timer.ends),
+            ],
+        ).map_err(|e| format_err!("Error storing timer in database: {}", e))?;
+        Ok(())
+    }
+
+    fn fetch_timer(&self) -> Result<Option<TimerState>, Error> {
+        let tx = &self.transaction;
+
+        let mut stmt = tx.prepare_cached(
+            "SELECT task_uuid, started, ends
+            FROM timer
+            WHERE id = 1
+            ")
+            .map_err(|e| format_err!("Error preparing timer query: {}", e))?;
+
+        let rows: Vec<SQLResult<TimerState>> = stmt.query_map(NO_PARAMS, |row| {
+                let sql_uuid: SqlBlobUuid = row.get(0)?;
+                Ok(TimerState {
+                    task_uuid: sql_uuid.uuid,
+                    started: row.get(1)?,
+                    ends: row.get(2)?,
+                })
+             })
+            .map_err(|e| format_err!("Error executing timer query: {}", e))?
+            .collect();
+
+        // the id = 1 check means there is at most one row
+        match rows.into_iter().next() {
+            Some(row) => Ok(Some(row.map_err(|e| format_err!("Error deserializing timer row from database: {}", e))?)),
+            None => Ok(None),
+        }
+    }
+
+    fn clear_timer(&self) -> Result<(), Error> {
+        let tx = &self.transaction;
+
+        tx.execute("DELETE FROM timer", NO_PARAMS)
+            .map_err(|e| format_err!("Error clearing timer: {}", e))?;
+        Ok(())
+    }
+
+    fn pause_session(&self) -> Result<bool, Error> {
+        self.stop_session()
+            .map_err(|e| format_err!("Failed to stop session during transaction: {}", e))
+    }
+
+    fn resume_session(&self) -> Result<bool, Error> {
+        let tx = self;
+
+        let open_session = tx.fetch_open_session()
+            .map_err(|e| format_err!("Failed to get open session during transaction: {}", e))?;
+        if open_session.is_some() {
+            return Ok(false);
+        }
+
+        let current_task = match DBBackend::fetch_current_task(tx)? {
+            Some(task) => task,
+            None => return Ok(false),
+        };
+        tx.start_session(&current_task)
+            .map_err(|e| format_err!("Failed to start session during transaction: {}", e))?;
+
+        Ok(true)
+    }
+
+    fn fetch_sessions(&self) -> Result<Vec<Session>, Error> {
+        let tx = &self.transaction;
+
+        let mut stmt = tx.prepare_cached(
+            "SELECT task_uuid, task, category, started, stopped
+            FROM sessions
+            ORDER BY id
+            ")
+            .map_err(|e| format_err!("Error preparing sessions query: {}", e))?;
+
+        let sessions: SQLResult<Vec<Session>> = stmt.query_map(NO_PARAMS, session_from_row)
+            .map_err(|e| format_err!("Error executing sessions query: {}", e))?
+            .collect();
+
+        sessions.map_err(|e| format_err!("Error deserializing session row from database: {}", e))
+    }
+
+    fn savepoint(&self, name: &str) -> Result<(), Error> {
+        let tx = &self.transaction;
+
+        // savepoint names can't be parameters
+        tx.execute_batch(&format!("SAVEPOINT \"{}\";", name))
+            .map_err(|e| format_err!("Error starting savepoint {}: {}", name, e))
+    }
+
+    fn release_savepoint(&self, name: &str) -> Result<(), Error> {
+        let tx = &self.transaction;
+
+        tx.execute_batch(&format!("RELEASE SAVEPOINT \"{}\";", name))
+            .map_err(|e| format_err!("Error releasing savepoint {}: {}", name, e))
+    }
+
+    fn rollback_to_savepoint(&self, name: &str) -> Result<(), Error> {
+        let tx = &self.transaction;
+
+        // rolling back leaves the savepoint open, so release it as well
+        tx.execute_batch(&format!("ROLLBACK TO SAVEPOINT \"{}\"; RELEASE SAVEPOINT \"{}\";", name, name))
+            .map_err(|e| format_err!("Error rolling back to savepoint {}: {}", name, e))
+    }
 
     fn finish(self) -> Result<(), Error> {
         self.commit()
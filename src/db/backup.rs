@@ -0,0 +1,141 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::Utc;
+use rusqlite::{Connection, OpenFlags, NO_PARAMS};
+use rusqlite::backup::{Backup, StepResult};
+use failure::Error;
+
+use crate::db::{SqliteBackend, DBMetadata, DB_FILENAME, SCHEMA_VERSION};
+
+/// The directory inside the database directory that backups made before migrations are written
+/// to.
+pub const MIGRATION_BACKUP_DIR: &str = "backups";
+
+/// How long a backup waits between steps, which lets other processes write to the database while
+/// it is being copied.
+const BACKUP_PAUSE: Duration = Duration::from_millis(10);
+
+/// How long a restore waits for other processes to finish writing to the database.
+const RESTORE_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Copy the database open in `from` to a new file at `path` with SQLite's online backup API.
+fn copy_database(from: &Connection, path: &Path) -> Result<(), Error> {
+    if path.exists() {
+        return Err(format_err!("{} already exists.", path.display()));
+    }
+
+    let mut to = Connection::open(path)
+        .map_err(|e| format_err!("Could not create {}: {}", path.display(), e))?;
+    Backup::new(from, &mut to)
+        .and_then(|backup| backup.run_to_completion(100, BACKUP_PAUSE, None))
+        .map_err(|e| format_err!("Could not copy database to {}: {}", path.display(), e))
+}
+
+// Backup and restore impls
+impl SqliteBackend {
+    /// Copy the database to a new file at `path`. This uses SQLite's online backup API, so unlike
+    /// copying the database file, it is safe while other processes are writing to the database.
+    pub fn backup_to<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        copy_database(&self.connection, path.as_ref())
+    }
+
+    /// Check that the file at `path` is a taskerizer database that can be restored: it passes
+    /// SQLite's quick check, has metadata, and its schema version is not newer than
+    /// `SCHEMA_VERSION`. Returns its schema version and metadata.
+    pub fn check_backup<P: AsRef<Path>>(path: P) -> Result<(u32, DBMetadata), Error> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(format_err!("Backup file {} does not exist.", path.display()));
+        }
+
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| format_err!("Could not open backup {}: {}", path.display(), e))?;
+
+        let check: String = conn.query_row("PRAGMA quick_check", NO_PARAMS, |row| row.get(0))
+            .map_err(|e| format_err!("{} is not a taskerizer database: {}", path.display(), e))?;
+        if check != "ok" {
+            return Err(format_err!("Backup {} is corrupt: {}", path.display(), check));
+        }
+
+        let version: i64 = conn.query_row("PRAGMA user_version", NO_PARAMS, |row| row.get(0))
+            .map_err(|e| format_err!("Could not read schema version of backup {}: {}", path.display(), e))?;
+        if version > i64::from(SCHEMA_VERSION) {
+            return Err(format_err!("Backup schema version {} is newer than the version supported by this version of taskerizer ({}).",
+                                   version, SCHEMA_VERSION));
+        }
+
+        let metadata = conn.query_row(
+            "SELECT version, date_created FROM metadata WHERE id = 1",
+            NO_PARAMS,
+            |row| Ok(DBMetadata {
+                version: row.get(0)?,
+                date_created: row.get(1)?,
+            })
+        ).map_err(|e| format_err!("{} is not a taskerizer database: {}", path.display(), e))?;
+
+        Ok((version as u32, metadata))
+    }
+
+    /// Replace the database in the directory `db_dir` with the backup at `path`, after checking it
+    /// with `check_backup`. Returns the metadata of the backup.
+    ///
+    /// The backup is copied into the database with SQLite's online backup API in a single step,
+    /// which holds a write lock on the database for the whole copy, so other processes, including
+    /// ones that already have it open, see either the old database or the restored one. The
+    /// restored database is migrated the next time it is opened.
+    pub fn restore<P: AsRef<Path>, Q: AsRef<Path>>(db_dir: P, path: Q) -> Result<DBMetadata, Error> {
+        let (_, metadata) = SqliteBackend::check_backup(&path)?;
+
+        let backup = Connection::open_with_flags(path.as_ref(), OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| format_err!("Could not open backup {}: {}", path.as_ref().display(), e))?;
+        let mut current = Connection::open(db_dir.as_ref().join(DB_FILENAME))
+            .map_err(|e| format_err!("Could not open database: {}", e))?;
+        current.busy_timeout(RESTORE_BUSY_TIMEOUT)
+            .map_err(|e| format_err!("Could not open database: {}", e))?;
+
+        // copying every page at once means the database is never left half restored. the busy
+        // timeout applies to the database being written to, so this waits for other processes.
+        let restore = Backup::new(&backup, &mut current)
+            .map_err(|e| format_err!("Could not restore backup: {}", e))?;
+        match restore.step(-1) {
+            Ok(StepResult::Done) => Ok(metadata),
+            Ok(StepResult::Busy) | Ok(StepResult::Locked) => {
+                Err(format_err!("Could not restore backup: the database is being used by another process."))
+            }
+            Ok(StepResult::More) => unreachable!("Restoring copied only part of the backup"),
+            Err(e) => Err(format_err!("Could not replace database with backup: {}", e)),
+        }
+    }
+
+    /// Back up the database at schema version `version` into the `MIGRATION_BACKUP_DIR` directory
+    /// inside `db_dir` before it is migrated, then remove the oldest backups there so that at most
+    /// `keep` are left.
+    pub(super) fn backup_before_migration(&self, db_dir: &Path, version: u32, keep: usize) -> Result<(), Error> {
+        let dir = db_dir.join(MIGRATION_BACKUP_DIR);
+        fs::create_dir_all(&dir)
+            .map_err(|e| format_err!("Could not create backup directory {}: {}", dir.display(), e))?;
+
+        // the time comes first so that sorting the names sorts the backups from oldest to newest
+        let name = format!("tkzr_sqlite3.{}.v{}.db", Utc::now().format("%Y%m%dT%H%M%S%.6fZ"), version);
+        self.backup_to(dir.join(name))
+            .map_err(|e| format_err!("Could not back up database before migrating it: {}", e))?;
+
+        let mut backups: Vec<PathBuf> = fs::read_dir(&dir)
+            .map_err(|e| format_err!("Could not read backup directory {}: {}", dir.display(), e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.file_name().and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("tkzr_sqlite3.") && name.ends_with(".db")))
+            .collect();
+        backups.sort();
+
+        let old = backups.len().saturating_sub(keep);
+        for path in &backups[..old] {
+            fs::remove_file(path)
+                .map_err(|e| format_err!("Could not remove old backup {}: {}", path.display(), e))?;
+        }
+
+        Ok(())
+    }
+}
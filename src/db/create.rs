@@ -5,32 +5,52 @@ use rusqlite::{Connection, NO_PARAMS};
 use failure::Error;
 
 use crate::db::SqliteBackend;
+use crate::db::search::SEARCH_INDEXES;
+use crate::db::SCHEMA_VERSION;
+
+/// The name of the database file inside the database directory.
+pub const DB_FILENAME: &str = "tkzr_sqlite3.db";
 
 // Open impls
 impl SqliteBackend {
     /// Creates a taskerizer database at the given path if it does not exist, and opens and returns
     /// an existing one if there already was one. Path must be a directory and not a file.
     pub fn open<P: AsRef<Path>>(path: P) -> Result<SqliteBackend, Error> {
-        let mut path = path.as_ref().to_path_buf();
+        SqliteBackend::open_with_backups(path, 0)
+    }
+
+    /// Like `open`, but if an existing database needs to be migrated, it is first backed up into
+    /// the `backups` directory inside `path`, keeping at most `keep_backups` backups there. No
+    /// backup is made if `keep_backups` is 0.
+    pub fn open_with_backups<P: AsRef<Path>>(path: P, keep_backups: usize) -> Result<SqliteBackend, Error> {
+        let db_dir = path.as_ref();
+        let mut path = db_dir.to_path_buf();
         
         if !path.is_dir() {
             return Err(format_err!("Database directory path \"{}\" is not a directory, or we do not have permission to access it.",
                                    path.to_string_lossy()));
         }
 
-        path.push("tkzr_sqlite3.db");
+        path.push(DB_FILENAME);
 
         let existing_db = path.is_file();
 
         let conn = Connection::open(path)?;
-        let db = SqliteBackend {
+        let mut db = SqliteBackend {
             connection: conn,
         };
 
-        // create db tables and populate metadata table
+        // create db tables and populate metadata table, or bring an existing db up to date
         if !existing_db {
             db.create_tables()?;
         }
+        else {
+            let version = db.schema_version()?;
+            if keep_backups > 0 && version < SCHEMA_VERSION {
+                db.backup_before_migration(db_dir, version, keep_backups)?;
+            }
+            db.migrate()?;
+        }
         Ok(db)
     }
 
@@ -60,7 +80,16 @@ impl SqliteBackend {
         self.create_replicas_table()?;
         self.create_servers_table()?;
         self.create_unsynced_ops_table()?;
+        SqliteBackend::create_selection_history_table(&self.connection)?;
+        SqliteBackend::create_break_stats_table(&self.connection)?;
+        SqliteBackend::create_timer_table(&self.connection)?;
+        SqliteBackend::create_sessions_table(&self.connection)?;
+        SqliteBackend::create_task_details_tables(&self.connection)?;
+        SqliteBackend::create_journal_table(&self.connection)?;
+        SqliteBackend::create_trash_table(&self.connection)?;
+        SqliteBackend::create_search_tables(&self.connection)?;
         //self.create_completed_table()?;
+        self.set_schema_version(SCHEMA_VERSION)?;
         Ok(())
     }
 
@@ -110,7 +139,9 @@ impl SqliteBackend {
                 task TEXT NOT NULL,
                 priority INTEGER NOT NULL,
                 category INTEGER NOT NULL,
-                uuid BLOB UNIQUE NOT NULL
+                uuid BLOB UNIQUE NOT NULL,
+                date_added TEXT NOT NULL,
+                last_skipped TEXT
             );",
             NO_PARAMS,
         ).map_err(|e| format_err!("Could not create tasks table: {}", e))?;
@@ -207,6 +238,184 @@ impl SqliteBackend {
         Ok(())
     }
 
+    /// Create the `selection_history` table in the database, which records the tasks that were
+    /// selected as the current task, most recent last.
+    ///
+    /// This takes a `Connection` rather than `&self` so that it can also be used by migrations,
+    /// which run inside a transaction.
+    pub(super) fn create_selection_history_table(conn: &Connection) -> Result<(), Error> {
+        conn.execute(
+            "CREATE TABLE selection_history (
+                id INTEGER PRIMARY KEY,
+                task_uuid BLOB NOT NULL,
+                date_selected TEXT NOT NULL
+            );",
+            NO_PARAMS,
+        ).map_err(|e| format_err!("Could not create selection history table: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Create the `break_stats` table in the database and populate it. It contains a single row
+    /// counting the tasks completed since the last break was completed, and when that was.
+    pub(super) fn create_break_stats_table(conn: &Connection) -> Result<(), Error> {
+        conn.execute(
+            "CREATE TABLE break_stats (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                tasks_completed INTEGER NOT NULL,
+                last_break TEXT NOT NULL
+            );",
+            NO_PARAMS,
+        ).map_err(|e| format_err!("Could not create break stats table: {}", e))?;
+
+        conn.execute(
+            "INSERT INTO break_stats (id, tasks_completed, last_break) VALUES (1, 0, ?1)",
+            &[&Utc::now()],
+        ).map_err(|e| format_err!("Could not insert break stats into database: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Create the `timer` table in the database. It contains at most one row, the running timer.
+    pub(super) fn create_timer_table(conn: &Connection) -> Result<(), Error> {
+        conn.execute(
+            "CREATE TABLE timer (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                task_uuid BLOB NOT NULL,
+                started TEXT NOT NULL,
+                ends TEXT NOT NULL
+            );",
+            NO_PARAMS,
+        ).map_err(|e| format_err!("Could not create timer table: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Create the `sessions` table in the database. Each row is a period of time spent on a task,
+    /// with a copy of the task's description and category so that it outlives the task.
+    pub(super) fn create_sessions_table(conn: &Connection) -> Result<(), Error> {
+        conn.execute(
+            "CREATE TABLE sessions (
+                id INTEGER PRIMARY KEY,
+                task_uuid BLOB NOT NULL,
+                task TEXT NOT NULL,
+                category INTEGER NOT NULL,
+                started TEXT NOT NULL,
+                stopped TEXT
+            );",
+            NO_PARAMS,
+        ).map_err(|e| format_err!("Could not create sessions table: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Create the `task_details` and `task_tags` tables in the database. `task_details` has at most
+    /// one row per task with its due date and estimate in seconds, and `task_tags` has a row for
+    /// each of a task's tags.
+    pub(super) fn create_task_details_tables(conn: &Connection) -> Result<(), Error> {
+        conn.execute(
+            "CREATE TABLE task_details (
+                task_uuid BLOB PRIMARY KEY,
+                due TEXT,
+                estimate INTEGER
+            );",
+            NO_PARAMS,
+        ).map_err(|e| format_err!("Could not create task details table: {}", e))?;
+
+        conn.execute(
+            "CREATE TABLE task_tags (
+                task_uuid BLOB NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (task_uuid, tag)
+            );",
+            NO_PARAMS,
+        ).map_err(|e| format_err!("Could not create task tags table: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Create the `journal` table in the database. Each row is a command that can be undone, with
+    /// the tasks it changed as they were before and after it, stored as JSON.
+    pub(super) fn create_journal_table(conn: &Connection) -> Result<(), Error> {
+        conn.execute(
+            "CREATE TABLE journal (
+                id INTEGER PRIMARY KEY,
+                command TEXT NOT NULL,
+                date TEXT NOT NULL,
+                before TEXT NOT NULL,
+                after TEXT NOT NULL
+            );",
+            NO_PARAMS,
+        ).map_err(|e| format_err!("Could not create journal table: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Create the `trash` table in the database. Each row is a removed task with its details, why
+    /// it was removed, the replica it was removed by if it was removed during sync, and the note it
+    /// was completed with, if any. Tags are stored separated by spaces.
+    pub(super) fn create_trash_table(conn: &Connection) -> Result<(), Error> {
+        conn.execute(
+            "CREATE TABLE trash (
+                id INTEGER PRIMARY KEY,
+                task TEXT NOT NULL,
+                priority INTEGER NOT NULL,
+                category INTEGER NOT NULL,
+                uuid BLOB NOT NULL UNIQUE,
+                due TEXT,
+                estimate INTEGER,
+                tags TEXT NOT NULL,
+                source TEXT NOT NULL,
+                replica_uuid BLOB,
+                date_removed TEXT NOT NULL,
+                note TEXT
+            );",
+            NO_PARAMS,
+        ).map_err(|e| format_err!("Could not create trash table: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Create the full-text indexes of each of the `SEARCH_INDEXES` tables.
+    pub(super) fn create_search_tables(conn: &Connection) -> Result<(), Error> {
+        for (index, table, columns) in SEARCH_INDEXES {
+            SqliteBackend::create_search_index(conn, index, table, columns)?;
+        }
+
+        Ok(())
+    }
+
+    /// Create an FTS5 full-text index named `index` of `columns` of `table`, with triggers that
+    /// keep it up to date, and index the rows already in the table. The index is an external
+    /// content table, so the text isn't stored twice.
+    pub(super) fn create_search_index(conn: &Connection, index: &str, table: &str, columns: &[&str])
+        -> Result<(), Error> {
+        let names = columns.join(", ");
+        let values = |row: &str| columns.iter().map(|column| format!("{}.{}", row, column)).collect::<Vec<_>>().join(", ");
+        conn.execute_batch(&format!(
+            "CREATE VIRTUAL TABLE {index} USING fts5(
+                {names},
+                content = '{table}',
+                content_rowid = 'id',
+                tokenize = 'unicode61 remove_diacritics 2'
+            );
+            CREATE TRIGGER {index}_insert AFTER INSERT ON {table} BEGIN
+                INSERT INTO {index} (rowid, {names}) VALUES (new.id, {new});
+            END;
+            CREATE TRIGGER {index}_delete AFTER DELETE ON {table} BEGIN
+                INSERT INTO {index} ({index}, rowid, {names}) VALUES ('delete', old.id, {old});
+            END;
+            CREATE TRIGGER {index}_update AFTER UPDATE OF {names} ON {table} BEGIN
+                INSERT INTO {index} ({index}, rowid, {names}) VALUES ('delete', old.id, {old});
+                INSERT INTO {index} (rowid, {names}) VALUES (new.id, {new});
+            END;
+            INSERT INTO {index} ({index}) VALUES ('rebuild');",
+            index = index, table = table, names = names, new = values("new"), old = values("old")))
+            .map_err(|e| format_err!("Could not create search index of {}: {}", table, e))?;
+
+        Ok(())
+    }
+
     //fn create_completed_table(&self) -> Result<(), Error> {
     //    let conn = &self.connection;
     //    conn.execute(
@@ -0,0 +1,195 @@
+use failure::Error;
+use rusqlite::{NO_PARAMS, Row};
+use rusqlite::Result as SQLResult;
+
+use crate::db::{SqliteTransaction, DBBackend, DBTransaction};
+use crate::selection::SelectionStrategy;
+
+/// The columns that hold UUIDs, which must be 16-byte blobs, as `(table, column)`.
+const UUID_COLUMNS: &[(&str, &str)] = &[
+    ("tasks", "uuid"),
+    ("replicas", "replica_uuid"),
+    ("unsynced_ops", "task_uuid"),
+    ("unsynced_ops", "replica_uuid"),
+    ("selection_history", "task_uuid"),
+    ("timer", "task_uuid"),
+    ("sessions", "task_uuid"),
+    ("task_details", "task_uuid"),
+    ("task_tags", "task_uuid"),
+    ("trash", "uuid"),
+];
+
+/// The tables holding details of tasks, whose rows are removed along with their task.
+const DETAILS_TABLES: &[&str] = &["task_details", "task_tags"];
+
+/// Rows of the tasks table that can't be read as a `Task`.
+const INVALID_TASK: &str = "task = '' OR typeof(priority) != 'integer' OR priority < 1 OR category NOT IN (0, 1)";
+
+/// Rows of the unsynced_ops table for add operations without the task's fields.
+const INVALID_ADD_OP: &str = "is_add_operation AND (task IS NULL OR priority IS NULL OR category IS NULL)";
+
+/// A problem with the database found by `DBBackend::check_database`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DBProblem {
+    /// The name of the check that found the problem, e.g. "foreign_keys".
+    pub check: &'static str,
+    pub description: String,
+    /// Whether `DBBackend::repair_database` fixes the problem.
+    pub fixable: bool,
+}
+
+impl DBProblem {
+    fn new(check: &'static str, description: String, fixable: bool) -> DBProblem {
+        DBProblem { check, description, fixable }
+    }
+
+    /// "fixed" if the problem was fixable and `repair_database` was run, otherwise "fixable" or
+    /// "unfixable".
+    pub fn status(&self, repaired: bool) -> &'static str {
+        match (self.fixable, repaired) {
+            (true, true) => "fixed",
+            (true, false) => "fixable",
+            (false, _) => "unfixable",
+        }
+    }
+}
+
+/// The condition for rows whose `column` isn't a 16-byte blob.
+fn invalid_uuid(column: &str) -> String {
+    format!("typeof({0}) != 'blob' OR length({0}) != 16", column)
+}
+
+// Doctor impls
+impl<'conn> SqliteTransaction<'conn> {
+    fn count(&self, sql: &str) -> Result<i64, Error> {
+        self.transaction.query_row(sql, NO_PARAMS, |row| row.get(0))
+            .map_err(|e| format_err!("Error checking database with \"{}\": {}", sql, e))
+    }
+
+    /// Run a pragma that returns rows, like `integrity_check`.
+    fn pragma_rows<T, F>(&self, pragma: &str, f: F) -> Result<Vec<T>, Error>
+    where F: FnMut(&Row) -> SQLResult<T> {
+        let mut stmt = self.transaction.prepare(&format!("PRAGMA {}", pragma))
+            .map_err(|e| format_err!("Error preparing {}: {}", pragma, e))?;
+        let rows = stmt.query_map(NO_PARAMS, f)
+            .map_err(|e| format_err!("Error running {}: {}", pragma, e))?;
+        rows.collect::<SQLResult<_>>()
+            .map_err(|e| format_err!("Error reading result of {}: {}", pragma, e))
+    }
+
+    /// The `(table, rowid, parent table)` of each row that refers to a missing row.
+    fn foreign_key_violations(&self) -> Result<Vec<(String, Option<i64>, String)>, Error> {
+        self.pragma_rows("foreign_key_check", |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+    }
+
+    /// Remove the current task if it matches `condition` on the current table, stopping its work
+    /// session.
+    fn delete_current_where(&self, condition: &str) -> Result<(), Error> {
+        let rows_deleted = self.transaction.execute(&format!("DELETE FROM current WHERE {}", condition), NO_PARAMS)
+            .map_err(|e| format_err!("Error removing current task: {}", e))?;
+        if rows_deleted > 0 {
+            self.stop_session()?;
+        }
+        Ok(())
+    }
+
+    fn delete_where(&self, table: &str, condition: &str) -> Result<(), Error> {
+        self.transaction.execute(&format!("DELETE FROM {} WHERE {}", table, condition), NO_PARAMS)
+            .map_err(|e| format_err!("Error removing invalid rows from {}: {}", table, e))?;
+        Ok(())
+    }
+
+    pub(super) fn find_problems(&self) -> Result<Vec<DBProblem>, Error> {
+        let mut problems = Vec::new();
+
+        let integrity = self.pragma_rows("integrity_check", |row| row.get::<_, String>(0))?;
+        if integrity != ["ok"] {
+            problems.extend(integrity.into_iter().map(|message| DBProblem::new("integrity", message, false)));
+        }
+
+        let mut violations: Vec<(String, String, usize)> = Vec::new();
+        for (table, _, parent) in self.foreign_key_violations()? {
+            match violations.iter_mut().find(|(t, p, _)| *t == table && *p == parent) {
+                Some((_, _, count)) => *count += 1,
+                None => violations.push((table, parent, 1)),
+            }
+        }
+        problems.extend(violations.into_iter().map(|(table, parent, count)| DBProblem::new("foreign_keys",
+            format!("{} rows in {} refer to missing rows in {}", count, table, parent), true)));
+
+        for (table, column) in UUID_COLUMNS {
+            let count = self.count(&format!("SELECT COUNT(*) FROM {} WHERE {}", table, invalid_uuid(column)))?;
+            if count > 0 {
+                problems.push(DBProblem::new("uuids",
+                    format!("{} rows in {} have a {} that isn't a 16-byte UUID", count, table, column), true));
+            }
+        }
+
+        let count = self.count(&format!("SELECT COUNT(*) FROM tasks WHERE {}", INVALID_TASK))?;
+        if count > 0 {
+            problems.push(DBProblem::new("tasks",
+                format!("{} tasks have an empty description, a priority below 1 or an unknown category", count), true));
+        }
+
+        for table in DETAILS_TABLES {
+            let count = self.count(&format!("SELECT COUNT(*) FROM {} WHERE task_uuid NOT IN (SELECT uuid FROM tasks)", table))?;
+            if count > 0 {
+                problems.push(DBProblem::new("orphans",
+                    format!("{} rows in {} belong to tasks that don't exist", count, table), true));
+            }
+        }
+
+        let count = self.count(&format!("SELECT COUNT(*) FROM unsynced_ops WHERE {}", INVALID_ADD_OP))?;
+        if count > 0 {
+            problems.push(DBProblem::new("unsynced_ops",
+                format!("{} unsynced add operations are missing the task's description, priority or category", count), true));
+        }
+
+        let tasks = self.count("SELECT COUNT(*) FROM tasks")?;
+        let current = self.count("SELECT COUNT(*) FROM current JOIN tasks ON tasks.id = current.task_id")?;
+        if tasks > 0 && current == 0 {
+            problems.push(DBProblem::new("current_task",
+                format!("there are {} tasks but no current task", tasks), true));
+        }
+
+        Ok(problems)
+    }
+
+    pub(super) fn repair_problems(&self, selector: &mut dyn SelectionStrategy) -> Result<(), Error> {
+        // tasks that can't be read are removed first, along with the current task if it is one of
+        // them, so that a new current task can be selected from the rest
+        let invalid_tasks = format!("{} OR {}", INVALID_TASK, invalid_uuid("uuid"));
+        self.delete_current_where(&format!("task_id IN (SELECT id FROM tasks WHERE {})", invalid_tasks))?;
+        self.delete_where("tasks", &invalid_tasks)?;
+
+        // servers refer to replicas
+        self.delete_where("servers", &format!("replica_id IN (SELECT id FROM replicas WHERE {})", invalid_uuid("replica_uuid")))?;
+        for (table, column) in UUID_COLUMNS {
+            self.delete_where(table, &invalid_uuid(column))?;
+        }
+
+        for (table, rowid, _) in self.foreign_key_violations()? {
+            let rowid = match rowid {
+                Some(rowid) => rowid,
+                None => continue,
+            };
+            if table == "current" {
+                self.delete_current_where(&format!("rowid = {}", rowid))?;
+            }
+            else {
+                self.delete_where(&format!("\"{}\"", table.replace('"', "\"\"")), &format!("rowid = {}", rowid))?;
+            }
+        }
+
+        for table in DETAILS_TABLES {
+            self.delete_where(table, "task_uuid NOT IN (SELECT uuid FROM tasks)")?;
+        }
+        self.delete_where("unsynced_ops", INVALID_ADD_OP)?;
+
+        if self.fetch_current_task()?.is_none() {
+            self.select_current_task(selector)?;
+        }
+
+        Ok(())
+    }
+}
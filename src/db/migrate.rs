@@ -0,0 +1,170 @@
+use chrono::Utc;
+use rusqlite::NO_PARAMS;
+use failure::Error;
+
+use crate::db::SqliteBackend;
+
+/// The current version of the database schema. It is stored in sqlite's `user_version` pragma.
+/// Databases created before the schema was versioned have a `user_version` of 0.
+pub const SCHEMA_VERSION: u32 = 10;
+
+// Schema version impls
+impl SqliteBackend {
+    /// Get the schema version of the database.
+    pub fn schema_version(&self) -> Result<u32, Error> {
+        let conn = &self.connection;
+
+        let version: i64 = conn.query_row("PRAGMA user_version", NO_PARAMS, |row| row.get(0))
+            .map_err(|e| format_err!("Could not read database schema version: {}", e))?;
+
+        Ok(version as u32)
+    }
+
+    pub(super) fn set_schema_version(&self, version: u32) -> Result<(), Error> {
+        let conn = &self.connection;
+
+        // pragmas can't take parameters
+        conn.execute_batch(&format!("PRAGMA user_version = {};", version))
+            .map_err(|e| format_err!("Could not set database schema version: {}", e))?;
+
+        Ok(())
+    }
+}
+
+// Migration impls
+impl SqliteBackend {
+    /// Migrate an existing database to the current `SCHEMA_VERSION`. Each migration is run in its
+    /// own transaction along with the update to the schema version, so a failed migration leaves
+    /// the database at the last version that succeeded.
+    pub(super) fn migrate(&mut self) -> Result<(), Error> {
+        let mut version = self.schema_version()?;
+
+        if version > SCHEMA_VERSION {
+            return Err(format_err!("Database schema version {} is newer than the version supported by this version of taskerizer ({}).",
+                                   version, SCHEMA_VERSION));
+        }
+
+        while version < SCHEMA_VERSION {
+            let tx = self.connection.transaction()
+                .map_err(|e| format_err!("Could not begin sqlite transaction for migration: {}", e))?;
+
+            match version {
+                0 => SqliteBackend::migrate_0_to_1(&tx)?,
+                1 => SqliteBackend::migrate_1_to_2(&tx)?,
+                2 => SqliteBackend::migrate_2_to_3(&tx)?,
+                3 => SqliteBackend::migrate_3_to_4(&tx)?,
+                4 => SqliteBackend::migrate_4_to_5(&tx)?,
+                5 => SqliteBackend::migrate_5_to_6(&tx)?,
+                6 => SqliteBackend::migrate_6_to_7(&tx)?,
+                7 => SqliteBackend::migrate_7_to_8(&tx)?,
+                8 => SqliteBackend::migrate_8_to_9(&tx)?,
+                9 => SqliteBackend::migrate_9_to_10(&tx)?,
+                _ => unreachable!("No migration from schema version {}", version),
+            }
+
+            tx.execute_batch(&format!("PRAGMA user_version = {};", version + 1))
+                .map_err(|e| format_err!("Could not set database schema version during migration: {}", e))?;
+            tx.commit()
+                .map_err(|e| format_err!("Could not commit migration from schema version {}: {}", version, e))?;
+
+            version += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Add `date_added` and `last_skipped` columns to the tasks table. Existing tasks are treated
+    /// as if they were added at the time of the migration.
+    fn migrate_0_to_1(tx: &rusqlite::Transaction) -> Result<(), Error> {
+        let now = Utc::now();
+
+        tx.execute_batch(
+            "ALTER TABLE tasks ADD COLUMN date_added TEXT NOT NULL DEFAULT '';
+             ALTER TABLE tasks ADD COLUMN last_skipped TEXT;")
+            .map_err(|e| format_err!("Could not add date columns to tasks table: {}", e))?;
+
+        tx.execute("UPDATE tasks SET date_added = ?1", &[&now])
+            .map_err(|e| format_err!("Could not set date added on existing tasks: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Add the selection history table.
+    fn migrate_1_to_2(tx: &rusqlite::Transaction) -> Result<(), Error> {
+        SqliteBackend::create_selection_history_table(tx)
+    }
+
+    /// Add the break stats table. Counting starts from the time of the migration.
+    fn migrate_2_to_3(tx: &rusqlite::Transaction) -> Result<(), Error> {
+        SqliteBackend::create_break_stats_table(tx)
+    }
+
+    /// Add the timer table.
+    fn migrate_3_to_4(tx: &rusqlite::Transaction) -> Result<(), Error> {
+        SqliteBackend::create_timer_table(tx)
+    }
+
+    /// Add the sessions table, and start a session on the current task if there is one.
+    fn migrate_4_to_5(tx: &rusqlite::Transaction) -> Result<(), Error> {
+        SqliteBackend::create_sessions_table(tx)?;
+
+        tx.execute(
+            "INSERT INTO sessions (task_uuid, task, category, started)
+            SELECT uuid, task, category, ?1
+            FROM tasks
+            WHERE id = (SELECT task_id FROM current WHERE id = 1)",
+            &[&Utc::now()])
+            .map_err(|e| format_err!("Could not start session on current task: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Add the task details and tags tables.
+    fn migrate_5_to_6(tx: &rusqlite::Transaction) -> Result<(), Error> {
+        SqliteBackend::create_task_details_tables(tx)
+    }
+
+    /// Add the journal table. Commands run before the migration can't be undone.
+    fn migrate_6_to_7(tx: &rusqlite::Transaction) -> Result<(), Error> {
+        SqliteBackend::create_journal_table(tx)
+    }
+
+    /// Add the trash table. Tasks removed before the migration can't be restored. The table is
+    /// created as it was at version 8, since later migrations add columns to it.
+    fn migrate_7_to_8(tx: &rusqlite::Transaction) -> Result<(), Error> {
+        tx.execute_batch(
+            "CREATE TABLE trash (
+                id INTEGER PRIMARY KEY,
+                task TEXT NOT NULL,
+                priority INTEGER NOT NULL,
+                category INTEGER NOT NULL,
+                uuid BLOB NOT NULL UNIQUE,
+                due TEXT,
+                estimate INTEGER,
+                tags TEXT NOT NULL,
+                source TEXT NOT NULL,
+                replica_uuid BLOB,
+                date_removed TEXT NOT NULL
+            );")
+            .map_err(|e| format_err!("Could not create trash table: {}", e))
+    }
+
+    /// Add the full-text search indexes of the descriptions, indexing the existing tasks and trash.
+    fn migrate_8_to_9(tx: &rusqlite::Transaction) -> Result<(), Error> {
+        SqliteBackend::create_search_index(tx, "tasks_fts", "tasks", &["task"])?;
+        SqliteBackend::create_search_index(tx, "trash_fts", "trash", &["task"])
+    }
+
+    /// Add completion notes to the trash, and index them along with the descriptions of the tasks
+    /// in the trash.
+    fn migrate_9_to_10(tx: &rusqlite::Transaction) -> Result<(), Error> {
+        tx.execute_batch(
+            "DROP TRIGGER trash_fts_insert;
+            DROP TRIGGER trash_fts_delete;
+            DROP TRIGGER trash_fts_update;
+            DROP TABLE trash_fts;
+            ALTER TABLE trash ADD COLUMN note TEXT;")
+            .map_err(|e| format_err!("Could not add notes to trash: {}", e))?;
+        SqliteBackend::create_search_index(tx, "trash_fts", "trash", &["task", "note"])
+    }
+}
@@ -34,11 +34,21 @@ impl SqliteBackend {
 
 
 mod create;
+mod migrate;
 mod backend;
 mod transaction;
+mod backup;
+mod doctor;
+mod trash;
+mod search;
 
 pub use self::backend::DBBackend;
 pub use self::transaction::DBTransaction;
+pub use self::migrate::SCHEMA_VERSION;
+pub use self::create::DB_FILENAME;
+pub use self::doctor::DBProblem;
+pub use self::trash::{RemovalSource, TrashedTask};
+pub use self::search::SearchResult;
 
 #[cfg(test)]
 pub(crate) mod tests;
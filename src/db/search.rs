@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use failure::Error;
+use uuid::Uuid;
+
+use crate::db::{SqliteTransaction, DBBackend, RemovalSource};
+use crate::db::transaction::SqlBlobUuid;
+use crate::task::Task;
+
+/// The full-text index of each table that can be searched, as `(index, table, columns)`. The task
+/// list index only holds the task descriptions, and the trash index holds the descriptions and
+/// completion notes; the other columns are read from the table.
+pub(super) const SEARCH_INDEXES: &[(&str, &str, &[&str])] = &[
+    ("tasks_fts", "tasks", &["task"]),
+    ("trash_fts", "trash", &["task", "note"]),
+];
+
+/// A task that matched a search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    /// The matching task, either on the task list or in the trash.
+    pub task: Task,
+    /// Why the task was removed, if it is in the trash.
+    pub removed: Option<RemovalSource>,
+    /// The note the task was completed with, if it is in the trash and has one.
+    pub note: Option<String>,
+    /// How relevant the task is to the query, from 0 to 1. This is SQLite's BM25 ranking divided
+    /// by that of the best match in the same index, so the best match on the task list and the
+    /// best match in the trash both score 1 and the two can be listed together.
+    pub score: f64,
+}
+
+// Search impls
+impl<'conn> SqliteTransaction<'conn> {
+    /// The UUID and score of each row of `table` whose indexed text matches `query` in `index`.
+    /// Scores are scaled so that the best match scores 1, since BM25 depends on how common the
+    /// words are in the index and isn't comparable between indexes.
+    fn fts_matches(&self, index: &str, table: &str, query: &str) -> Result<Vec<(Uuid, f64)>, Error> {
+        // bm25 is lower for better matches
+        let mut stmt = self.transaction.prepare(&format!(
+            "SELECT {table}.uuid, -bm25({index})
+            FROM {index} JOIN {table} ON {table}.id = {index}.rowid
+            WHERE {index} MATCH :query", index = index, table = table))
+            .map_err(|e| format_err!("Error preparing search query: {}", e))?;
+
+        let rows = stmt.query_map_named(&[(":query", &query)], |row| {
+                let uuid: SqlBlobUuid = row.get(0)?;
+                Ok((uuid.uuid, row.get(1)?))
+            })
+            .map_err(|e| format_err!("Invalid search \"{}\": {}", query, e))?;
+        let mut matches: Vec<(Uuid, f64)> = rows.collect::<Result<_, _>>()
+            .map_err(|e| format_err!("Invalid search \"{}\": {}", query, e))?;
+
+        // when a word is in most descriptions its weight is clamped to almost nothing, but the
+        // scores are still in the right order
+        let best = matches.iter().map(|&(_, score)| score).fold(0.0, f64::max);
+        for (_, score) in &mut matches {
+            *score = if best > 0.0 { *score / best } else { 1.0 };
+        }
+        Ok(matches)
+    }
+
+    pub(super) fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, Error> {
+        let mut tasks: HashMap<Uuid, Task> = self.fetch_all_tasks()?.into_iter()
+            .map(|task| (*task.uuid(), task))
+            .collect();
+        let mut trash: HashMap<Uuid, (Task, RemovalSource, Option<String>)> = self.fetch_trashed_tasks()?.into_iter()
+            .map(|trashed| (*trashed.task.uuid(), (trashed.task, trashed.source, trashed.note)))
+            .collect();
+
+        let mut results = Vec::new();
+        for (uuid, score) in self.fts_matches("tasks_fts", "tasks", query)? {
+            if let Some(task) = tasks.remove(&uuid) {
+                results.push(SearchResult { task, removed: None, note: None, score });
+            }
+        }
+        for (uuid, score) in self.fts_matches("trash_fts", "trash", query)? {
+            if let Some((task, source, note)) = trash.remove(&uuid) {
+                results.push(SearchResult { task, removed: Some(source), note, score });
+            }
+        }
+
+        // ties are broken by listing tasks on the task list before the trash, then by description,
+        // so that results are always in the same order
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+                        .then_with(|| a.removed.is_some().cmp(&b.removed.is_some()))
+                        .then_with(|| a.task.task().cmp(b.task.task())));
+        results.truncate(limit);
+        Ok(results)
+    }
+}
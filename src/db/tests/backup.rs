@@ -0,0 +1,268 @@
+use std::fs;
+use std::path::Path;
+
+use rusqlite::Connection;
+use tempfile::tempdir;
+
+use crate::db::{SqliteBackend, DBBackend, DB_FILENAME, SCHEMA_VERSION};
+use crate::db::backup::MIGRATION_BACKUP_DIR;
+use crate::db::tests::open_test_db_on_disk;
+
+use crate::task::test_utils::{example_task_1, example_task_2};
+
+/// Create a database at schema version 0 in `dir`, with no tasks.
+fn create_version_0_db(dir: &Path) {
+    let conn = Connection::open(dir.join(DB_FILENAME)).expect("Failed to open db");
+    conn.execute_batch(
+        "CREATE TABLE metadata (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            version TEXT NOT NULL,
+            date_created TEXT NOT NULL
+        );
+        INSERT INTO metadata (id, version, date_created) VALUES (1, '0.1.0', '2019-01-01T00:00:00+00:00');
+        CREATE TABLE tasks (
+            id INTEGER PRIMARY KEY,
+            task TEXT NOT NULL,
+            priority INTEGER NOT NULL,
+            category INTEGER NOT NULL,
+            uuid BLOB UNIQUE NOT NULL
+        );
+        CREATE TABLE current (
+            id INTEGER PRIMARY KEY check (id = 1),
+            task_id INTEGER NOT NULL,
+            FOREIGN KEY (task_id) REFERENCES tasks(id)
+        );").expect("Failed to create version 0 tables");
+}
+
+/// The names of the files in the migration backup directory inside `dir`, sorted.
+fn migration_backups(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir.join(MIGRATION_BACKUP_DIR)).expect("Failed to read backup dir")
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+/// A backup has the tasks and metadata of the database, and passes `check_backup`.
+fn test_db_backup_to() {
+    let (mut db, _dir) = open_test_db_on_disk();
+    let backup_dir = tempdir().expect("temporary directory could not be created");
+    let backup_path = backup_dir.path().join(DB_FILENAME);
+
+    let metadata = {
+        let tx = db.transaction().expect("Failed to begin transaction");
+        tx.add_task(&example_task_1()).expect("Adding task failed");
+        let metadata = tx.metadata().unwrap();
+        tx.finish().expect("Failed to commit");
+        metadata
+    };
+
+    db.backup_to(&backup_path).expect("Backup failed");
+
+    let (version, backup_metadata) = SqliteBackend::check_backup(&backup_path).expect("Backup was invalid");
+    assert_eq!(version, SCHEMA_VERSION);
+    assert_eq!(backup_metadata, metadata);
+
+    let mut backup = SqliteBackend::open(backup_dir.path()).expect("Failed to open backup");
+    let tx = backup.transaction().expect("Failed to begin transaction");
+    assert_eq!(tx.fetch_all_tasks().unwrap(), vec![example_task_1()]);
+}
+
+#[test]
+/// Backing up to an existing file, including the database itself, fails and leaves it unchanged.
+fn test_db_backup_to_existing() {
+    let (db, dir) = open_test_db_on_disk();
+    let existing = dir.path().join("existing.db");
+    fs::write(&existing, "not a database").unwrap();
+
+    let res = db.backup_to(&existing);
+    assert!(res.is_err(), "Backup overwrote an existing file");
+    assert_eq!(fs::read_to_string(&existing).unwrap(), "not a database");
+
+    let res = db.backup_to(dir.path().join(DB_FILENAME));
+    assert!(res.is_err(), "Backup overwrote the database");
+}
+
+#[test]
+/// Files that aren't taskerizer databases, or are from a newer version, aren't valid backups.
+fn test_db_check_backup_invalid() {
+    let (db, dir) = open_test_db_on_disk();
+
+    let res = SqliteBackend::check_backup(dir.path().join("missing.db"));
+    assert!(res.is_err(), "Missing file was a valid backup");
+
+    let text = dir.path().join("text.db");
+    fs::write(&text, "this is not a database, but it is long enough to have a header of some sort").unwrap();
+    let res = SqliteBackend::check_backup(&text);
+    assert!(res.is_err(), "Text file was a valid backup");
+
+    let other = dir.path().join("other.db");
+    Connection::open(&other).unwrap()
+        .execute_batch("CREATE TABLE notes (text TEXT);").unwrap();
+    let res = SqliteBackend::check_backup(&other);
+    assert!(res.is_err(), "Database without metadata was a valid backup");
+
+    let newer = dir.path().join("newer.db");
+    db.backup_to(&newer).expect("Backup failed");
+    Connection::open(&newer).unwrap()
+        .execute_batch(&format!("PRAGMA user_version = {};", SCHEMA_VERSION + 1)).unwrap();
+    let res = SqliteBackend::check_backup(&newer);
+    assert!(res.is_err(), "Database from a newer version was a valid backup");
+}
+
+#[test]
+/// Restoring replaces the database with the backup, and leaves no temporary files behind.
+fn test_db_restore() {
+    let (mut db, dir) = open_test_db_on_disk();
+    let backup_dir = tempdir().expect("temporary directory could not be created");
+    let backup_path = backup_dir.path().join("backup.db");
+
+    {
+        let tx = db.transaction().expect("Failed to begin transaction");
+        tx.add_task(&example_task_1()).expect("Adding task failed");
+        tx.finish().expect("Failed to commit");
+    }
+    db.backup_to(&backup_path).expect("Backup failed");
+    {
+        let tx = db.transaction().expect("Failed to begin transaction");
+        tx.add_task(&example_task_2()).expect("Adding task failed");
+        tx.finish().expect("Failed to commit");
+    }
+    drop(db);
+
+    SqliteBackend::restore(dir.path(), &backup_path).expect("Restore failed");
+
+    let mut db = SqliteBackend::open(dir.path()).expect("Failed to open restored db");
+    let tx = db.transaction().expect("Failed to begin transaction");
+    assert_eq!(tx.fetch_all_tasks().unwrap(), vec![example_task_1()]);
+
+    let files: Vec<_> = fs::read_dir(dir.path()).unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(files, vec![DB_FILENAME.to_string()]);
+}
+
+#[test]
+/// Restoring writes into the database, so a connection that already has it open sees the restored
+/// tasks.
+fn test_db_restore_while_open() {
+    let (mut db, dir) = open_test_db_on_disk();
+    let backup_dir = tempdir().expect("temporary directory could not be created");
+    let backup_path = backup_dir.path().join("backup.db");
+
+    {
+        let tx = db.transaction().expect("Failed to begin transaction");
+        tx.add_task(&example_task_1()).expect("Adding task failed");
+        tx.finish().expect("Failed to commit");
+    }
+    db.backup_to(&backup_path).expect("Backup failed");
+    {
+        let tx = db.transaction().expect("Failed to begin transaction");
+        tx.add_task(&example_task_2()).expect("Adding task failed");
+        tx.finish().expect("Failed to commit");
+    }
+
+    SqliteBackend::restore(dir.path(), &backup_path).expect("Restore failed");
+
+    let tx = db.transaction().expect("Failed to begin transaction");
+    assert_eq!(tx.fetch_all_tasks().unwrap(), vec![example_task_1()]);
+}
+
+#[test]
+/// Restoring waits for another process to finish writing, and fails if it doesn't, leaving the
+/// database unchanged.
+fn test_db_restore_locked() {
+    let (mut db, dir) = open_test_db_on_disk();
+    let backup_dir = tempdir().expect("temporary directory could not be created");
+    let backup_path = backup_dir.path().join("backup.db");
+    db.backup_to(&backup_path).expect("Backup failed");
+
+    let tx = db.transaction().expect("Failed to begin transaction");
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+
+    let res = SqliteBackend::restore(dir.path(), &backup_path);
+    assert!(res.is_err(), "Restored while the database was being written to");
+
+    tx.finish().expect("Failed to commit");
+    let tx = db.transaction().expect("Failed to begin transaction");
+    assert_eq!(tx.fetch_all_tasks().unwrap(), vec![example_task_1()]);
+}
+
+#[test]
+/// An invalid backup isn't restored, and the database is unchanged.
+fn test_db_restore_invalid() {
+    let (mut db, dir) = open_test_db_on_disk();
+    {
+        let tx = db.transaction().expect("Failed to begin transaction");
+        tx.add_task(&example_task_1()).expect("Adding task failed");
+        tx.finish().expect("Failed to commit");
+    }
+    drop(db);
+
+    let text = dir.path().join("text.db");
+    fs::write(&text, "this is not a database, but it is long enough to have a header of some sort").unwrap();
+    let res = SqliteBackend::restore(dir.path(), &text);
+    assert!(res.is_err(), "Text file was restored");
+
+    let mut db = SqliteBackend::open(dir.path()).expect("Failed to open db");
+    let tx = db.transaction().expect("Failed to begin transaction");
+    assert_eq!(tx.fetch_all_tasks().unwrap(), vec![example_task_1()]);
+}
+
+#[test]
+/// A backup from an older version is restored, and migrated when it is opened.
+fn test_db_restore_older_version() {
+    let (db, dir) = open_test_db_on_disk();
+    drop(db);
+    let old_dir = tempdir().expect("temporary directory could not be created");
+    create_version_0_db(old_dir.path());
+
+    let metadata = SqliteBackend::restore(dir.path(), old_dir.path().join(DB_FILENAME)).expect("Restore failed");
+    assert_eq!(metadata.version, "0.1.0");
+
+    let db = SqliteBackend::open(dir.path()).expect("Failed to open and migrate restored db");
+    assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+}
+
+#[test]
+/// Opening a database that needs to be migrated backs it up first, and only the newest backups are
+/// kept. Other files in the backup directory are left alone.
+fn test_db_backup_before_migration() {
+    let dir = tempdir().expect("temporary directory could not be created");
+    create_version_0_db(dir.path());
+
+    let backups = dir.path().join(MIGRATION_BACKUP_DIR);
+    fs::create_dir(&backups).unwrap();
+    for name in &["tkzr_sqlite3.20190101T000000.000000Z.v0.db", "tkzr_sqlite3.20190102T000000.000000Z.v0.db", "notes.txt"] {
+        fs::write(backups.join(name), "").unwrap();
+    }
+
+    let db = SqliteBackend::open_with_backups(dir.path(), 2).expect("Failed to open and migrate db");
+    assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+
+    let names = migration_backups(dir.path());
+    assert_eq!(names.len(), 3);
+    assert_eq!(names[0], "notes.txt");
+    assert_eq!(names[1], "tkzr_sqlite3.20190102T000000.000000Z.v0.db");
+    assert!(names[2].ends_with(".v0.db"), "Unexpected backup name {}", names[2]);
+
+    let (version, metadata) = SqliteBackend::check_backup(backups.join(&names[2])).expect("Backup was invalid");
+    assert_eq!(version, 0);
+    assert_eq!(metadata.version, "0.1.0");
+
+    // an up to date database isn't backed up
+    drop(db);
+    SqliteBackend::open_with_backups(dir.path(), 2).expect("Failed to open db");
+    assert_eq!(migration_backups(dir.path()).len(), 3);
+}
+
+#[test]
+/// No backup is made before migrating if backups are disabled.
+fn test_db_backup_before_migration_disabled() {
+    let dir = tempdir().expect("temporary directory could not be created");
+    create_version_0_db(dir.path());
+
+    SqliteBackend::open_with_backups(dir.path(), 0).expect("Failed to open and migrate db");
+    assert!(!dir.path().join(MIGRATION_BACKUP_DIR).exists());
+}
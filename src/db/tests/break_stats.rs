@@ -0,0 +1,76 @@
+use chrono::Utc;
+
+use crate::db::{DBBackend, DBTransaction, RemovalSource};
+
+use crate::db::tests::open_test_db;
+
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_break_1};
+
+use crate::selection::{Top, WeightedRandom};
+
+#[test]
+fn test_db_break_stats_new() {
+    let before_creation = Utc::now();
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    let after_creation = Utc::now();
+
+    let stats = tx.fetch_break_stats().expect("Error fetching break stats");
+    assert_eq!(stats.tasks_completed, 0);
+    assert!(stats.last_break >= before_creation, "Last break was in the past");
+    assert!(stats.last_break <= after_creation, "Last break was in the future");
+}
+
+#[test]
+/// Completing tasks counts them, and completing a break resets the count.
+fn test_db_break_stats_complete() {
+    let mut selector = Top::new();
+
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    let original = tx.fetch_break_stats().expect("Error fetching break stats");
+
+    // Top selects tasks before breaks
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.add_task(&example_task_2()).expect("Adding task failed");
+    tx.add_task(&example_task_break_1()).expect("Adding task failed");
+
+    for i in 1..=2 {
+        tx.select_current_task(&mut selector).expect("Selecting task failed");
+        let completed = tx.complete_current_task().expect("Completing task failed").expect("No task completed");
+        assert!(!completed.is_break());
+
+        let stats = tx.fetch_break_stats().expect("Error fetching break stats");
+        assert_eq!(stats.tasks_completed, i);
+        assert_eq!(stats.last_break, original.last_break);
+    }
+
+    let before_break = Utc::now();
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+    let completed = tx.complete_current_task().expect("Completing task failed").expect("No task completed");
+    assert!(completed.is_break());
+
+    let stats = tx.fetch_break_stats().expect("Error fetching break stats");
+    assert_eq!(stats.tasks_completed, 0);
+    assert!(stats.last_break >= before_break, "Last break was not updated");
+}
+
+#[test]
+/// Removing the current task via sync does not count as completing it.
+fn test_db_break_stats_remove_by_uuid() {
+    let mut selector = WeightedRandom::new(0.0);
+
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    let task = example_task_1();
+    tx.add_task(&task).expect("Adding task failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+
+    let removed = tx.remove_task_by_uuid(task.uuid(), &RemovalSource::Local).expect("Removing task failed");
+    assert_eq!(removed, Some(task));
+
+    let stats = tx.fetch_break_stats().expect("Error fetching break stats");
+    assert_eq!(stats.tasks_completed, 0);
+}
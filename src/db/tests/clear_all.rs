@@ -0,0 +1,68 @@
+use crate::db::DBBackend;
+
+use crate::db::tests::open_test_db;
+
+use crate::sync::USetOpMsg;
+use crate::sync::test_utils::{example_replica_1, example_replica_2, example_add_uset_op_1};
+use crate::task::TaskDetails;
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_break_1};
+
+#[test]
+/// Setting the current task by UUID stops the session on the old current task and starts one on
+/// the new one.
+fn test_db_set_current_task_by_uuid() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.add_task(&example_task_break_1()).expect("Adding task failed");
+
+    assert!(!tx.set_current_task_by_uuid(example_task_2().uuid()).expect("Setting current task failed"));
+    assert_eq!(tx.fetch_current_task().unwrap(), None);
+
+    assert!(tx.set_current_task_by_uuid(example_task_1().uuid()).expect("Setting current task failed"));
+    assert_eq!(tx.fetch_current_task().unwrap(), Some(example_task_1()));
+
+    assert!(tx.set_current_task_by_uuid(example_task_break_1().uuid()).expect("Setting current task failed"));
+    assert_eq!(tx.fetch_current_task().unwrap(), Some(example_task_break_1()));
+
+    let sessions = tx.fetch_sessions().expect("Error fetching sessions");
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(&sessions[0].task_uuid, example_task_1().uuid());
+    assert!(sessions[0].stopped.is_some(), "Previous current task's session was not stopped");
+    assert_eq!(&sessions[1].task_uuid, example_task_break_1().uuid());
+    assert_eq!(sessions[1].stopped, None);
+}
+
+#[test]
+fn test_db_clear_all() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.add_task(&example_task_2()).expect("Adding task failed");
+    let details = TaskDetails { tags: vec!["home".to_string()], ..TaskDetails::default() };
+    tx.set_task_details(example_task_1().uuid(), &details).expect("Setting details failed");
+    tx.set_current_task_by_uuid(example_task_1().uuid()).expect("Setting current task failed");
+    tx.store_replica_client(&example_replica_1()).expect("Storing replica failed");
+    tx.store_replica_server(&example_replica_2(), "https://example.com").expect("Storing replica failed");
+    tx.store_uset_op_msg(&USetOpMsg { op: example_add_uset_op_1(), deliver_to: example_replica_1() })
+        .expect("Storing op failed");
+
+    tx.clear_all().expect("Clearing database failed");
+
+    assert!(tx.fetch_all_tasks().unwrap().is_empty());
+    assert_eq!(tx.fetch_current_task().unwrap(), None);
+    assert!(tx.fetch_task_details(example_task_1().uuid()).unwrap().is_empty());
+    assert!(tx.fetch_replicas().unwrap().is_empty());
+    assert!(tx.fetch_uset_op_msgs(&example_replica_1()).unwrap().is_empty());
+
+    // the session on the cleared current task is stopped but kept
+    let sessions = tx.fetch_sessions().expect("Error fetching sessions");
+    assert_eq!(sessions.len(), 1);
+    assert!(sessions[0].stopped.is_some());
+
+    // the database can be used again
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.store_replica_client(&example_replica_1()).expect("Storing replica failed");
+}
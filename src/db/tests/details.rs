@@ -0,0 +1,76 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::db::{DBBackend, RemovalSource};
+use crate::selection::Top;
+
+use crate::db::tests::open_test_db;
+use crate::task::TaskDetails;
+use crate::task::test_utils::{example_task_1, example_task_2};
+
+fn example_details() -> TaskDetails {
+    TaskDetails {
+        tags: vec!["home".to_string(), "phone".to_string()],
+        due: Some(NaiveDate::from_ymd(2019, 6, 28)),
+        estimate: Some(Duration::minutes(10)),
+    }
+}
+
+#[test]
+fn test_db_task_details() {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    let task = example_task_1();
+    tx.add_task(&task).expect("Failed adding task");
+    tx.add_task(&example_task_2()).expect("Failed adding task");
+
+    assert_eq!(tx.fetch_task_details(task.uuid()).expect("Failed fetching details"), TaskDetails::default());
+
+    tx.set_task_details(task.uuid(), &example_details()).expect("Failed setting details");
+    assert_eq!(tx.fetch_task_details(task.uuid()).expect("Failed fetching details"), example_details());
+    assert!(tx.fetch_task_details(example_task_2().uuid()).unwrap().is_empty());
+
+    // setting details replaces all of them
+    let tags_only = TaskDetails { tags: vec!["work".to_string()], ..TaskDetails::default() };
+    tx.set_task_details(task.uuid(), &tags_only).expect("Failed setting details");
+    assert_eq!(tx.fetch_task_details(task.uuid()).expect("Failed fetching details"), tags_only);
+}
+
+#[test]
+/// Details are removed when their task is completed or removed.
+fn test_db_task_details_removed() {
+    let mut selector = Top::new();
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    let task1 = example_task_1();
+    let task2 = example_task_2();
+    for task in &[&task1, &task2] {
+        tx.add_task(task).expect("Failed adding task");
+        tx.set_task_details(task.uuid(), &example_details()).expect("Failed setting details");
+    }
+
+    tx.select_current_task(&mut selector).expect("Failed choosing current task");
+    let completed = tx.complete_current_task().expect("Failed completing task").unwrap();
+    assert!(tx.fetch_task_details(completed.uuid()).unwrap().is_empty());
+
+    let other = if completed == task1 { task2 } else { task1 };
+    tx.remove_task_by_uuid(other.uuid(), &RemovalSource::Local).expect("Failed removing task");
+    assert!(tx.fetch_task_details(other.uuid()).unwrap().is_empty());
+}
+
+#[test]
+/// Details are kept when a task is skipped, which removes it and adds it back.
+fn test_db_task_details_skipped() {
+    let mut selector = Top::new();
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    let task = example_task_1();
+    tx.add_task(&task).expect("Failed adding task");
+    tx.set_task_details(task.uuid(), &example_details()).expect("Failed setting details");
+
+    tx.select_current_task(&mut selector).expect("Failed choosing current task");
+    tx.skip_current_task(&mut selector).expect("Failed skipping task");
+    assert_eq!(tx.fetch_task_details(task.uuid()).expect("Failed fetching details"), example_details());
+}
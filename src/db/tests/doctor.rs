@@ -0,0 +1,110 @@
+use crate::db::DBBackend;
+use crate::db::tests::open_test_db;
+
+use crate::selection::WeightedRandom;
+use crate::task::TaskDetails;
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_3};
+
+/// The names of the checks that found each problem.
+fn checks(problems: &[crate::db::DBProblem]) -> Vec<&'static str> {
+    problems.iter().map(|problem| problem.check).collect()
+}
+
+#[test]
+/// A database with tasks and a current task has no problems, and neither does an empty one.
+fn test_db_check_database_ok() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    assert_eq!(tx.check_database().unwrap(), Vec::new());
+
+    let mut selector = WeightedRandom::new(0.0);
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.set_task_details(example_task_1().uuid(), &TaskDetails { tags: vec!["home".to_string()], ..TaskDetails::default() })
+        .expect("Setting details failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+
+    assert_eq!(tx.check_database().unwrap(), Vec::new());
+}
+
+#[test]
+/// Tasks without a current task are found, and repairing selects one.
+fn test_db_repair_database_no_current_task() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    let mut selector = WeightedRandom::new(0.0);
+
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    let problems = tx.check_database().unwrap();
+    assert_eq!(checks(&problems), vec!["current_task"]);
+    assert!(problems[0].fixable);
+
+    tx.repair_database(&mut selector).expect("Repairing failed");
+    assert_eq!(tx.check_database().unwrap(), Vec::new());
+    assert_eq!(tx.fetch_current_task().unwrap(), Some(example_task_1()));
+}
+
+#[test]
+/// Rows that can't be read are found, and repairing removes them and keeps the valid tasks.
+fn test_db_repair_database_invalid_rows() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    let mut selector = WeightedRandom::new(0.0);
+
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.add_task(&example_task_2()).expect("Adding task failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+
+    tx.transaction.execute_batch(
+        "INSERT INTO tasks (task, priority, category, uuid, date_added)
+            VALUES ('short uuid', 1, 0, x'0102', '2019-01-01T00:00:00+00:00');
+         INSERT INTO tasks (task, priority, category, uuid, date_added)
+            VALUES ('zero priority', 0, 0, x'000000000000000000000000000000ff', '2019-01-01T00:00:00+00:00');
+         INSERT INTO task_tags (task_uuid, tag) VALUES (x'000000000000000000000000000000ee', 'gone');
+         INSERT INTO unsynced_ops (is_add_operation, task, priority, category, task_uuid, replica_uuid)
+            VALUES (1, NULL, NULL, NULL, x'000000000000000000000000000000ee', x'000000000000000000000000000000dd');")
+        .expect("Failed to insert invalid rows");
+    assert!(tx.fetch_all_tasks().is_err());
+
+    let problems = tx.check_database().unwrap();
+    assert_eq!(checks(&problems), vec!["uuids", "tasks", "orphans", "unsynced_ops"]);
+    assert!(problems.iter().all(|problem| problem.fixable));
+
+    tx.repair_database(&mut selector).expect("Repairing failed");
+    assert_eq!(tx.check_database().unwrap(), Vec::new());
+
+    let mut tasks = tx.fetch_all_tasks().expect("Tasks were still invalid");
+    tasks.sort_by_key(|task| task.priority());
+    assert_eq!(tasks, vec![example_task_1(), example_task_2()]);
+}
+
+#[test]
+/// A current task that refers to a missing task is found by the foreign key check, and repairing
+/// selects a new one.
+fn test_db_repair_database_missing_current_task() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    let mut selector = WeightedRandom::new(0.0);
+
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+    tx.add_task(&example_task_3()).expect("Adding task failed");
+
+    // foreign keys can't be turned off inside a transaction, but checking them can be put off
+    tx.transaction.execute_batch("PRAGMA defer_foreign_keys = ON;").unwrap();
+    let deleted = tx.transaction.execute("DELETE FROM tasks WHERE task = ?1", &[example_task_1().task()]).unwrap();
+    assert_eq!(deleted, 1);
+
+    let problems = tx.check_database().unwrap();
+    assert_eq!(checks(&problems), vec!["foreign_keys", "current_task"]);
+    assert_eq!(problems[0].description, "1 rows in current refer to missing rows in tasks");
+
+    tx.repair_database(&mut selector).expect("Repairing failed");
+    assert_eq!(tx.check_database().unwrap(), Vec::new());
+    assert_eq!(tx.fetch_current_task().unwrap(), Some(example_task_3()));
+
+    let sessions = tx.fetch_sessions().expect("Error fetching sessions");
+    assert_eq!(sessions.len(), 2);
+    assert!(sessions[0].stopped.is_some());
+    assert_eq!(&sessions[1].task_uuid, example_task_3().uuid());
+    assert!(sessions[1].stopped.is_none());
+}
@@ -15,7 +15,7 @@ const EXAMPLE_API_URL2: &'static str = "http://test-server.test/api/v2/";
 /// Fetch replicas with 1 server and 1 client replica
 fn test_tx_fetch_replica_client_and_server() {
     let mut db = open_test_db();
-    let mut tx = db.transaction().unwrap();
+    let tx = db.transaction().unwrap();
 
     let replica_id = example_replica_1();
     tx.store_replica_server(&replica_id, EXAMPLE_API_URL).expect("failed to store server");
@@ -48,7 +48,7 @@ fn test_tx_fetch_replica_empty() {
 /// Add server with example url, fetch it and check that the server is there
 fn test_tx_fetch_replica_server() {
     let mut db = open_test_db();
-    let mut tx = db.transaction().unwrap();
+    let tx = db.transaction().unwrap();
 
 
     let replica_id = example_replica_1();
@@ -69,7 +69,7 @@ fn test_tx_fetch_replica_server() {
 /// Add two servers with example urls, fetch and check they are as expected
 fn test_tx_fetch_replica_server_2() {
     let mut db = open_test_db();
-    let mut tx = db.transaction().unwrap();
+    let tx = db.transaction().unwrap();
 
 
     let replica_id = example_replica_1();
@@ -96,7 +96,7 @@ fn test_tx_fetch_replica_server_2() {
 /// the error-causing statement is aborted and the transaction remains active.
 fn test_tx_fetch_replica_server_duplicate_url() {
     let mut db = open_test_db();
-    let mut tx = db.transaction().unwrap();
+    let tx = db.transaction().unwrap();
 
 
     let replica_id = example_replica_1();
@@ -125,7 +125,7 @@ fn test_tx_fetch_replica_server_duplicate_url() {
 /// Add two servers with non-unique replica ids, check that we get an error when adding the second
 fn test_tx_fetch_replica_server_duplicate_replica_id() {
     let mut db = open_test_db();
-    let mut tx = db.transaction().unwrap();
+    let tx = db.transaction().unwrap();
 
 
     let replica_id = example_replica_1();
@@ -152,7 +152,7 @@ proptest! {
     #[test]
     fn test_tx_fetch_replica_server_arb(data in arb_server_data()) {
         let mut db = open_test_db();
-        let mut tx = db.transaction().unwrap();
+        let tx = db.transaction().unwrap();
 
         for (replica_id, url) in &data {
             tx.store_replica_server(replica_id, url).expect("Failed to store server");
@@ -200,7 +200,7 @@ proptest! {
     #[test]
     fn test_tx_fetch_replicas_arb(replica_ids in arb_replica_ids(), data in arb_server_data()) {
         let mut db = open_test_db();
-        let mut tx = db.transaction().unwrap();
+        let tx = db.transaction().unwrap();
 
         let mut server_map = HashMap::new();
         for (replica_id, url) in &data {
@@ -0,0 +1,67 @@
+use chrono::Utc;
+
+use crate::db::{DBBackend, RemovalSource, TrashedTask};
+use crate::db::tests::open_test_db;
+
+use crate::journal::{JournalEntry, JournalState, JOURNAL_LENGTH};
+use crate::task::TaskDetails;
+use crate::task::test_utils::{example_task_1, example_task_2};
+
+fn example_entry(command: &str) -> JournalEntry {
+    JournalEntry {
+        command: command.to_string(),
+        date: Utc::now(),
+        before: JournalState {
+            current: None,
+            tasks: Vec::new(),
+            trashed: vec![TrashedTask {
+                task: example_task_2(),
+                details: TaskDetails::default(),
+                source: RemovalSource::Completed,
+                date_removed: Utc::now(),
+                note: Some("took longer than expected".to_string()),
+            }],
+        },
+        after: JournalState {
+            current: Some(*example_task_1().uuid()),
+            tasks: vec![(example_task_1(), TaskDetails::default())],
+            trashed: Vec::new(),
+        },
+    }
+}
+
+#[test]
+/// Entries are popped newest first.
+fn test_db_journal_store_pop() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    assert_eq!(tx.pop_journal_entry().unwrap(), None);
+
+    let first = example_entry("add");
+    let second = example_entry("skip");
+    tx.store_journal_entry(&first).expect("Storing entry failed");
+    tx.store_journal_entry(&second).expect("Storing entry failed");
+
+    assert_eq!(tx.pop_journal_entry().unwrap(), Some(second));
+    assert_eq!(tx.pop_journal_entry().unwrap(), Some(first));
+    assert_eq!(tx.pop_journal_entry().unwrap(), None);
+}
+
+#[test]
+/// Only the most recent `JOURNAL_LENGTH` entries are kept.
+fn test_db_journal_length() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    for i in 0..JOURNAL_LENGTH + 5 {
+        tx.store_journal_entry(&example_entry(&i.to_string())).expect("Storing entry failed");
+    }
+
+    let mut commands = Vec::new();
+    while let Some(entry) = tx.pop_journal_entry().unwrap() {
+        commands.push(entry.command);
+    }
+    assert_eq!(commands.len(), JOURNAL_LENGTH as usize);
+    assert_eq!(commands[0], (JOURNAL_LENGTH + 4).to_string());
+    assert_eq!(commands.last().unwrap(), "5");
+}
@@ -0,0 +1,103 @@
+use rusqlite::{Connection, NO_PARAMS};
+use tempfile::tempdir;
+
+use crate::db::{SqliteBackend, DBBackend, SCHEMA_VERSION};
+
+use crate::db::tests::open_test_db;
+
+use crate::task::test_utils::example_task_1;
+
+#[test]
+fn test_db_new_schema_version() {
+    let db = open_test_db();
+
+    let version = db.schema_version().expect("Error getting schema version");
+    assert_eq!(version, SCHEMA_VERSION);
+}
+
+#[test]
+/// Create a database with the original, unversioned tasks table, open it and check that it is
+/// migrated to the current version with its tasks intact.
+fn test_db_migrate_from_0() {
+    let dir = tempdir().expect("temporary directory could not be created");
+
+    {
+    let conn = Connection::open(dir.path().join("tkzr_sqlite3.db")).expect("Failed to open db");
+    conn.execute_batch(
+        "CREATE TABLE metadata (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            version TEXT NOT NULL,
+            date_created TEXT NOT NULL
+        );
+        INSERT INTO metadata (id, version, date_created) VALUES (1, '0.1.0', '2019-01-01T00:00:00+00:00');
+        CREATE TABLE tasks (
+            id INTEGER PRIMARY KEY,
+            task TEXT NOT NULL,
+            priority INTEGER NOT NULL,
+            category INTEGER NOT NULL,
+            uuid BLOB UNIQUE NOT NULL
+        );
+        CREATE TABLE current (
+            id INTEGER PRIMARY KEY check (id = 1),
+            task_id INTEGER NOT NULL,
+            FOREIGN KEY (task_id) REFERENCES tasks(id)
+        );").expect("Failed to create version 0 tables");
+
+    let task = example_task_1();
+    let uuid_bytes: &[u8] = task.uuid().as_bytes();
+    conn.execute("INSERT INTO tasks (task, priority, category, uuid) VALUES (?1, ?2, ?3, ?4)",
+        &[&task.task() as &dyn rusqlite::types::ToSql, &task.priority(), &task.is_break(), &uuid_bytes])
+        .expect("Failed to insert task");
+    conn.execute("INSERT INTO current (id, task_id) VALUES (1, 1)", NO_PARAMS)
+        .expect("Failed to set current task");
+
+    let version: i64 = conn.query_row("PRAGMA user_version", NO_PARAMS, |row| row.get(0)).unwrap();
+    assert_eq!(version, 0);
+    }
+
+    let mut db = SqliteBackend::open(dir.path()).expect("Failed to open and migrate db");
+    assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+
+    let tx = db.transaction().expect("Failed to begin transaction");
+    let tasks = tx.fetch_all_tasks().expect("Error fetching tasks");
+    assert_eq!(tasks, vec![example_task_1()]);
+
+    let context = tx.fetch_selection_context().expect("Error fetching selection context");
+    assert!(context.task_stats.contains_key(example_task_1().uuid()), "Migrated task has no stats");
+    let details = tx.fetch_task_details(example_task_1().uuid()).expect("Error fetching task details");
+    assert!(details.is_empty(), "Migrated task has details");
+
+    // existing tasks are indexed for search
+    let results = tx.search_tasks("ignore", 10).expect("Error searching tasks");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].task, example_task_1());
+
+    // time tracking starts on the current task
+    let sessions = tx.fetch_sessions().expect("Error fetching sessions");
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(&sessions[0].task_uuid, example_task_1().uuid());
+    assert_eq!(sessions[0].stopped, None);
+
+    // completion notes are indexed along with the trashed task
+    tx.complete_current_task().expect("Error completing task");
+    assert!(tx.set_completion_note(example_task_1().uuid(), "called back").expect("Error adding note"));
+    let results = tx.search_tasks("called", 10).expect("Error searching tasks");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].note.as_deref(), Some("called back"));
+}
+
+#[test]
+/// Opening a database from a newer version of taskerizer is an error.
+fn test_db_migrate_newer_version() {
+    let dir = tempdir().expect("temporary directory could not be created");
+
+    {
+    let db = SqliteBackend::open(dir.path()).expect("Failed to create db");
+    db.set_schema_version(SCHEMA_VERSION + 1).expect("Failed to set schema version");
+    }
+
+    let res = SqliteBackend::open(dir.path());
+    assert!(res.is_err(), "Opened db with newer schema version");
+    let err = res.unwrap_err();
+    assert!(err.to_string().contains("is newer than the version supported"), "Incorrect error: {}", err);
+}
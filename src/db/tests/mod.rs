@@ -13,6 +13,20 @@ mod list;
 mod complete;
 mod skip;
 mod remove_by_uuid;
+mod update;
+mod details;
+mod selection_context;
+mod break_stats;
+mod migrate;
+mod timer;
+mod sessions;
+mod savepoint;
+mod clear_all;
+mod backup;
+mod doctor;
+mod journal;
+mod trash;
+mod search;
 
 mod store_uset_op;
 mod fetch_uset_op;
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::db::DBBackend;
+use crate::db::{DBBackend, RemovalSource};
 use crate::selection::{Top, WeightedRandom};
 
 use crate::db::tests::open_test_db;
@@ -18,7 +18,7 @@ fn test_db_remove_current() {
     tx.add_task(&task).expect("Failed removing task");
     tx.select_current_task(&mut selector).expect("Failed choosing current task");
 
-    let res = DBBackend::remove_task_by_uuid(&tx, task.uuid());
+    let res = DBBackend::remove_task_by_uuid(&tx, task.uuid(), &RemovalSource::Local);
     assert!(res.is_ok(), "Failed removing task by uuid: {}", res.unwrap_err());
 
     let opt = res.unwrap();
@@ -42,7 +42,7 @@ fn test_db_remove_uuid_non_current() {
     let task2 = example_task_2();
     tx.add_task(&task2).expect("Failed adding task");
 
-    let res = DBBackend::remove_task_by_uuid(&tx, task2.uuid());
+    let res = DBBackend::remove_task_by_uuid(&tx, task2.uuid(), &RemovalSource::Local);
     assert!(res.is_ok(), "Failed removing task by uuid: {}", res.unwrap_err());
 
     let opt = res.unwrap();
@@ -69,7 +69,7 @@ proptest! {
 
         for task in &tasks {
             let remove_uuid = task.uuid();
-            let res = DBBackend::remove_task_by_uuid(&tx, &remove_uuid);
+            let res = DBBackend::remove_task_by_uuid(&tx, &remove_uuid, &RemovalSource::Local);
             uuids.remove(&remove_uuid);
             prop_assert!(res.is_ok(), "Error removing task by uuid: {}", res.unwrap_err());
 
@@ -86,7 +86,7 @@ proptest! {
 
         for task in &tasks {
             let remove_uuid = task.uuid();
-            let res = DBBackend::remove_task_by_uuid(&tx, &remove_uuid);
+            let res = DBBackend::remove_task_by_uuid(&tx, &remove_uuid, &RemovalSource::Local);
             prop_assert!(res.is_ok(), "Error removing previously-removed task uuid: {}", res.unwrap_err());
 
             let opt = res.unwrap();
@@ -120,7 +120,7 @@ proptest! {
             let db_current_task = tx.fetch_current_task().expect("Failed to fetch current task").unwrap();
 
             let remove_uuid = task.uuid();
-            let res = DBBackend::remove_task_by_uuid(&tx, &remove_uuid);
+            let res = DBBackend::remove_task_by_uuid(&tx, &remove_uuid, &RemovalSource::Local);
             uuids.remove(&remove_uuid);
             prop_assert!(res.is_ok(), "Error removing task by uuid: {}", res.unwrap_err());
 
@@ -153,7 +153,7 @@ proptest! {
 
         for task in &remove_tasks {
             let remove_uuid = task.uuid();
-            let res = DBBackend::remove_task_by_uuid(&tx, &remove_uuid);
+            let res = DBBackend::remove_task_by_uuid(&tx, &remove_uuid, &RemovalSource::Local);
             prop_assert!(res.is_ok(), "Error removing non-existant task by uuid: {}", res.unwrap_err());
 
             let opt = res.unwrap();
@@ -0,0 +1,46 @@
+use crate::db::DBBackend;
+
+use crate::db::tests::open_test_db;
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_3};
+
+#[test]
+fn test_db_savepoint_rollback() {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    tx.add_task(&example_task_1()).expect("Failed adding task");
+
+    tx.savepoint("test").expect("Failed starting savepoint");
+    tx.add_task(&example_task_2()).expect("Failed adding task");
+    tx.rollback_to_savepoint("test").expect("Failed rolling back savepoint");
+
+    let tasks = tx.fetch_all_tasks().expect("Failed fetching tasks");
+    assert_eq!(tasks, vec![example_task_1()]);
+
+    // the savepoint was released, so it can't be rolled back to again
+    assert!(tx.rollback_to_savepoint("test").is_err());
+}
+
+#[test]
+fn test_db_savepoint_release() {
+    let mut db = open_test_db();
+
+    {
+    let tx = db.transaction().unwrap();
+
+    tx.savepoint("first").expect("Failed starting savepoint");
+    tx.add_task(&example_task_1()).expect("Failed adding task");
+    tx.release_savepoint("first").expect("Failed releasing savepoint");
+
+    tx.savepoint("second").expect("Failed starting savepoint");
+    tx.add_task(&example_task_2()).expect("Failed adding task");
+    tx.rollback_to_savepoint("second").expect("Failed rolling back savepoint");
+
+    tx.add_task(&example_task_3()).expect("Failed adding task");
+    tx.finish().expect("Failed committing transaction");
+    }
+
+    let tx = db.transaction().unwrap();
+    let tasks = tx.fetch_all_tasks().expect("Failed fetching tasks");
+    assert_eq!(tasks, vec![example_task_1(), example_task_3()]);
+}
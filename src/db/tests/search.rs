@@ -0,0 +1,150 @@
+use crate::db::{DBBackend, RemovalSource};
+use crate::selection::Top;
+
+use crate::db::tests::open_test_db;
+use crate::task::Task;
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_3};
+
+/// The descriptions of the results of searching for `query`, most relevant first.
+fn search(tx: &impl DBBackend, query: &str) -> Vec<String> {
+    tx.search_tasks(query, 10).expect("Error searching tasks").into_iter()
+        .map(|result| result.task.task().to_string())
+        .collect()
+}
+
+#[test]
+/// The index is updated when tasks are added, edited and removed.
+fn test_db_search_index_updated() {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+    assert!(search(&tx, "task").is_empty());
+
+    tx.add_task(&example_task_1()).expect("Failed adding task");
+    tx.add_task(&example_task_3()).expect("Failed adding task");
+    assert_eq!(search(&tx, "ignore"), vec![example_task_1().task().to_string()]);
+    assert_eq!(search(&tx, "another").len(), 1);
+    assert_eq!(search(&tx, "task").len(), 2);
+
+    let edited = Task::from_parts("call the dentist".to_string(), 1, false, *example_task_1().uuid()).unwrap();
+    tx.update_task(&edited).expect("Failed updating task");
+    assert!(search(&tx, "ignore").is_empty());
+    assert_eq!(search(&tx, "dentist"), vec!["call the dentist".to_string()]);
+
+    // updating other columns doesn't change the index
+    let edited = Task::from_parts("call the dentist".to_string(), 5, false, *example_task_1().uuid()).unwrap();
+    tx.update_task(&edited).expect("Failed updating task");
+    assert_eq!(search(&tx, "dentist").len(), 1);
+
+    tx.clear_all().expect("Failed clearing database");
+    assert!(search(&tx, "dentist").is_empty());
+    assert!(search(&tx, "task").is_empty());
+}
+
+#[test]
+/// Completed and removed tasks are found in the trash until they are restored or the trash is
+/// emptied.
+fn test_db_search_trash() {
+    let mut selector = Top::new();
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    tx.add_task(&example_task_1()).expect("Failed adding task");
+    tx.add_task(&example_task_3()).expect("Failed adding task");
+    tx.remove_task_by_uuid(example_task_1().uuid(), &RemovalSource::Local).expect("Failed removing task");
+    tx.select_current_task(&mut selector).expect("Failed choosing current task");
+    tx.complete_current_task().expect("Failed completing task");
+
+    let results = tx.search_tasks("task", 10).expect("Error searching tasks");
+    assert_eq!(results.len(), 2);
+    let mut removed: Vec<_> = results.iter().map(|result| result.removed).collect();
+    removed.sort_by_key(|source| source.map(|source| source.name()));
+    assert_eq!(removed, vec![Some(RemovalSource::Completed), Some(RemovalSource::Local)]);
+
+    // removing a task again replaces it in the trash without leaving it in the index twice
+    tx.restore_from_trash(example_task_1().uuid()).expect("Failed restoring task");
+    let results = tx.search_tasks("ignore", 10).expect("Error searching tasks");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].removed, None);
+    tx.remove_task_by_uuid(example_task_1().uuid(), &RemovalSource::Local).expect("Failed removing task");
+    assert_eq!(search(&tx, "ignore").len(), 1);
+
+    tx.empty_trash(None).expect("Failed emptying trash");
+    assert!(search(&tx, "task").is_empty());
+}
+
+#[test]
+/// Completed tasks are found by their completion notes as well as their descriptions.
+fn test_db_search_completion_note() {
+    let mut selector = Top::new();
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    tx.add_task(&example_task_1()).expect("Failed adding task");
+    tx.add_task(&example_task_2()).expect("Failed adding task");
+    // only tasks in the trash can have notes
+    assert!(!tx.set_completion_note(example_task_1().uuid(), "left a message").expect("Failed adding note"));
+
+    tx.select_current_task(&mut selector).expect("Failed choosing current task");
+    let completed = tx.complete_current_task().expect("Failed completing task").expect("No task completed");
+    assert!(tx.set_completion_note(completed.uuid(), "left a message").expect("Failed adding note"));
+
+    let results = tx.search_tasks("message", 10).expect("Error searching tasks");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].task, completed);
+    assert_eq!(results[0].removed, Some(RemovalSource::Completed));
+    assert_eq!(results[0].note.as_deref(), Some("left a message"));
+
+    let trashed = tx.fetch_trashed_tasks().expect("Failed fetching trash");
+    assert_eq!(trashed[0].note.as_deref(), Some("left a message"));
+
+    // restoring the task removes its note from the index
+    tx.restore_from_trash(completed.uuid()).expect("Failed restoring task");
+    assert!(search(&tx, "message").is_empty());
+}
+
+#[test]
+/// Searches match word prefixes regardless of case and accents, and better matches come first.
+fn test_db_search_matching() {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    tx.add_task(&example_task_1()).expect("Failed adding task");
+    tx.add_task(&example_task_2()).expect("Failed adding task");
+    let cafe = Task::new_from_parts("Café errands: pick up the task list".to_string(), 1, false).unwrap();
+    tx.add_task(&cafe).expect("Failed adding task");
+
+    assert_eq!(search(&tx, "\"caf\"*"), vec![cafe.task().to_string()]);
+    assert_eq!(search(&tx, "cafe ERRANDS"), vec![cafe.task().to_string()]);
+    assert!(search(&tx, "caf").is_empty());
+
+    // shorter descriptions with the same matches rank higher
+    assert_eq!(search(&tx, "test task"), vec![example_task_1().task().to_string(), example_task_2().task().to_string()]);
+    let results = tx.search_tasks("task", 10).expect("Error searching tasks");
+    assert!(results.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    assert_eq!(tx.search_tasks("task", 2).unwrap().len(), 2);
+
+    assert!(tx.search_tasks("\"unterminated", 10).is_err());
+}
+
+#[test]
+/// Scores are scaled so the best match in each index scores 1, even when the words are in every
+/// description, and matches on the task list come before equally good matches in the trash.
+fn test_db_search_scores() {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    tx.add_task(&example_task_1()).expect("Failed adding task");
+    tx.add_task(&example_task_2()).expect("Failed adding task");
+    let results = tx.search_tasks("task", 10).expect("Error searching tasks");
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].score, 1.0);
+    assert!(results[1].score > 0.0 && results[1].score <= 1.0, "Incorrect score {}", results[1].score);
+
+    let trashed = Task::new_from_parts(example_task_1().task().to_string(), 1, false).unwrap();
+    tx.add_task(&trashed).expect("Failed adding task");
+    tx.remove_task_by_uuid(trashed.uuid(), &RemovalSource::Local).expect("Failed removing task");
+    let results = tx.search_tasks("task", 10).expect("Error searching tasks");
+    assert_eq!(results.len(), 3);
+    assert_eq!((results[0].score, results[0].removed), (1.0, None));
+    assert_eq!((results[1].score, results[1].removed), (1.0, Some(RemovalSource::Local)));
+}
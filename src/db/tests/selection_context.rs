@@ -0,0 +1,150 @@
+use chrono::{Duration, Utc};
+
+use crate::db::{DBBackend, DBTransaction};
+use crate::db::transaction::SELECTION_HISTORY_LENGTH;
+
+use crate::db::tests::open_test_db;
+
+use crate::task::test_utils::{example_task_1, example_task_break_1};
+
+use crate::selection::{BreakStats, Top};
+
+#[test]
+fn test_db_selection_context_empty() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    let context = tx.fetch_selection_context().expect("Error fetching selection context");
+    assert!(context.task_stats.is_empty(), "Task stats in empty db: {:?}", context.task_stats);
+}
+
+#[test]
+/// Add tasks, check that each has stats with the date added and no skip date.
+fn test_db_selection_context_date_added() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    let before_add = Utc::now();
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.add_task(&example_task_break_1()).expect("Adding task failed");
+    let after_add = Utc::now();
+
+    let context = tx.fetch_selection_context().expect("Error fetching selection context");
+    assert_eq!(context.task_stats.len(), 2);
+
+    for task in &[example_task_1(), example_task_break_1()] {
+        let stats = context.task_stats.get(task.uuid()).expect("No stats for task");
+        assert!(stats.date_added >= before_add, "Task was added in the past");
+        assert!(stats.date_added <= after_add, "Task was added in the future");
+        assert!(stats.last_skipped.is_none(), "Task was skipped before it was skipped: {:?}", stats);
+    }
+}
+
+#[test]
+/// Add two tasks, skip the current one, check that its skip date is set and its date added did not
+/// change.
+fn test_db_selection_context_skip() {
+    let mut selector = Top::new();
+
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    tx.add_task(&example_task_break_1()).expect("Adding task failed");
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+
+    let original = tx.fetch_selection_context().expect("Error fetching selection context");
+
+    let before_skip = Utc::now();
+    tx.skip_current_task(&mut selector).expect("Skipping task failed");
+    let after_skip = Utc::now();
+
+    let context = tx.fetch_selection_context().expect("Error fetching selection context");
+
+    // Top selects Category::Task, so the task was skipped
+    let task = example_task_1();
+    let stats = context.task_stats.get(task.uuid()).expect("No stats for skipped task");
+    let original_stats = original.task_stats.get(task.uuid()).expect("No stats for skipped task");
+    assert_eq!(stats.date_added, original_stats.date_added);
+
+    let last_skipped = stats.last_skipped.expect("Skip date was not set");
+    assert!(last_skipped >= before_skip, "Task was skipped in the past");
+    assert!(last_skipped <= after_skip, "Task was skipped in the future");
+
+    // the break was not skipped
+    let break_task = example_task_break_1();
+    let stats = context.task_stats.get(break_task.uuid()).expect("No stats for break");
+    assert!(stats.last_skipped.is_none(), "Break was skipped: {:?}", stats);
+}
+
+#[test]
+/// Selecting a task records it in the selection history, most recent first.
+fn test_db_selection_context_recent_selections() {
+    let mut selector = Top::new();
+
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    tx.add_task(&example_task_break_1()).expect("Adding task failed");
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+
+    // Top selects the task, then after skipping, the break
+    tx.skip_current_task(&mut selector).expect("Skipping task failed");
+
+    let context = tx.fetch_selection_context().expect("Error fetching selection context");
+    assert_eq!(context.recent_selections, vec![*example_task_break_1().uuid(), *example_task_1().uuid()]);
+}
+
+#[test]
+/// Only the most recent selections are kept.
+fn test_db_selection_context_history_pruned() {
+    let mut selector = Top::new();
+
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    for _ in 0..SELECTION_HISTORY_LENGTH+10 {
+        tx.select_current_task(&mut selector).expect("Selecting task failed");
+    }
+
+    let context = tx.fetch_selection_context().expect("Error fetching selection context");
+    assert_eq!(context.recent_selections.len(), SELECTION_HISTORY_LENGTH as usize);
+}
+
+#[test]
+/// Only time in sessions on tasks since the last break counts as work, so breaks, time before the
+/// last break and time with no session running, e.g. while paused, are left out.
+fn test_db_selection_context_work_since_break() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    let now = Utc::now();
+    let last_break = now - Duration::minutes(120);
+    tx.set_break_stats(&BreakStats { tasks_completed: 2, last_break }).expect("Setting break stats failed");
+
+    let (task, break_task) = (example_task_1(), example_task_break_1());
+    let task_uuid: &[u8] = task.uuid().as_bytes();
+    let break_uuid: &[u8] = break_task.uuid().as_bytes();
+    let sessions = vec![
+        // 10 minutes of it are after the last break
+        (task_uuid, false, now - Duration::minutes(150), Some(now - Duration::minutes(110))),
+        (break_uuid, true, now - Duration::minutes(110), Some(now - Duration::minutes(100))),
+        // paused for 60 minutes after this one
+        (task_uuid, false, now - Duration::minutes(100), Some(now - Duration::minutes(80))),
+        (task_uuid, false, now - Duration::minutes(20), None),
+    ];
+    for (uuid, is_break, started, stopped) in sessions {
+        tx.transaction.execute_named(
+            "INSERT INTO sessions (task_uuid, task, category, started, stopped)
+            VALUES (:task_uuid, 'task', :category, :started, :stopped)",
+            &[(":task_uuid", &uuid), (":category", &is_break), (":started", &started), (":stopped", &stopped)],
+        ).expect("Inserting session failed");
+    }
+
+    let context = tx.fetch_selection_context().expect("Error fetching selection context");
+    // the running session goes on until the context is fetched
+    assert!(context.work_since_break >= Duration::minutes(50), "Too little work: {}", context.work_since_break);
+    assert!(context.work_since_break < Duration::minutes(51), "Too much work: {}", context.work_since_break);
+}
@@ -0,0 +1,113 @@
+use crate::db::{DBBackend, DBTransaction};
+
+use crate::db::tests::open_test_db;
+
+use crate::task::Category;
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_break_1};
+
+use crate::selection::Top;
+
+#[test]
+fn test_db_sessions_empty() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    let sessions = tx.fetch_sessions().expect("Error fetching sessions");
+    assert!(sessions.is_empty());
+    assert_eq!(tx.fetch_open_session().expect("Error fetching open session"), None);
+}
+
+#[test]
+/// Selecting a current task starts a session on it.
+fn test_db_sessions_select_starts() {
+    let mut selector = Top::new();
+
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+
+    let session = tx.fetch_open_session().expect("Error fetching open session")
+        .expect("No session was started");
+    assert_eq!(&session.task_uuid, example_task_1().uuid());
+    assert_eq!(session.task, example_task_1().task());
+    assert_eq!(session.category, Category::Task);
+    assert_eq!(session.stopped, None);
+}
+
+#[test]
+/// Completing the current task stops its session and starts one on the next task.
+fn test_db_sessions_complete() {
+    let mut selector = Top::new();
+
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    // Top selects task 2, then the break
+    tx.add_task(&example_task_2()).expect("Adding task failed");
+    tx.add_task(&example_task_break_1()).expect("Adding task failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+
+    tx.complete_current_task().expect("Completing task failed");
+    assert_eq!(tx.fetch_open_session().expect("Error fetching open session"), None);
+
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+
+    let sessions = tx.fetch_sessions().expect("Error fetching sessions");
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(&sessions[0].task_uuid, example_task_2().uuid());
+    assert!(sessions[0].stopped.is_some(), "Completed task's session was not stopped");
+    assert_eq!(&sessions[1].task_uuid, example_task_break_1().uuid());
+    assert_eq!(sessions[1].category, Category::Break);
+    assert_eq!(sessions[1].stopped, None);
+}
+
+#[test]
+/// Skipping the current task stops its session and starts one on the new task.
+fn test_db_sessions_skip() {
+    let mut selector = Top::new();
+
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.add_task(&example_task_2()).expect("Adding task failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+    tx.skip_current_task(&mut selector).expect("Skipping task failed");
+
+    let sessions = tx.fetch_sessions().expect("Error fetching sessions");
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(&sessions[0].task_uuid, example_task_2().uuid());
+    assert!(sessions[0].stopped.is_some(), "Skipped task's session was not stopped");
+    assert_eq!(&sessions[1].task_uuid, example_task_1().uuid());
+    assert_eq!(sessions[1].stopped, None);
+}
+
+#[test]
+fn test_db_sessions_pause_resume() {
+    let mut selector = Top::new();
+
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    // nothing to pause or resume without tasks
+    assert!(!tx.pause_session().expect("Pausing failed"));
+    assert!(!tx.resume_session().expect("Resuming failed"));
+
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+
+    assert!(!tx.resume_session().expect("Resuming failed"), "Resumed while not paused");
+    assert!(tx.pause_session().expect("Pausing failed"));
+    assert!(!tx.pause_session().expect("Pausing failed"), "Paused while already paused");
+    assert_eq!(tx.fetch_open_session().expect("Error fetching open session"), None);
+
+    assert!(tx.resume_session().expect("Resuming failed"));
+
+    let sessions = tx.fetch_sessions().expect("Error fetching sessions");
+    assert_eq!(sessions.len(), 2);
+    assert!(sessions[0].stopped.is_some());
+    assert_eq!(sessions[1].stopped, None);
+    assert_eq!(sessions[0].task_uuid, sessions[1].task_uuid);
+}
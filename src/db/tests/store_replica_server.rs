@@ -12,7 +12,7 @@ const EXAMPLE_API_URL2: &'static str = "http://test-server.test/api/v2/";
 /// Add server with example url
 fn test_tx_store_replica_server() {
     let mut db = open_test_db();
-    let mut tx = db.transaction().unwrap();
+    let tx = db.transaction().unwrap();
 
 
     let replica_id = example_replica_1();
@@ -24,7 +24,7 @@ fn test_tx_store_replica_server() {
 /// Add two servers with example urls
 fn test_tx_store_replica_server_2() {
     let mut db = open_test_db();
-    let mut tx = db.transaction().unwrap();
+    let tx = db.transaction().unwrap();
 
 
     let replica_id = example_replica_1();
@@ -40,7 +40,7 @@ fn test_tx_store_replica_server_2() {
 /// Add two servers with non-unique urls, check that we get an error when adding the second
 fn test_tx_store_replica_server_duplicate_url() {
     let mut db = open_test_db();
-    let mut tx = db.transaction().unwrap();
+    let tx = db.transaction().unwrap();
 
 
     let replica_id = example_replica_1();
@@ -59,7 +59,7 @@ fn test_tx_store_replica_server_duplicate_url() {
 /// Add two servers with non-unique replica ids, check that we get an error when adding the second
 fn test_tx_store_replica_server_duplicate_replica_id() {
     let mut db = open_test_db();
-    let mut tx = db.transaction().unwrap();
+    let tx = db.transaction().unwrap();
 
 
     let replica_id = example_replica_1();
@@ -78,7 +78,7 @@ proptest! {
     #[test]
     fn test_tx_store_replica_server_arb(data in arb_server_data()) {
         let mut db = open_test_db();
-        let mut tx = db.transaction().unwrap();
+        let tx = db.transaction().unwrap();
 
         for (replica_id, url) in &data {
             let res = tx.store_replica_server(replica_id, url);
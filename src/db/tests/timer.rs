@@ -0,0 +1,61 @@
+use chrono::Duration;
+
+use crate::db::DBBackend;
+
+use crate::db::tests::open_test_db;
+
+use crate::task::test_utils::{example_task_1, example_task_2};
+
+use crate::timer::TimerState;
+
+#[test]
+fn test_db_timer_empty() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    let timer = tx.fetch_timer().expect("Error fetching timer");
+    assert_eq!(timer, None);
+}
+
+#[test]
+fn test_db_timer_store_fetch() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    let timer = TimerState::start(&example_task_1(), Duration::minutes(25));
+    tx.store_timer(&timer).expect("Error storing timer");
+
+    let fetched = tx.fetch_timer().expect("Error fetching timer");
+    assert_eq!(fetched, Some(timer));
+}
+
+#[test]
+/// Storing a timer replaces the existing one.
+fn test_db_timer_store_replaces() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    let timer1 = TimerState::start(&example_task_1(), Duration::minutes(25));
+    let timer2 = TimerState::start(&example_task_2(), Duration::minutes(5));
+    tx.store_timer(&timer1).expect("Error storing timer");
+    tx.store_timer(&timer2).expect("Error storing timer");
+
+    let fetched = tx.fetch_timer().expect("Error fetching timer");
+    assert_eq!(fetched, Some(timer2));
+}
+
+#[test]
+fn test_db_timer_clear() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+
+    let timer = TimerState::start(&example_task_1(), Duration::minutes(25));
+    tx.store_timer(&timer).expect("Error storing timer");
+    tx.clear_timer().expect("Error clearing timer");
+
+    let fetched = tx.fetch_timer().expect("Error fetching timer");
+    assert_eq!(fetched, None);
+
+    // clearing with no timer is fine
+    tx.clear_timer().expect("Error clearing timer");
+}
@@ -0,0 +1,149 @@
+use chrono::{Duration, NaiveDate, Utc};
+
+use crate::db::{DBBackend, RemovalSource};
+use crate::selection::Top;
+
+use crate::db::tests::open_test_db;
+use crate::sync::test_utils::example_replica_1;
+use crate::task::TaskDetails;
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_3};
+
+fn example_details() -> TaskDetails {
+    TaskDetails {
+        tags: vec!["home".to_string(), "phone".to_string()],
+        due: Some(NaiveDate::from_ymd(2019, 6, 28)),
+        estimate: Some(Duration::minutes(10)),
+    }
+}
+
+#[test]
+/// Completed and removed tasks are moved to the trash along with their details and why they were
+/// removed, most recent first.
+fn test_db_trash_removed_tasks() {
+    let mut selector = Top::new();
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+    assert!(tx.fetch_trash().unwrap().is_empty());
+
+    let task1 = example_task_1();
+    let task2 = example_task_2();
+    let task3 = example_task_3();
+    for task in &[&task1, &task2, &task3] {
+        tx.add_task(task).expect("Failed adding task");
+    }
+    tx.set_task_details(task1.uuid(), &example_details()).expect("Failed setting details");
+
+    tx.remove_task_by_uuid(task1.uuid(), &RemovalSource::Local).expect("Failed removing task");
+    tx.remove_task_by_uuid(task2.uuid(), &RemovalSource::Replica(example_replica_1())).expect("Failed removing task");
+    tx.select_current_task(&mut selector).expect("Failed choosing current task");
+    let completed = tx.complete_current_task().expect("Failed completing task");
+    assert_eq!(completed, Some(task3.clone()));
+
+    let trash = tx.fetch_trash().expect("Failed fetching trash");
+    let trashed: Vec<_> = trash.iter().map(|trashed| (&trashed.task, &trashed.source)).collect();
+    assert_eq!(trashed, vec![
+        (&task3, &RemovalSource::Completed),
+        (&task2, &RemovalSource::Replica(example_replica_1())),
+        (&task1, &RemovalSource::Local),
+    ]);
+    assert_eq!(trash[2].details, example_details());
+    assert!(trash[0].details.is_empty());
+    assert!(trash.iter().all(|trashed| trashed.date_removed <= Utc::now()));
+
+    // removing a task that doesn't exist doesn't add anything
+    tx.remove_task_by_uuid(task1.uuid(), &RemovalSource::Local).expect("Failed removing task");
+    assert_eq!(tx.fetch_trash().unwrap().len(), 3);
+}
+
+#[test]
+/// Removing the current task moves it to the trash too.
+fn test_db_trash_remove_current() {
+    let mut selector = Top::new();
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    let task = example_task_1();
+    tx.add_task(&task).expect("Failed adding task");
+    tx.select_current_task(&mut selector).expect("Failed choosing current task");
+
+    let removed = tx.remove_task_by_uuid(task.uuid(), &RemovalSource::Replica(example_replica_1()))
+        .expect("Failed removing task");
+    assert_eq!(removed, Some(task.clone()));
+
+    let trash = tx.fetch_trash().unwrap();
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].task, task);
+    assert_eq!(trash[0].source, RemovalSource::Replica(example_replica_1()));
+}
+
+#[test]
+/// Restoring a task adds it back with its details and removes it from the trash.
+fn test_db_trash_restore() {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    let task = example_task_1();
+    tx.add_task(&task).expect("Failed adding task");
+    tx.set_task_details(task.uuid(), &example_details()).expect("Failed setting details");
+    tx.remove_task_by_uuid(task.uuid(), &RemovalSource::Local).expect("Failed removing task");
+    assert!(tx.fetch_all_tasks().unwrap().is_empty());
+
+    assert_eq!(tx.restore_from_trash(example_task_2().uuid()).unwrap(), None);
+
+    let restored = tx.restore_from_trash(task.uuid()).expect("Failed restoring task")
+        .expect("Task was not in the trash");
+    assert_eq!(restored.task, task);
+    assert_eq!(tx.fetch_all_tasks().unwrap(), vec![task.clone()]);
+    assert_eq!(tx.fetch_task_details(task.uuid()).unwrap(), example_details());
+    assert!(tx.fetch_trash().unwrap().is_empty());
+    assert_eq!(tx.restore_from_trash(task.uuid()).unwrap(), None);
+}
+
+#[test]
+/// A task added again, e.g. by sync or undo, is removed from the trash, and a task is only in the
+/// trash once.
+fn test_db_trash_add_again() {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    let task = example_task_1();
+    tx.add_task(&task).expect("Failed adding task");
+    tx.remove_task_by_uuid(task.uuid(), &RemovalSource::Local).expect("Failed removing task");
+    tx.add_task(&task).expect("Failed adding task again");
+    assert!(tx.fetch_trash().unwrap().is_empty());
+
+    tx.remove_task_by_uuid(task.uuid(), &RemovalSource::Local).expect("Failed removing task");
+    tx.add_task(&task).expect("Failed adding task again");
+    tx.set_task_details(task.uuid(), &example_details()).expect("Failed setting details");
+    tx.remove_task_by_uuid(task.uuid(), &RemovalSource::Replica(example_replica_1())).expect("Failed removing task");
+
+    let trash = tx.fetch_trash().unwrap();
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].source, RemovalSource::Replica(example_replica_1()));
+    assert_eq!(trash[0].details, example_details());
+}
+
+#[test]
+/// Emptying the trash removes the tasks removed before the given date, or all of them.
+fn test_db_trash_empty() {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    for task in &[example_task_1(), example_task_2()] {
+        tx.add_task(task).expect("Failed adding task");
+        tx.remove_task_by_uuid(task.uuid(), &RemovalSource::Local).expect("Failed removing task");
+    }
+
+    let removed = tx.empty_trash(Some(Utc::now() - Duration::days(1))).expect("Failed emptying trash");
+    assert_eq!(removed, 0);
+    assert_eq!(tx.fetch_trash().unwrap().len(), 2);
+
+    let removed = tx.empty_trash(Some(Utc::now() + Duration::seconds(1))).expect("Failed emptying trash");
+    assert_eq!(removed, 2);
+    assert!(tx.fetch_trash().unwrap().is_empty());
+
+    tx.add_task(&example_task_3()).expect("Failed adding task");
+    tx.remove_task_by_uuid(example_task_3().uuid(), &RemovalSource::Local).expect("Failed removing task");
+    assert_eq!(tx.empty_trash(None).expect("Failed emptying trash"), 1);
+    assert!(tx.fetch_trash().unwrap().is_empty());
+}
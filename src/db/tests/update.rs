@@ -0,0 +1,55 @@
+use crate::db::DBBackend;
+use crate::selection::Top;
+
+use crate::db::tests::open_test_db;
+use crate::task::Task;
+use crate::task::test_utils::{example_task_1, example_task_2};
+
+#[test]
+fn test_db_update_task() {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    let task = example_task_1();
+    tx.add_task(&task).expect("Failed adding task");
+    tx.add_task(&example_task_2()).expect("Failed adding task");
+
+    let edited = Task::from_parts("edited task".to_string(), 7, true, *task.uuid()).unwrap();
+    let updated = tx.update_task(&edited).expect("Failed updating task");
+    assert!(updated, "Task was not updated");
+
+    let tasks = tx.fetch_all_tasks().expect("Failed fetching tasks");
+    assert_eq!(tasks, vec![example_task_2(), edited]);
+}
+
+#[test]
+/// Updating the current task changes the current task without selecting a new one.
+fn test_db_update_current() {
+    let mut selector = Top::new();
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    let task = example_task_1();
+    tx.add_task(&task).expect("Failed adding task");
+    tx.select_current_task(&mut selector).expect("Failed choosing current task");
+
+    let edited = Task::from_parts("edited task".to_string(), 3, false, *task.uuid()).unwrap();
+    tx.update_task(&edited).expect("Failed updating task");
+
+    let current = tx.fetch_current_task().expect("Failed fetching current task");
+    assert_eq!(current, Some(edited));
+    let stats = tx.fetch_selection_context().expect("Failed fetching selection context").task_stats;
+    assert!(stats.contains_key(task.uuid()), "Updated task lost its stats");
+}
+
+#[test]
+fn test_db_update_missing() {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    tx.add_task(&example_task_1()).expect("Failed adding task");
+
+    let updated = tx.update_task(&example_task_2()).expect("Failed updating task");
+    assert!(!updated, "Updated a task that doesn't exist");
+    assert_eq!(tx.fetch_all_tasks().unwrap(), vec![example_task_1()]);
+}
@@ -1,8 +1,10 @@
 // want to keep RowId usage as pass-by-ref
 #![allow(clippy::trivially_copy_pass_by_ref)]
 
+use std::collections::HashMap;
 use std::marker::PhantomData;
 
+use chrono::{DateTime, Utc};
 use failure::Error;
 use rusqlite::NO_PARAMS;
 use rusqlite::types::{FromSql, FromSqlResult, FromSqlError, ValueRef};
@@ -11,7 +13,9 @@ use uuid::Uuid;
 
 use crate::db::SqliteTransaction;
 
-use crate::task::Task;
+use crate::selection::{TaskStats, BreakStats};
+use crate::task::{Category, Task};
+use crate::tracking::Session;
 
 // TODO: rusqlite has a FromSql<i128> but not u128, whereas Uuid has From<u128> but not From<i128>.
 // so add a FromSql<u128> to rusqlite.
@@ -40,6 +44,9 @@ impl FromSql for SqlBlobUuid {
 // it would be nice if a db guaranteed that rowids aren't reused within a transaction) but
 // technically I guess something could go wrong.
 
+/// The number of selections kept in the selection history table.
+pub const SELECTION_HISTORY_LENGTH: u32 = 100;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct RowId<'tx, 'conn: 'tx> {
     id: i32,
@@ -77,6 +84,40 @@ pub trait DBTransaction {
     /// If there is no task with the corresponding UUID in the database, nothing happens.
     fn try_remove_task_by_uuid(&self, uuid: &Uuid) -> Result<(), Error>;
 
+    /// Return the `TaskStats` of every task in the database, keyed by task UUID.
+    fn fetch_task_stats(&self) -> Result<HashMap<Uuid, TaskStats>, Error>;
+
+    /// Overwrite the `TaskStats` of the task with the given UUID. It is an error if there is no
+    /// such task.
+    fn set_task_stats(&self, uuid: &Uuid, stats: &TaskStats) -> Result<(), Error>;
+
+    /// Record that the task with the given UUID was selected as the current task. Only the most
+    /// recent `SELECTION_HISTORY_LENGTH` selections are kept.
+    fn record_selection(&self, uuid: &Uuid) -> Result<(), Error>;
+
+    /// Return the UUIDs of the tasks that were most recently selected as the current task, most
+    /// recent first. A task appears more than once if it was selected more than once.
+    fn fetch_recent_selections(&self) -> Result<Vec<Uuid>, Error>;
+
+    /// Return the break counters.
+    fn fetch_break_stats(&self) -> Result<BreakStats, Error>;
+
+    /// Overwrite the break counters.
+    fn set_break_stats(&self, stats: &BreakStats) -> Result<(), Error>;
+
+    /// Start a work session on `task` now, stopping the open session if there is one.
+    fn start_session(&self, task: &Task) -> Result<(), Error>;
+
+    /// Stop the open work session now. Returns false if there was no open session.
+    fn stop_session(&self) -> Result<bool, Error>;
+
+    /// Return the open work session, if there is one.
+    fn fetch_open_session(&self) -> Result<Option<Session>, Error>;
+
+    /// Return the work sessions that were still running at `since` or started after it, oldest
+    /// first.
+    fn fetch_sessions_since(&self, since: &DateTime<Utc>) -> Result<Vec<Session>, Error>;
+
     /// Commit the transaction. If this method is not called, implementors of this trait should
     /// default to rolling back the transaction upon drop.
     fn commit(self) -> Result<(), Error>;
@@ -228,6 +269,15 @@ impl<'conn> DBTransaction for SqliteTransaction<'conn> {
 
     fn remove_task(&self, id: &RowId) -> Result<(), Error> {
         let tx = &self.transaction;
+        for table in &["task_details", "task_tags"] {
+            tx.execute_named(
+                &format!("DELETE FROM {}
+                WHERE
+                    task_uuid = (SELECT uuid FROM tasks WHERE id = :task_id)", table),
+                &[(":task_id", &id.id)])
+                .map_err(|e| format_err!("Error deleting task details: {}", e))?;
+        }
+
         let rows_modified = tx.execute_named(
             "DELETE FROM tasks
             WHERE
@@ -247,6 +297,13 @@ impl<'conn> DBTransaction for SqliteTransaction<'conn> {
     fn try_remove_task_by_uuid(&self, uuid: &Uuid) -> Result<(), Error> {
         let tx = &self.transaction;
         let uuid_bytes: &[u8] = uuid.as_bytes();
+        for table in &["task_details", "task_tags"] {
+            tx.execute_named(
+                &format!("DELETE FROM {} WHERE task_uuid = :task_uuid", table),
+                &[(":task_uuid", &uuid_bytes)])
+                .map_err(|e| format_err!("Error deleting task details: {}", e))?;
+        }
+
         let rows_modified = tx.execute_named(
             "DELETE FROM tasks
             WHERE
@@ -260,6 +317,197 @@ impl<'conn> DBTransaction for SqliteTransaction<'conn> {
         Ok(())
     }
 
+    fn fetch_task_stats(&self) -> Result<HashMap<Uuid, TaskStats>, Error> {
+        let tx = &self.transaction;
+
+        let mut stmt = tx.prepare_cached(
+            "SELECT uuid, date_added, last_skipped
+            FROM tasks
+            ")
+            .map_err(|e| format_err!("Error preparing task stats query: {}", e))?;
+        let rows = stmt.query_map(NO_PARAMS, |row| {
+                let sql_uuid: SqlBlobUuid = row.get(0)?;
+                let stats = TaskStats {
+                    date_added: row.get(1)?,
+                    last_skipped: row.get(2)?,
+                };
+
+                Ok((sql_uuid.uuid, stats))
+             })
+            .map_err(|e| format_err!("Error executing task stats query: {}", e))?;
+
+        let mut stats = HashMap::new();
+        for row_res in rows {
+            let (uuid, task_stats) =
+                row_res.map_err(|e| format_err!("Error deserializing task stats row from database: {}", e))?;
+            stats.insert(uuid, task_stats);
+        }
+        Ok(stats)
+    }
+
+    fn set_task_stats(&self, uuid: &Uuid, stats: &TaskStats) -> Result<(), Error> {
+        let tx = &self.transaction;
+        let uuid_bytes: &[u8] = uuid.as_bytes();
+        let rows_modified = tx.execute_named(
+            "UPDATE tasks
+            SET date_added = :date_added, last_skipped = :last_skipped
+            WHERE
+                uuid = :task_uuid",
+            &[(":date_added", &stats.date_added),
+              (":last_skipped", &stats.last_skipped),
+              (":task_uuid", &uuid_bytes)])
+            .map_err(|e| format_err!("Error updating task stats: {}", e))?;
+        if rows_modified == 0 {
+            return Err(format_err!("Error updating task stats: No task with uuid {}.", uuid));
+        }
+        else if rows_modified > 1 {
+            return Err(format_err!("Error updating task stats: More than one row was modified: {}.", rows_modified));
+        }
+
+        Ok(())
+    }
+
+    fn record_selection(&self, uuid: &Uuid) -> Result<(), Error> {
+        let tx = &self.transaction;
+        let uuid_bytes: &[u8] = uuid.as_bytes();
+
+        tx.execute_named(
+            "INSERT INTO selection_history (task_uuid, date_selected) VALUES (:task_uuid, :date_selected)",
+            &[(":task_uuid", &uuid_bytes),
+              (":date_selected", &Utc::now())])
+            .map_err(|e| format_err!("Error recording selection: {}", e))?;
+
+        // ids are monotonically increasing, so this removes everything but the most recent
+        tx.execute_named(
+            "DELETE FROM selection_history
+            WHERE
+                id <= (SELECT MAX(id) FROM selection_history) - :length",
+            &[(":length", &SELECTION_HISTORY_LENGTH)])
+            .map_err(|e| format_err!("Error pruning selection history: {}", e))?;
+
+        Ok(())
+    }
+
+    fn fetch_recent_selections(&self) -> Result<Vec<Uuid>, Error> {
+        let tx = &self.transaction;
+
+        let mut stmt = tx.prepare_cached(
+            "SELECT task_uuid
+            FROM selection_history
+            ORDER BY id DESC
+            ")
+            .map_err(|e| format_err!("Error preparing selection history query: {}", e))?;
+        let rows = stmt.query_map(NO_PARAMS, |row| {
+                let sql_uuid: SqlBlobUuid = row.get(0)?;
+                Ok(sql_uuid.uuid)
+             })
+            .map_err(|e| format_err!("Error executing selection history query: {}", e))?;
+
+        let mut uuids = Vec::new();
+        for row_res in rows {
+            let uuid = row_res.map_err(|e| format_err!("Error deserializing selection history row from database: {}", e))?;
+            uuids.push(uuid);
+        }
+        Ok(uuids)
+    }
+
+    fn fetch_break_stats(&self) -> Result<BreakStats, Error> {
+        let tx = &self.transaction;
+
+        tx.query_row(
+            "SELECT tasks_completed, last_break FROM break_stats WHERE id = 1",
+            NO_PARAMS,
+            |row| {
+                Ok(BreakStats {
+                    tasks_completed: row.get(0)?,
+                    last_break: row.get(1)?,
+                })
+            }
+        ).map_err(|e| format_err!("Error getting break stats from database: {}", e))
+    }
+
+    fn set_break_stats(&self, stats: &BreakStats) -> Result<(), Error> {
+        let tx = &self.transaction;
+        let rows_modified = tx.execute_named(
+            "UPDATE break_stats
+            SET tasks_completed = :tasks_completed, last_break = :last_break
+            WHERE id = 1",
+            &[(":tasks_completed", &stats.tasks_completed),
+              (":last_break", &stats.last_break)])
+            .map_err(|e| format_err!("Error updating break stats: {}", e))?;
+        if rows_modified != 1 {
+            return Err(format_err!("Error updating break stats: {} rows were modified.", rows_modified));
+        }
+
+        Ok(())
+    }
+
+    fn start_session(&self, task: &Task) -> Result<(), Error> {
+        self.stop_session()?;
+
+        let tx = &self.transaction;
+        let uuid_bytes: &[u8] = task.uuid().as_bytes();
+        tx.execute_named(
+            "INSERT INTO sessions (task_uuid, task, category, started)
+            VALUES (:task_uuid, :task, :category, :started)",
+            &[(":task_uuid", &uuid_bytes),
+              (":task", &task.task()),
+              (":category", &task.is_break()),
+              (":started", &Utc::now()),
+            ],
+        ).map_err(|e| format_err!("Error starting work session: {}", e))?;
+
+        Ok(())
+    }
+
+    fn stop_session(&self) -> Result<bool, Error> {
+        let tx = &self.transaction;
+        let rows_modified = tx.execute_named(
+            "UPDATE sessions
+            SET stopped = :stopped
+            WHERE stopped IS NULL",
+            &[(":stopped", &Utc::now())])
+            .map_err(|e| format_err!("Error stopping work session: {}", e))?;
+
+        Ok(rows_modified > 0)
+    }
+
+    fn fetch_open_session(&self) -> Result<Option<Session>, Error> {
+        let tx = &self.transaction;
+        let mut stmt = tx.prepare_cached(
+            "SELECT task_uuid, task, category, started, stopped
+            FROM sessions
+            WHERE stopped IS NULL
+            ORDER BY id DESC
+            LIMIT 1")
+            .map_err(|e| format_err!("Error preparing open session query: {}", e))?;
+
+        let rows: Vec<SQLResult<Session>> = stmt.query_map(NO_PARAMS, session_from_row)
+            .map_err(|e| format_err!("Error executing open session query: {}", e))?
+            .collect();
+
+        match rows.into_iter().next() {
+            Some(row) => Ok(Some(row.map_err(|e| format_err!("Error deserializing session row from database: {}", e))?)),
+            None => Ok(None),
+        }
+    }
+
+    fn fetch_sessions_since(&self, since: &DateTime<Utc>) -> Result<Vec<Session>, Error> {
+        let tx = &self.transaction;
+        let mut stmt = tx.prepare_cached(
+            "SELECT task_uuid, task, category, started, stopped
+            FROM sessions
+            WHERE stopped IS NULL OR stopped > :since
+            ORDER BY id")
+            .map_err(|e| format_err!("Error preparing sessions query: {}", e))?;
+
+        let sessions: SQLResult<Vec<Session>> = stmt.query_map_named(&[(":since", since)], session_from_row)
+            .map_err(|e| format_err!("Error executing sessions query: {}", e))?
+            .collect();
+
+        sessions.map_err(|e| format_err!("Error deserializing session row from database: {}", e))
+    }
+
     fn commit(self) -> Result<(), Error> {
         let tx = self.transaction;
 
@@ -276,3 +524,16 @@ impl<'conn> DBTransaction for SqliteTransaction<'conn> {
 
 #[cfg(test)]
 mod tests;
+
+/// Deserialize a `Session` from a row of `task_uuid, task, category, started, stopped`.
+pub fn session_from_row(row: &rusqlite::Row) -> SQLResult<Session> {
+    let sql_uuid: SqlBlobUuid = row.get(0)?;
+    let is_break: bool = row.get(2)?;
+    Ok(Session {
+        task_uuid: sql_uuid.uuid,
+        task: row.get(1)?,
+        category: if is_break { Category::Break } else { Category::Task },
+        started: row.get(3)?,
+        stopped: row.get(4)?,
+    })
+}
@@ -0,0 +1,175 @@
+use std::fmt;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use failure::Error;
+use rusqlite::NO_PARAMS;
+use rusqlite::Result as SQLResult;
+use uuid::Uuid;
+
+use crate::db::{SqliteTransaction, DBBackend};
+use crate::db::transaction::SqlBlobUuid;
+use crate::sync::ReplicaUuid;
+use crate::task::{Task, TaskDetails};
+
+/// Why a task was removed from the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalSource {
+    /// The task was removed on this replica, e.g. deleted in the TUI or with `remove`.
+    Local,
+    /// The task was completed.
+    Completed,
+    /// The task was removed by a sync operation from the given replica.
+    Replica(ReplicaUuid),
+}
+
+impl RemovalSource {
+    /// The name stored in the database and used in machine-readable output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RemovalSource::Local => "local",
+            RemovalSource::Completed => "completed",
+            RemovalSource::Replica(_) => "replica",
+        }
+    }
+
+    /// The replica the removal came from, if it came from sync.
+    pub fn replica(&self) -> Option<&ReplicaUuid> {
+        match self {
+            RemovalSource::Replica(replica) => Some(replica),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn from_parts(name: &str, replica: Option<ReplicaUuid>) -> Result<RemovalSource, Error> {
+        match (name, replica) {
+            ("local", None) => Ok(RemovalSource::Local),
+            ("completed", None) => Ok(RemovalSource::Completed),
+            ("replica", Some(replica)) => Ok(RemovalSource::Replica(replica)),
+            _ => Err(format_err!("Invalid removal source \"{}\".", name)),
+        }
+    }
+}
+
+impl fmt::Display for RemovalSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RemovalSource::Replica(replica) => write!(f, "replica {}", replica),
+            source => f.write_str(source.name()),
+        }
+    }
+}
+
+/// A task that was removed from the database, kept in the trash so that it can be restored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashedTask {
+    pub task: Task,
+    pub details: TaskDetails,
+    pub source: RemovalSource,
+    pub date_removed: DateTime<Utc>,
+    /// A note added when the task was completed, e.g. how it went.
+    pub note: Option<String>,
+}
+
+// Trash impls
+impl<'conn> SqliteTransaction<'conn> {
+    /// Copy `task` and its details into the trash before it is removed. A task is in the trash at
+    /// most once, so an older copy of it is replaced.
+    pub(super) fn move_to_trash(&self, task: &Task, source: &RemovalSource) -> Result<(), Error> {
+        let details = self.fetch_task_details(task.uuid())?;
+        self.insert_into_trash(task, &details, source, &Utc::now(), None)
+    }
+
+    /// Store `task` in the trash as removed by `source` at `date_removed`, replacing any older copy.
+    pub(super) fn insert_into_trash(&self, task: &Task, details: &TaskDetails, source: &RemovalSource,
+                                    date_removed: &DateTime<Utc>, note: Option<&str>) -> Result<(), Error> {
+        // not INSERT OR REPLACE, which doesn't run the delete triggers that keep the search index
+        // up to date
+        self.delete_from_trash(task.uuid())?;
+        let uuid_bytes: &[u8] = task.uuid().as_bytes();
+        let replica_bytes: Option<&[u8]> = source.replica().map(|replica| &replica.as_bytes()[..]);
+
+        self.transaction.execute_named(
+            "INSERT INTO trash (task, priority, category, uuid, due, estimate, tags, source, replica_uuid, date_removed, note)
+            VALUES (:task, :priority, :category, :uuid, :due, :estimate, :tags, :source, :replica_uuid, :date_removed, :note)",
+            &[(":task", &task.task()),
+              (":priority", &task.priority()),
+              (":category", &task.is_break()),
+              (":uuid", &uuid_bytes),
+              (":due", &details.due),
+              (":estimate", &details.estimate.map(|e| e.num_seconds())),
+              // tags can't contain whitespace
+              (":tags", &details.tags.join(" ")),
+              (":source", &source.name()),
+              (":replica_uuid", &replica_bytes),
+              (":date_removed", date_removed),
+              (":note", &note),
+            ],
+        ).map_err(|e| format_err!("Error moving task to trash: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Set the note of the task with the given UUID in the trash. Returns false if it isn't there.
+    pub(super) fn set_trash_note(&self, uuid: &Uuid, note: &str) -> Result<bool, Error> {
+        let uuid_bytes: &[u8] = uuid.as_bytes();
+        let rows = self.transaction.execute_named("UPDATE trash SET note = :note WHERE uuid = :uuid",
+                                                  &[(":note", &note), (":uuid", &uuid_bytes)])
+            .map_err(|e| format_err!("Error adding note to task in trash: {}", e))?;
+        Ok(rows > 0)
+    }
+
+    /// Remove the task with the given UUID from the trash, if it is there.
+    pub(super) fn delete_from_trash(&self, uuid: &Uuid) -> Result<(), Error> {
+        let uuid_bytes: &[u8] = uuid.as_bytes();
+        self.transaction.execute_named("DELETE FROM trash WHERE uuid = :uuid", &[(":uuid", &uuid_bytes)])
+            .map_err(|e| format_err!("Error removing task from trash: {}", e))?;
+        Ok(())
+    }
+
+    pub(super) fn fetch_trashed_tasks(&self) -> Result<Vec<TrashedTask>, Error> {
+        let mut stmt = self.transaction.prepare_cached(
+            "SELECT task, priority, category, uuid, due, estimate, tags, source, replica_uuid, date_removed, note
+            FROM trash
+            ORDER BY date_removed DESC, id DESC")
+            .map_err(|e| format_err!("Error preparing trash query: {}", e))?;
+
+        let rows = stmt.query_map(NO_PARAMS, |row| {
+                let uuid: SqlBlobUuid = row.get(3)?;
+                let replica: Option<SqlBlobUuid> = row.get(8)?;
+                Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?, row.get::<_, bool>(2)?, uuid.uuid,
+                    row.get::<_, Option<NaiveDate>>(4)?, row.get::<_, Option<i64>>(5)?, row.get::<_, String>(6)?,
+                    row.get::<_, String>(7)?, replica.map(|replica| replica.uuid), row.get::<_, DateTime<Utc>>(9)?,
+                    row.get::<_, Option<String>>(10)?))
+            })
+            .map_err(|e| format_err!("Error executing trash query: {}", e))?
+            .collect::<SQLResult<Vec<_>>>()
+            .map_err(|e| format_err!("Error deserializing trash row from database: {}", e))?;
+
+        let mut trashed = Vec::new();
+        for (task, priority, category, uuid, due, estimate, tags, source, replica, date_removed, note) in rows {
+            let task = Task::from_parts(task, priority, category, uuid)
+                .map_err(|e| format_err!("Invalid task read from trash: {}", e))?;
+            let details = TaskDetails {
+                tags: tags.split_whitespace().map(String::from).collect(),
+                due,
+                estimate: estimate.map(Duration::seconds),
+            };
+            let source = RemovalSource::from_parts(&source, replica)?;
+            trashed.push(TrashedTask { task, details, source, date_removed, note });
+        }
+
+        Ok(trashed)
+    }
+
+    /// Remove tasks from the trash that were removed before `removed_before`, or every task if it
+    /// is `None`. Returns the number removed.
+    pub(super) fn delete_trashed_tasks(&self, removed_before: Option<DateTime<Utc>>) -> Result<usize, Error> {
+        let rows = match removed_before {
+            Some(date) => self.transaction.execute_named(
+                "DELETE FROM trash WHERE date_removed < :date", &[(":date", &date)]),
+            None => self.transaction.execute("DELETE FROM trash", NO_PARAMS),
+        }.map_err(|e| format_err!("Error emptying trash: {}", e))?;
+
+        Ok(rows)
+    }
+}
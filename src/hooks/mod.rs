@@ -0,0 +1,126 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use failure::Error;
+
+use crate::db::DBBackend;
+use crate::task::Task;
+
+/// Something that happened to a task during a command, which can trigger a hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    /// A task was added.
+    Add,
+    /// A task was completed and removed.
+    Complete,
+    /// The current task was skipped, leaving it in the task list.
+    Skip,
+    /// A task was selected as the new current task.
+    Select,
+}
+
+impl HookEvent {
+    /// The filename of the executable in the hooks directory that is run for this event.
+    pub fn hook_name(&self) -> &'static str {
+        match self {
+            HookEvent::Add => "on-add",
+            HookEvent::Complete => "on-complete",
+            HookEvent::Skip => "on-skip",
+            HookEvent::Select => "on-select",
+        }
+    }
+}
+
+/// The current task at some point during a command. Commands report which tasks they added,
+/// completed or skipped themselves, and comparing snapshots from before and after a command fills
+/// in the task that was selected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskSnapshot {
+    /// The current task.
+    pub current: Option<Task>,
+}
+
+impl TaskSnapshot {
+    /// Take a snapshot of the current task in the database.
+    pub fn capture(tx: &impl DBBackend) -> Result<TaskSnapshot, Error> {
+        let current = tx.fetch_current_task()
+            .map_err(|e| format_err!("Could not get current task from database. {}", e))?;
+
+        Ok(TaskSnapshot { current })
+    }
+
+    /// The current task in `after`, if it is a different task from the current task here.
+    pub fn selected(&self, after: &TaskSnapshot) -> Option<Task> {
+        let current_uuid = |snapshot: &TaskSnapshot| snapshot.current.as_ref().map(|t| *t.uuid());
+        if current_uuid(self) == current_uuid(after) {
+            return None;
+        }
+        after.current.clone()
+    }
+}
+
+/// Runs user executables from a hooks directory when tasks are added, completed, skipped, or
+/// selected. The affected task is written to the hook's stdin as JSON, and its stdout is returned
+/// to be displayed along with the command's output.
+#[derive(Debug, Clone)]
+pub struct Hooks {
+    dir: PathBuf,
+}
+
+impl Hooks {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Hooks {
+        Hooks {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Run the hook for `event` with `task`, returning the lines it printed to stdout. If there is
+    /// no hook for the event, nothing is run and the result is empty. It is an error if the hook
+    /// cannot be run or exits unsuccessfully.
+    pub fn run(&self, event: HookEvent, task: &Task) -> Result<Vec<String>, Error> {
+        let path = self.dir.join(event.hook_name());
+        if !path.is_file() {
+            return Ok(Vec::new());
+        }
+
+        let task_json = serde_json::to_string(task)
+            .map_err(|e| format_err!("Could not serialize task for hook \"{}\": {}", event.hook_name(), e))?;
+
+        let mut child = Command::new(&path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format_err!("Could not run hook \"{}\": {}", path.display(), e))?;
+
+        // the hook may exit without reading its input, so a broken pipe is not an error here. if
+        // something actually went wrong, the exit status will tell us.
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(task_json.as_bytes());
+        }
+
+        let output = child.wait_with_output()
+            .map_err(|e| format_err!("Could not run hook \"{}\": {}", path.display(), e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format_err!("Hook \"{}\" failed ({}): {}", event.hook_name(), output.status, stderr.trim_end()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).lines().map(|l| l.to_string()).collect())
+    }
+
+    /// Run the hooks for each of `events` in order, stopping at the first one that fails.
+    pub fn run_all(&self, events: &[(HookEvent, Task)]) -> Result<Vec<String>, Error> {
+        let mut output = Vec::new();
+        for (event, task) in events {
+            output.extend(self.run(*event, task)?);
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,78 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use tempfile::tempdir;
+
+use crate::task::test_utils::{example_task_1, example_task_break_1};
+
+use super::{Hooks, HookEvent, TaskSnapshot};
+
+/// Write an executable shell script named `name` into `dir`.
+fn write_hook(dir: &Path, name: &str, script: &str) {
+    let path = dir.join(name);
+    fs::write(&path, format!("#!/bin/sh\n{}\n", script)).expect("Failed to write hook");
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).expect("Failed to make hook executable");
+}
+
+#[test]
+fn test_hooks_snapshot_unchanged() {
+    let snapshot = TaskSnapshot { current: Some(example_task_1()) };
+
+    assert_eq!(snapshot.selected(&snapshot), None);
+}
+
+#[test]
+/// Adding a task to an empty database selects it.
+fn test_hooks_snapshot_add_select() {
+    let before = TaskSnapshot { current: None };
+    let after = TaskSnapshot { current: Some(example_task_1()) };
+
+    assert_eq!(before.selected(&after), Some(example_task_1()));
+}
+
+#[test]
+/// Removing the current task selects another one.
+fn test_hooks_snapshot_removed() {
+    let before = TaskSnapshot { current: Some(example_task_1()) };
+    let after = TaskSnapshot { current: Some(example_task_break_1()) };
+
+    assert_eq!(before.selected(&after), Some(example_task_break_1()));
+}
+
+#[test]
+fn test_hooks_run_missing() {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let hooks = Hooks::new(dir.path());
+
+    let output = hooks.run(HookEvent::Add, &example_task_1()).expect("Running missing hook failed");
+    assert!(output.is_empty());
+}
+
+#[test]
+/// The hook gets the task as JSON on stdin, and its stdout is returned.
+fn test_hooks_run_output() {
+    let dir = tempdir().expect("Failed to create temp dir");
+    write_hook(dir.path(), "on-complete", "echo completed; cat");
+    let hooks = Hooks::new(dir.path());
+
+    let task = example_task_1();
+    let output = hooks.run(HookEvent::Complete, &task).expect("Running hook failed");
+
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[0], "completed");
+    let parsed: crate::task::Task = serde_json::from_str(&output[1]).expect("Hook input was not a task");
+    assert_eq!(parsed, task);
+}
+
+#[test]
+fn test_hooks_run_fails() {
+    let dir = tempdir().expect("Failed to create temp dir");
+    write_hook(dir.path(), "on-add", "echo no thanks >&2; exit 3");
+    let hooks = Hooks::new(dir.path());
+
+    let res = hooks.run(HookEvent::Add, &example_task_1());
+    let err = res.expect_err("Failing hook did not return an error");
+    assert!(err.to_string().contains("on-add"), "Incorrect error: {}", err);
+    assert!(err.to_string().contains("no thanks"), "Incorrect error: {}", err);
+}
@@ -0,0 +1,285 @@
+use chrono::{Duration, NaiveDate, Utc};
+use failure::Error;
+use uuid::Uuid;
+
+use crate::task::{Task, TaskDetails};
+use crate::timer::{MAX_DURATION_SECONDS, duration_from_seconds};
+
+use super::DatabaseExport;
+
+/// iCalendar lines longer than this many bytes are folded.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// The CATEGORIES value that marks a break.
+const BREAK_CATEGORY: &str = "break";
+
+/// The exact priority of tasks whose priority is above 9, which is the most PRIORITY can hold.
+const PRIORITY_PROPERTY: &str = "X-TASKERIZER-PRIORITY";
+
+/// The estimate in seconds. VTODO's DURATION needs a start time, so it isn't used.
+const ESTIMATE_PROPERTY: &str = "X-TASKERIZER-ESTIMATE";
+
+/// The iCalendar PRIORITY for `priority`. PRIORITY is 1 for the highest priority to 9 for the
+/// lowest, so priority 1 is 9, and priorities of 9 and above are 1.
+fn ical_priority(priority: u32) -> u32 {
+    10 - priority.min(9)
+}
+
+/// The priority for an iCalendar PRIORITY, or `None` for 0, which means no priority.
+fn task_priority(ical_priority: u32) -> Result<Option<u32>, Error> {
+    match ical_priority {
+        0 => Ok(None),
+        p @ 1..=9 => Ok(Some(10 - p)),
+        p => Err(format_err!("Invalid PRIORITY {}: expected 0 to 9.", p)),
+    }
+}
+
+/// Escape a TEXT value.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Unescape a TEXT value, or split a list of TEXT values on unescaped commas if `split` is true.
+fn unescape(text: &str, split: bool) -> Vec<String> {
+    let mut values = vec![String::new()];
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        let value = values.last_mut().unwrap();
+        match c {
+            '\\' => match chars.next() {
+                Some('n') | Some('N') => value.push('\n'),
+                Some(c) => value.push(c),
+                None => {}
+            },
+            ',' if split => values.push(String::new()),
+            c => value.push(c),
+        }
+    }
+    values
+}
+
+/// Fold a content line so that no line is longer than `MAX_LINE_LENGTH` bytes, and end it with
+/// CRLF.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// Join folded lines, returning each content line with the number of the line it starts on.
+fn unfold(input: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        match (line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')), lines.last_mut()) {
+            (Some(continuation), Some((_, last))) => last.push_str(continuation),
+            _ => lines.push((idx + 1, line.to_string())),
+        }
+    }
+    lines
+}
+
+/// Split a content line into its upper-cased name and its value, skipping parameters. Colons in
+/// quoted parameter values don't end the name.
+fn parse_line(line: &str) -> Result<(String, &str), Error> {
+    let mut quoted = false;
+    let colon = line.char_indices()
+        .find(|&(_, c)| {
+            if c == '"' {
+                quoted = !quoted;
+            }
+            c == ':' && !quoted
+        })
+        .map(|(idx, _)| idx)
+        .ok_or_else(|| format_err!("Invalid content line \"{}\": expected a colon.", line))?;
+
+    let name = line[..colon].split(';').next().unwrap_or_default().to_uppercase();
+    Ok((name, &line[colon + 1..]))
+}
+
+/// The properties of a VTODO that are read.
+#[derive(Debug, Default)]
+struct Todo {
+    uid: Option<String>,
+    summary: Option<String>,
+    priority: Option<u32>,
+    exact_priority: Option<u32>,
+    categories: Vec<String>,
+    due: Option<NaiveDate>,
+    estimate: Option<Duration>,
+    done: bool,
+}
+
+impl Todo {
+    fn set(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        match name {
+            "UID" => self.uid = Some(value.to_string()),
+            "SUMMARY" => self.summary = unescape(value, false).pop(),
+            "PRIORITY" => {
+                let priority = value.parse().map_err(|e| format_err!("Invalid PRIORITY \"{}\": {}", value, e))?;
+                self.priority = task_priority(priority)?;
+            }
+            PRIORITY_PROPERTY => {
+                self.exact_priority = Some(value.parse()
+                    .map_err(|e| format_err!("Invalid {} \"{}\": {}", PRIORITY_PROPERTY, value, e))?);
+            }
+            "CATEGORIES" => {
+                self.categories.extend(unescape(value, true).into_iter()
+                    .map(|category| category.trim().to_string())
+                    .filter(|category| !category.is_empty()));
+            }
+            // DUE can be a date or a date and time, and only the date is kept
+            "DUE" => {
+                let date = value.get(..8).unwrap_or(value);
+                self.due = Some(NaiveDate::parse_from_str(date, "%Y%m%d")
+                    .map_err(|e| format_err!("Invalid DUE \"{}\": {}", value, e))?);
+            }
+            ESTIMATE_PROPERTY => {
+                let seconds: i64 = value.parse()
+                    .map_err(|e| format_err!("Invalid {} \"{}\": {}", ESTIMATE_PROPERTY, value, e))?;
+                self.estimate = Some(duration_from_seconds(seconds).ok_or_else(|| {
+                    format_err!("Invalid {} \"{}\": must be greater than 0 and at most {}.",
+                                ESTIMATE_PROPERTY, value, MAX_DURATION_SECONDS)
+                })?);
+            }
+            "STATUS" => self.done = matches!(value.to_uppercase().as_str(), "COMPLETED" | "CANCELLED"),
+            "COMPLETED" => self.done = true,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// The task, or `None` if the to-do is completed or cancelled. A UID that isn't a UUID gets a
+    /// new UUID.
+    fn into_task(self) -> Result<Option<(Task, TaskDetails)>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let mut reward = false;
+        let mut details = TaskDetails::default();
+        for category in &self.categories {
+            if category.eq_ignore_ascii_case(BREAK_CATEGORY) {
+                reward = true;
+            }
+            else {
+                // tags can't contain whitespace
+                details.add_tag(&category.split_whitespace().collect::<Vec<_>>().join("-"));
+            }
+        }
+        details.due = self.due;
+        details.estimate = self.estimate;
+
+        let uuid = self.uid.and_then(|uid| Uuid::parse_str(&uid).ok()).unwrap_or_else(Uuid::new_v4);
+        let summary = self.summary.ok_or_else(|| format_err!("VTODO has no SUMMARY."))?;
+        let priority = self.exact_priority.or(self.priority).unwrap_or(1);
+        let task = Task::from_parts(summary, priority, reward, uuid)
+            .map_err(|e| format_err!("Task was invalid: {}", e))?;
+
+        Ok(Some((task, details)))
+    }
+}
+
+/// Format a task as a VTODO component.
+fn format_task(task: &Task, details: &TaskDetails, stamp: &str) -> String {
+    let mut lines = vec![
+        "BEGIN:VTODO".to_string(),
+        format!("UID:{}", task.uuid()),
+        format!("DTSTAMP:{}", stamp),
+        format!("SUMMARY:{}", escape(task.task())),
+        format!("PRIORITY:{}", ical_priority(task.priority())),
+        "STATUS:NEEDS-ACTION".to_string(),
+    ];
+    if task.priority() > 9 {
+        lines.push(format!("{}:{}", PRIORITY_PROPERTY, task.priority()));
+    }
+
+    let mut categories: Vec<String> = details.tags.iter().map(|tag| escape(tag)).collect();
+    if task.is_break() {
+        categories.push(BREAK_CATEGORY.to_string());
+    }
+    if !categories.is_empty() {
+        lines.push(format!("CATEGORIES:{}", categories.join(",")));
+    }
+    if let Some(due) = details.due {
+        lines.push(format!("DUE;VALUE=DATE:{}", due.format("%Y%m%d")));
+    }
+    if let Some(estimate) = details.estimate {
+        lines.push(format!("{}:{}", ESTIMATE_PROPERTY, estimate.num_seconds()));
+    }
+    lines.push("END:VTODO".to_string());
+
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+/// Write every task in `export` as a VTODO in an iCalendar file.
+pub fn write(export: &DatabaseExport) -> Result<String, Error> {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut output = fold("BEGIN:VCALENDAR") + &fold("VERSION:2.0") + &fold("PRODID:-//taskerizer//tkzr//EN");
+    for (task, details) in export.tasks_with_details()? {
+        output.push_str(&format_task(task, &details, &stamp));
+    }
+    output.push_str(&fold("END:VCALENDAR"));
+
+    Ok(output)
+}
+
+/// Read the VTODOs in an iCalendar file. Completed and cancelled to-dos and other components, like
+/// events, are skipped.
+pub fn read(input: &str) -> Result<DatabaseExport, Error> {
+    let mut tasks = Vec::new();
+    let mut todo: Option<(usize, Todo)> = None;
+    // the number of components, like alarms, open inside the current VTODO
+    let mut nested = 0;
+
+    for (line_number, line) in unfold(input) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (name, value) = parse_line(&line).map_err(|e| format_err!("Line {}: {}", line_number, e))?;
+        let component = value.trim().to_uppercase();
+
+        match (name.as_str(), &mut todo) {
+            ("BEGIN", None) if component == "VTODO" => todo = Some((line_number, Todo::default())),
+            ("BEGIN", Some(_)) => nested += 1,
+            ("END", Some(_)) if nested > 0 => nested -= 1,
+            ("END", Some(_)) if component == "VTODO" => {
+                let (start, finished) = todo.take().unwrap();
+                if let Some(task) = finished.into_task().map_err(|e| format_err!("Line {}: {}", start, e))? {
+                    tasks.push(task);
+                }
+            }
+            (_, Some((_, todo))) if nested == 0 => {
+                todo.set(&name, value).map_err(|e| format_err!("Line {}: {}", line_number, e))?;
+            }
+            _ => {}
+        }
+    }
+    if let Some((start, _)) = todo {
+        return Err(format_err!("Line {}: VTODO is not ended.", start));
+    }
+
+    DatabaseExport::from_tasks(tasks)
+}
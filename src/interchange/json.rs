@@ -0,0 +1,279 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use failure::Error;
+use uuid::Uuid;
+
+use crate::db::DBBackend;
+use crate::sync::{USetOp, USetOpMsg, ReplicaUuid};
+use crate::task::{Task, TaskDetails};
+use crate::timer::{MAX_DURATION_SECONDS, duration_from_seconds};
+
+use super::ImportSummary;
+
+/// The version of the export document format. Documents with a newer version can't be imported.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// A task's `TaskDetails` in an export document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedDetails {
+    pub uuid: Uuid,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The due date as "YYYY-MM-DD".
+    pub due: Option<String>,
+    pub estimate_seconds: Option<i64>,
+}
+
+/// A replica in an export document. Servers have an API URL and clients don't.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedReplica {
+    pub uuid: ReplicaUuid,
+    pub api_url: Option<String>,
+}
+
+/// Everything needed to recreate a database on another machine: the tasks and their details, the
+/// current task, and the sync state. Selection history, statistics and time tracking are not
+/// included.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatabaseExport {
+    pub version: u32,
+    pub tasks: Vec<Task>,
+    /// Details of the tasks that have any.
+    #[serde(default)]
+    pub details: Vec<ExportedDetails>,
+    pub current: Option<Uuid>,
+    #[serde(default)]
+    pub replicas: Vec<ExportedReplica>,
+    /// U-Set operations that haven't been sent to their replica yet.
+    #[serde(default)]
+    pub unsynced_ops: Vec<USetOpMsg>,
+}
+
+/// Check a task that was deserialized, which skips the checks in `Task::from_parts`.
+fn validate_task(task: &Task) -> Result<Task, Error> {
+    Task::from_parts(task.task().to_string(), task.priority(), task.is_break(), *task.uuid())
+        .map_err(|e| format_err!("Invalid task {}: {}", task.uuid(), e))
+}
+
+impl ExportedDetails {
+    fn new(uuid: Uuid, details: &TaskDetails) -> ExportedDetails {
+        ExportedDetails {
+            uuid,
+            tags: details.tags.clone(),
+            due: details.due.map(|due| due.to_string()),
+            estimate_seconds: details.estimate.map(|estimate| estimate.num_seconds()),
+        }
+    }
+
+    fn to_details(&self) -> Result<TaskDetails, Error> {
+        let mut details = TaskDetails::default();
+        for tag in &self.tags {
+            if tag.is_empty() || tag.contains(char::is_whitespace) {
+                return Err(format_err!("Invalid tag \"{}\" on task {}.", tag, self.uuid));
+            }
+            details.add_tag(tag);
+        }
+        details.due = self.due.as_ref()
+            .map(|due| NaiveDate::parse_from_str(due, "%Y-%m-%d")
+                 .map_err(|e| format_err!("Invalid due date \"{}\" on task {}: {}", due, self.uuid, e)))
+            .transpose()?;
+        details.estimate = self.estimate_seconds
+            .map(|seconds| duration_from_seconds(seconds)
+                 .ok_or_else(|| format_err!("Invalid estimate on task {}: must be greater than 0 and at most {} seconds.",
+                                            self.uuid, MAX_DURATION_SECONDS)))
+            .transpose()?;
+
+        Ok(details)
+    }
+}
+
+impl DatabaseExport {
+    /// Export the contents of the database.
+    pub fn from_db(tx: &impl DBBackend) -> Result<DatabaseExport, Error> {
+        let tasks = tx.fetch_all_tasks()
+            .map_err(|e| format_err!("Could not get tasks from database. {}", e))?;
+
+        let mut details = Vec::new();
+        for task in &tasks {
+            let task_details = tx.fetch_task_details(task.uuid())
+                .map_err(|e| format_err!("Could not get task details from database. {}", e))?;
+            if !task_details.is_empty() {
+                details.push(ExportedDetails::new(*task.uuid(), &task_details));
+            }
+        }
+
+        let current = tx.fetch_current_task()
+            .map_err(|e| format_err!("Could not get current task from database. {}", e))?
+            .map(|task| *task.uuid());
+
+        let replicas = tx.fetch_replicas()
+            .map_err(|e| format_err!("Could not get replicas from database. {}", e))?;
+        let mut unsynced_ops = Vec::new();
+        for (uuid, _) in &replicas {
+            unsynced_ops.extend(tx.fetch_uset_op_msgs(uuid)
+                .map_err(|e| format_err!("Could not get unsynced operations from database. {}", e))?);
+        }
+        let replicas = replicas.into_iter()
+            .map(|(uuid, api_url)| ExportedReplica { uuid, api_url })
+            .collect();
+
+        Ok(DatabaseExport {
+            version: EXPORT_VERSION,
+            tasks,
+            details,
+            current,
+            replicas,
+            unsynced_ops,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| format_err!("Could not serialize export: {}", e))
+    }
+
+    /// Read an export document, checking its version and every task in it.
+    pub fn from_json(json: &str) -> Result<DatabaseExport, Error> {
+        let export: DatabaseExport = serde_json::from_str(json)
+            .map_err(|e| format_err!("Could not read export: {}", e))?;
+
+        if export.version > EXPORT_VERSION {
+            return Err(format_err!("Export version {} is newer than the version supported by this version of taskerizer ({}).",
+                                   export.version, EXPORT_VERSION));
+        }
+
+        export.validate()
+    }
+
+    /// An export of only `tasks` and their details, for formats that don't have the current task
+    /// or sync state.
+    pub(super) fn from_tasks(tasks: Vec<(Task, TaskDetails)>) -> Result<DatabaseExport, Error> {
+        let details = tasks.iter()
+            .filter(|(_, details)| !details.is_empty())
+            .map(|(task, details)| ExportedDetails::new(*task.uuid(), details))
+            .collect();
+
+        DatabaseExport {
+            version: EXPORT_VERSION,
+            tasks: tasks.into_iter().map(|(task, _)| task).collect(),
+            details,
+            current: None,
+            replicas: Vec::new(),
+            unsynced_ops: Vec::new(),
+        }.validate()
+    }
+
+    /// The tasks along with their details.
+    pub(super) fn tasks_with_details(&self) -> Result<Vec<(&Task, TaskDetails)>, Error> {
+        self.tasks.iter()
+            .map(|task| {
+                let details = match self.details.iter().find(|details| &details.uuid == task.uuid()) {
+                    Some(details) => details.to_details()?,
+                    None => TaskDetails::default(),
+                };
+                Ok((task, details))
+            })
+            .collect()
+    }
+
+    /// Check every task, and that the details and current task refer to tasks in the export.
+    fn validate(mut self) -> Result<DatabaseExport, Error> {
+        let mut uuids = HashSet::new();
+        for task in &mut self.tasks {
+            *task = validate_task(task)?;
+            if !uuids.insert(*task.uuid()) {
+                return Err(format_err!("Task {} appears more than once.", task.uuid()));
+            }
+        }
+        let replicas: HashSet<ReplicaUuid> = self.replicas.iter().map(|replica| replica.uuid).collect();
+        for msg in &mut self.unsynced_ops {
+            if !replicas.contains(&msg.deliver_to) {
+                return Err(format_err!("Unsynced operation is for replica {}, which isn't in the export.", msg.deliver_to));
+            }
+            if let USetOp::Add(task) = &msg.op {
+                msg.op = USetOp::Add(validate_task(task)?);
+            }
+        }
+        for details in &self.details {
+            if !uuids.contains(&details.uuid) {
+                return Err(format_err!("Details are for task {}, which isn't in the export.", details.uuid));
+            }
+            details.to_details()?;
+        }
+        if let Some(current) = &self.current {
+            if !uuids.contains(current) {
+                return Err(format_err!("Current task {} isn't in the export.", current));
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Import into the database. Tasks that are already in the database, by UUID, are replaced;
+    /// replicas and unsynced operations that are already there are skipped. The current task is
+    /// only changed if `replace` is true or there is no current task.
+    ///
+    /// If `replace` is true, the database is cleared first. See `DBBackend::clear_all`.
+    pub fn import(&self, tx: &impl DBBackend, replace: bool) -> Result<ImportSummary, Error> {
+        if replace {
+            tx.clear_all()
+                .map_err(|e| format_err!("Could not clear database. {}", e))?;
+        }
+
+        let mut summary = ImportSummary::default();
+
+        let existing: HashSet<Uuid> = tx.fetch_all_tasks()
+            .map_err(|e| format_err!("Could not get tasks from database. {}", e))?
+            .iter().map(|task| *task.uuid()).collect();
+        for task in &self.tasks {
+            if existing.contains(task.uuid()) {
+                tx.update_task(task)
+                    .map_err(|e| format_err!("Could not update task in database. {}", e))?;
+                summary.updated += 1;
+            }
+            else {
+                tx.add_task(task)
+                    .map_err(|e| format_err!("Could not add task to database. {}", e))?;
+                summary.added.push(task.clone());
+            }
+        }
+        for details in &self.details {
+            tx.set_task_details(&details.uuid, &details.to_details()?)
+                .map_err(|e| format_err!("Could not add task details to database. {}", e))?;
+        }
+
+        if let Some(current) = &self.current {
+            let has_current = tx.fetch_current_task()
+                .map_err(|e| format_err!("Could not get current task from database. {}", e))?
+                .is_some();
+            if !has_current || replace {
+                tx.set_current_task_by_uuid(current)
+                    .map_err(|e| format_err!("Could not set current task. {}", e))?;
+            }
+        }
+
+        let existing: HashSet<ReplicaUuid> = tx.fetch_replicas()
+            .map_err(|e| format_err!("Could not get replicas from database. {}", e))?
+            .into_iter().map(|(uuid, _)| uuid).collect();
+        for replica in self.replicas.iter().filter(|replica| !existing.contains(&replica.uuid)) {
+            match &replica.api_url {
+                Some(api_url) => tx.store_replica_server(&replica.uuid, api_url),
+                None => tx.store_replica_client(&replica.uuid),
+            }.map_err(|e| format_err!("Could not add replica to database. {}", e))?;
+            summary.replicas += 1;
+        }
+
+        for msg in &self.unsynced_ops {
+            let existing = tx.fetch_uset_op_msgs(&msg.deliver_to)
+                .map_err(|e| format_err!("Could not get unsynced operations from database. {}", e))?;
+            if !existing.contains(msg) {
+                tx.store_uset_op_msg(msg)
+                    .map_err(|e| format_err!("Could not add unsynced operation to database. {}", e))?;
+                summary.unsynced_ops += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+}
@@ -0,0 +1,38 @@
+use failure::Error;
+
+use super::DatabaseExport;
+use super::todotxt::{format_task, parse_task};
+
+/// The text of an unchecked checklist item like `- [ ] call mom`, or `None` for other lines and
+/// checked items.
+fn unchecked_item(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    ["- [ ]", "* [ ]", "+ [ ]"].iter()
+        .filter_map(|marker| line.strip_prefix(marker))
+        .find(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+}
+
+/// Write every task in `export` as a Markdown checklist. Each item is written like a todo.txt
+/// line, e.g. `- [ ] (A) call mom +family @break id:<uuid>`.
+pub fn write(export: &DatabaseExport) -> Result<String, Error> {
+    export.tasks_with_details()?.iter()
+        .map(|(task, details)| Ok(format!("- [ ] {}\n", format_task(task, details)?)))
+        .collect()
+}
+
+/// Read the unchecked items of the Markdown checklists in `input`, which are parsed like todo.txt
+/// lines. Everything else, including checked items, is skipped.
+pub fn read(input: &str) -> Result<DatabaseExport, Error> {
+    let mut tasks = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        let item = match unchecked_item(line) {
+            Some(item) => item,
+            None => continue,
+        };
+        if let Some(task) = parse_task(item).map_err(|e| format_err!("Line {}: {}", idx + 1, e))? {
+            tasks.push(task);
+        }
+    }
+
+    DatabaseExport::from_tasks(tasks)
+}
@@ -0,0 +1,91 @@
+//! Reading and writing the task database in other formats, for backups and for moving tasks
+//! between machines or other programs without syncing.
+
+use std::path::Path;
+use std::str::FromStr;
+
+use failure::Error;
+
+use crate::task::Task;
+
+mod json;
+mod todotxt;
+mod markdown;
+mod ical;
+pub mod taskwarrior;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::json::{DatabaseExport, ExportedDetails, ExportedReplica, EXPORT_VERSION};
+
+/// What was changed by an import.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Tasks that were not in the database, in the order they were added.
+    pub added: Vec<Task>,
+    /// Tasks that were already in the database and were replaced.
+    pub updated: usize,
+    /// Replicas that were not in the database.
+    pub replicas: usize,
+    /// Unsynced operations that were not in the database.
+    pub unsynced_ops: usize,
+}
+
+/// The file formats that tasks can be exported to and imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// Everything in the database. See `DatabaseExport`.
+    Json,
+    /// A todo.txt file, with only the tasks and their details.
+    TodoTxt,
+    /// A Markdown checklist, with only the tasks and their details.
+    Markdown,
+    /// An iCalendar file with a VTODO for each task.
+    ICal,
+}
+
+impl FileFormat {
+    /// Guess the format from a file's extension: ".txt" is todo.txt, ".md" is Markdown, ".ics" is
+    /// iCalendar, and anything else is JSON.
+    pub fn from_path(path: &Path) -> FileFormat {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("txt") => FileFormat::TodoTxt,
+            Some("md") | Some("markdown") => FileFormat::Markdown,
+            Some("ics") => FileFormat::ICal,
+            _ => FileFormat::Json,
+        }
+    }
+
+    pub fn write(self, export: &DatabaseExport) -> Result<String, Error> {
+        match self {
+            FileFormat::Json => export.to_json().map(|json| json + "\n"),
+            FileFormat::TodoTxt => todotxt::write(export),
+            FileFormat::Markdown => markdown::write(export),
+            FileFormat::ICal => ical::write(export),
+        }
+    }
+
+    pub fn read(self, input: &str) -> Result<DatabaseExport, Error> {
+        match self {
+            FileFormat::Json => DatabaseExport::from_json(input),
+            FileFormat::TodoTxt => todotxt::read(input),
+            FileFormat::Markdown => markdown::read(input),
+            FileFormat::ICal => ical::read(input),
+        }
+    }
+}
+
+impl FromStr for FileFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(FileFormat::Json),
+            "todotxt" | "todo.txt" => Ok(FileFormat::TodoTxt),
+            "markdown" | "md" => Ok(FileFormat::Markdown),
+            "ical" | "ics" => Ok(FileFormat::ICal),
+            _ => Err(format_err!("Unknown file format \"{}\": expected json, todotxt, markdown, or ical", s)),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use failure::Error;
+use uuid::Uuid;
+
+use crate::task::{Task, TaskDetails};
+
+use super::DatabaseExport;
+
+/// The Taskwarrior tag that makes a task a break.
+const BREAK_TAG: &str = "break";
+
+/// Which Taskwarrior field is used for the priority of imported tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskwarriorWeight {
+    /// `priority`: none is 1, L is 2, M is 3 and H is 4.
+    Priority,
+    /// `urgency`, rounded.
+    Urgency,
+}
+
+impl FromStr for TaskwarriorWeight {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "priority" => Ok(TaskwarriorWeight::Priority),
+            "urgency" => Ok(TaskwarriorWeight::Urgency),
+            _ => Err(format_err!("Unknown Taskwarrior field \"{}\": expected priority or urgency", s)),
+        }
+    }
+}
+
+/// A task in a Taskwarrior file that was not imported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedTask {
+    pub uuid: Uuid,
+    pub description: String,
+    /// The Taskwarrior status, e.g. "completed".
+    pub status: String,
+}
+
+/// The fields of a task in `task export` output that are read.
+#[derive(Debug, Deserialize)]
+struct TaskwarriorTask {
+    uuid: Uuid,
+    description: String,
+    status: String,
+    priority: Option<String>,
+    urgency: Option<f64>,
+    #[serde(default)]
+    tags: Vec<String>,
+    /// A date and time like "20190630T040000Z".
+    due: Option<String>,
+}
+
+impl TaskwarriorTask {
+    /// Completed and deleted tasks, and the templates of recurring tasks, aren't imported.
+    fn is_skipped(&self) -> bool {
+        matches!(self.status.as_str(), "completed" | "deleted" | "recurring")
+    }
+
+    fn weight(&self, weight: TaskwarriorWeight, scale: f64) -> Result<u32, Error> {
+        let value = match weight {
+            TaskwarriorWeight::Priority => match self.priority.as_deref() {
+                None | Some("") => 1.0,
+                Some("L") => 2.0,
+                Some("M") => 3.0,
+                Some("H") => 4.0,
+                Some(priority) => return Err(format_err!("Unknown priority \"{}\": expected H, M or L.", priority)),
+            },
+            TaskwarriorWeight::Urgency => self.urgency.unwrap_or(0.0),
+        };
+
+        // negative and small urgencies are the lowest priority, 1
+        Ok((value * scale).round().max(1.0).min(f64::from(u32::MAX)) as u32)
+    }
+
+    fn into_task(self, weight: TaskwarriorWeight, scale: f64) -> Result<(Task, TaskDetails), Error> {
+        let priority = self.weight(weight, scale)?;
+
+        let mut reward = false;
+        let mut details = TaskDetails::default();
+        for tag in &self.tags {
+            if tag == BREAK_TAG {
+                reward = true;
+            }
+            else if !tag.is_empty() && !tag.contains(char::is_whitespace) {
+                details.add_tag(tag);
+            }
+        }
+        // the due date is in UTC, and only the date is kept
+        details.due = self.due.as_ref()
+            .map(|due| NaiveDate::parse_from_str(due.get(..8).unwrap_or(due), "%Y%m%d")
+                 .map_err(|e| format_err!("Invalid due date \"{}\": {}", due, e)))
+            .transpose()?;
+
+        let task = Task::from_parts(self.description, priority, reward, self.uuid)
+            .map_err(|e| format_err!("Task was invalid: {}", e))?;
+
+        Ok((task, details))
+    }
+}
+
+/// Read the output of Taskwarrior's `task export`, either a JSON array or one JSON object per line
+/// as older versions write it. Each task keeps its Taskwarrior UUID, and its priority is taken
+/// from `weight` and multiplied by `scale`. Tasks tagged `break` are breaks, and other tags are
+/// kept.
+///
+/// Completed and deleted tasks, and the templates of recurring tasks, are skipped and returned
+/// separately.
+pub fn read(input: &str, weight: TaskwarriorWeight, scale: f64) -> Result<(DatabaseExport, Vec<SkippedTask>), Error> {
+    let entries: Vec<TaskwarriorTask> = if input.trim_start().starts_with('[') {
+        serde_json::from_str(input)
+            .map_err(|e| format_err!("Could not read Taskwarrior export: {}", e))?
+    }
+    else {
+        input.lines()
+            .map(|line| line.trim().trim_end_matches(','))
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_str(line)
+                 .map_err(|e| format_err!("Could not read Taskwarrior export: {}", e)))
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut tasks = Vec::new();
+    let mut skipped = Vec::new();
+    for entry in entries {
+        if entry.is_skipped() {
+            skipped.push(SkippedTask { uuid: entry.uuid, description: entry.description, status: entry.status });
+            continue;
+        }
+
+        let uuid = entry.uuid;
+        tasks.push(entry.into_task(weight, scale).map_err(|e| format_err!("Task {}: {}", uuid, e))?);
+    }
+
+    Ok((DatabaseExport::from_tasks(tasks)?, skipped))
+}
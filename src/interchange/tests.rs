@@ -0,0 +1,453 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::db::DBBackend;
+use crate::db::tests::open_test_db;
+use crate::sync::USetOpMsg;
+use crate::sync::test_utils::{example_replica_1, example_replica_2, example_add_uset_op_1, example_remove_uset_op_2};
+use crate::task::{Task, TaskDetails};
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_3, example_task_break_1};
+
+use std::path::Path;
+
+use uuid::Uuid;
+
+use super::{DatabaseExport, FileFormat, ImportSummary, EXPORT_VERSION};
+use super::todotxt::parse_task;
+use super::taskwarrior::{self, TaskwarriorWeight};
+
+fn example_details() -> TaskDetails {
+    TaskDetails {
+        tags: vec!["home".to_string()],
+        due: Some(NaiveDate::from_ymd(2019, 6, 28)),
+        estimate: Some(Duration::minutes(10)),
+    }
+}
+
+fn example_export() -> DatabaseExport {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    for task in &[example_task_1(), example_task_2(), example_task_break_1()] {
+        tx.add_task(task).expect("Failed adding task");
+    }
+    tx.set_task_details(example_task_2().uuid(), &example_details()).expect("Failed setting details");
+    tx.set_current_task_by_uuid(example_task_2().uuid()).expect("Failed setting current task");
+
+    tx.store_replica_client(&example_replica_1()).expect("Failed storing replica");
+    tx.store_replica_server(&example_replica_2(), "https://example.com").expect("Failed storing replica");
+    tx.store_uset_op_msg(&USetOpMsg { op: example_add_uset_op_1(), deliver_to: example_replica_1() })
+        .expect("Failed storing op");
+    tx.store_uset_op_msg(&USetOpMsg { op: example_remove_uset_op_2(), deliver_to: example_replica_2() })
+        .expect("Failed storing op");
+
+    DatabaseExport::from_db(&tx).expect("Export failed")
+}
+
+/// Sort the parts of an export whose order depends on the database.
+fn sorted(mut export: DatabaseExport) -> DatabaseExport {
+    export.tasks.sort_by_key(|task| *task.uuid());
+    export.details.sort_by_key(|details| details.uuid);
+    export.replicas.sort_by_key(|replica| replica.uuid);
+    export.unsynced_ops.sort_by_key(|msg| msg.deliver_to);
+    export
+}
+
+#[test]
+fn test_interchange_roundtrip() {
+    let export = example_export();
+    assert_eq!(export.version, EXPORT_VERSION);
+    assert_eq!(export.tasks.len(), 3);
+    assert_eq!(export.details.len(), 1);
+    assert_eq!(export.current, Some(*example_task_2().uuid()));
+    assert_eq!(export.replicas.len(), 2);
+    assert_eq!(export.unsynced_ops.len(), 2);
+
+    let json = export.to_json().expect("Serializing failed");
+    let read = DatabaseExport::from_json(&json).expect("Reading export failed");
+    assert_eq!(read, export);
+
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+    let summary = read.import(&tx, false).expect("Import failed");
+    assert_eq!(summary, ImportSummary { added: read.tasks.clone(), updated: 0, replicas: 2, unsynced_ops: 2 });
+
+    assert_eq!(tx.fetch_task_details(example_task_2().uuid()).unwrap(), example_details());
+    assert_eq!(sorted(DatabaseExport::from_db(&tx).expect("Export failed")), sorted(export));
+}
+
+#[test]
+/// Importing the same export twice updates the tasks instead of adding them again.
+fn test_interchange_import_twice() {
+    let export = example_export();
+
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+    export.import(&tx, false).expect("Import failed");
+    let summary = export.import(&tx, false).expect("Import failed");
+    assert_eq!(summary, ImportSummary { added: Vec::new(), updated: 3, replicas: 0, unsynced_ops: 0 });
+
+    assert_eq!(tx.fetch_all_tasks().unwrap().len(), 3);
+    assert_eq!(tx.fetch_uset_op_msgs(&example_replica_1()).unwrap().len(), 1);
+}
+
+#[test]
+/// Merging keeps tasks and the current task that are already in the database, and replacing
+/// removes them.
+fn test_interchange_merge_replace() {
+    let export = example_export();
+
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+    let changed = Task::from_parts("changed description".to_string(), 1, false, *example_task_1().uuid()).unwrap();
+    tx.add_task(&changed).expect("Failed adding task");
+    tx.add_task(&example_task_3()).expect("Failed adding task");
+    tx.set_current_task_by_uuid(example_task_3().uuid()).expect("Failed setting current task");
+
+    let summary = export.import(&tx, false).expect("Import failed");
+    let added: Vec<Task> = export.tasks.iter().filter(|task| task.uuid() != example_task_1().uuid()).cloned().collect();
+    assert_eq!((summary.added, summary.updated), (added, 1));
+    let tasks = tx.fetch_all_tasks().unwrap();
+    assert_eq!(tasks.len(), 4);
+    assert!(tasks.contains(&example_task_1()));
+    assert_eq!(tx.fetch_current_task().unwrap(), Some(example_task_3()));
+
+    let summary = export.import(&tx, true).expect("Import failed");
+    assert_eq!(summary, ImportSummary { added: export.tasks.clone(), updated: 0, replicas: 2, unsynced_ops: 2 });
+    assert_eq!(tx.fetch_all_tasks().unwrap().len(), 3);
+    assert!(!tx.fetch_all_tasks().unwrap().contains(&example_task_3()));
+    assert_eq!(tx.fetch_current_task().unwrap(), Some(example_task_2()));
+}
+
+#[test]
+fn test_interchange_invalid_task() {
+    for task in &[Task::example_invalid_empty_desc(), Task::example_invalid_zero_priority()] {
+        let mut export = example_export();
+        export.tasks.push(task.clone());
+        let json = export.to_json().unwrap();
+
+        let err = DatabaseExport::from_json(&json).unwrap_err();
+        assert!(err.to_string().starts_with(&format!("Invalid task {}", task.uuid())), "{}", err);
+    }
+}
+
+#[test]
+fn test_interchange_invalid_document() {
+    let mut export = example_export();
+    export.version = EXPORT_VERSION + 1;
+    let err = DatabaseExport::from_json(&export.to_json().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("is newer than"), "{}", err);
+
+    let mut export = example_export();
+    export.tasks.push(example_task_1());
+    let err = DatabaseExport::from_json(&export.to_json().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("appears more than once"), "{}", err);
+
+    let mut export = example_export();
+    export.current = Some(*example_task_3().uuid());
+    let err = DatabaseExport::from_json(&export.to_json().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("isn't in the export"), "{}", err);
+
+    let mut export = example_export();
+    export.details[0].estimate_seconds = Some(0);
+    let err = DatabaseExport::from_json(&export.to_json().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("Invalid estimate"), "{}", err);
+
+    // too long for a Duration
+    let mut export = example_export();
+    export.details[0].estimate_seconds = Some(i64::MAX);
+    let err = DatabaseExport::from_json(&export.to_json().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("Invalid estimate"), "{}", err);
+
+    // operations can only be queued for replicas in the export
+    let mut export = example_export();
+    export.replicas.retain(|replica| replica.uuid != example_replica_2());
+    let err = DatabaseExport::from_json(&export.to_json().unwrap()).unwrap_err();
+    assert!(err.to_string().contains(&format!("replica {}, which isn't in the export", example_replica_2())), "{}", err);
+
+    assert!(DatabaseExport::from_json("{\"tasks\": []}").is_err());
+}
+
+#[test]
+fn test_interchange_todotxt_parse() {
+    let line = "(A) 2019-05-30 call mom +family @phone @break due:2019-06-30 est:10m id:00000000-0000-0000-0000-000000000001";
+    let (task, details) = parse_task(line).unwrap().expect("Line was skipped");
+    assert_eq!(task.task(), "call mom");
+    assert_eq!(task.priority(), 26);
+    assert!(task.is_break());
+    assert_eq!(task.uuid(), &Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap());
+    assert_eq!(details, TaskDetails {
+        tags: vec!["family".to_string(), "phone".to_string()],
+        due: Some(NaiveDate::from_ymd(2019, 6, 30)),
+        estimate: Some(Duration::minutes(10)),
+    });
+
+    let (task, details) = parse_task("(C) water plants").unwrap().unwrap();
+    assert_eq!((task.task(), task.priority(), task.is_break()), ("water plants", 24, false));
+    assert!(details.is_empty());
+
+    // without a priority, the task has priority 1 and a new uuid
+    let (first, _) = parse_task("water plants").unwrap().unwrap();
+    let (second, _) = parse_task("water plants").unwrap().unwrap();
+    assert_eq!(first.priority(), 1);
+    assert_ne!(first.uuid(), second.uuid());
+
+    assert_eq!(parse_task("x 2019-06-01 done already").unwrap(), None);
+    assert_eq!(parse_task("   ").unwrap(), None);
+
+    assert!(parse_task("(A) +tag").is_err());
+    assert!(parse_task("task due:friday").is_err());
+    assert!(parse_task("task id:1234").is_err());
+    assert!(parse_task("task pri:0").is_err());
+}
+
+#[test]
+/// Tasks and their details are the same after writing and reading todo.txt and Markdown, but the
+/// current task and sync state are not included.
+fn test_interchange_lists_roundtrip() {
+    let mut export = example_export();
+    let big = Task::from_parts("very important".to_string(), 30, false, Uuid::from_u128(30)).unwrap();
+    export.tasks.push(big);
+
+    for format in &[FileFormat::TodoTxt, FileFormat::Markdown] {
+        let text = format.write(&export).expect("Writing failed");
+        assert_eq!(text.lines().count(), 4);
+
+        let read = format.read(&text).expect("Reading failed");
+        assert_eq!(read.tasks, export.tasks);
+        assert_eq!(read.details, export.details);
+        assert_eq!(read.current, None);
+        assert!(read.replicas.is_empty() && read.unsynced_ops.is_empty());
+    }
+
+    let text = FileFormat::TodoTxt.write(&export).unwrap();
+    assert!(text.lines().any(|line| line.starts_with("(A) very important pri:30 id:")), "{}", text);
+}
+
+#[test]
+/// Descriptions with words that look like todo.txt markers are escaped, so they are read back
+/// unchanged instead of becoming tags, keys, priorities or completed tasks.
+fn test_interchange_lists_escape() {
+    let descriptions = [
+        "x ray appointment",
+        "email +team about it",
+        "(A) is the best grade",
+        "2019-06-01 was a saturday",
+        "ask about due:friday",
+        "est:5m is too short",
+        "set pri:3 in the config",
+        "find id:1234",
+        "meet @home",
+        "@break room",
+        "\\literal backslash",
+        "a + b @ c x (A)",
+    ];
+    let tasks: Vec<(Task, TaskDetails)> = descriptions.iter().enumerate()
+        .map(|(i, description)| {
+            let task = Task::from_parts(description.to_string(), (i as u32 % 3) + 1, false, Uuid::from_u128(i as u128 + 1)).unwrap();
+            (task, TaskDetails::default())
+        })
+        .collect();
+    let export = DatabaseExport::from_tasks(tasks).unwrap();
+
+    for format in &[FileFormat::TodoTxt, FileFormat::Markdown] {
+        let text = format.write(&export).expect("Writing failed");
+        let read = format.read(&text).expect("Reading failed");
+        assert_eq!(read.tasks, export.tasks, "{}", text);
+        assert!(read.details.is_empty(), "{}", text);
+    }
+
+    let text = FileFormat::TodoTxt.write(&export).unwrap();
+    assert!(text.starts_with("\\x ray appointment id:"), "{}", text);
+    assert!(text.contains("email \\+team about it"), "{}", text);
+}
+
+#[test]
+/// Descriptions with line breaks can't be written as one line.
+fn test_interchange_lists_line_break() {
+    let task = Task::from_parts("first line\nsecond line".to_string(), 1, false, Uuid::from_u128(1)).unwrap();
+    let export = DatabaseExport::from_tasks(vec![(task, TaskDetails::default())]).unwrap();
+
+    for format in &[FileFormat::TodoTxt, FileFormat::Markdown] {
+        let err = format.write(&export).unwrap_err();
+        assert!(err.to_string().contains("line break"), "{}", err);
+    }
+}
+
+#[test]
+fn test_interchange_markdown_read() {
+    let text = "\
+# Groceries
+
+Some notes, not a task.
+
+- [ ] (B) buy milk +shop
+- [x] buy eggs
+  * [ ] nested item @break
+- [X] done too
+-[ ] not an item
+";
+    let read = FileFormat::Markdown.read(text).expect("Reading failed");
+    let tasks: Vec<_> = read.tasks.iter().map(|task| (task.task(), task.priority(), task.is_break())).collect();
+    assert_eq!(tasks, vec![("buy milk", 25, false), ("nested item", 1, true)]);
+    assert_eq!(read.details.len(), 1);
+    assert_eq!(read.details[0].tags, vec!["shop".to_string()]);
+
+    let err = FileFormat::Markdown.read("# List\n\n- [ ] ok\n- [ ] due:tomorrow\n").unwrap_err();
+    assert!(err.to_string().starts_with("Line 4:"), "{}", err);
+}
+
+#[test]
+fn test_interchange_todotxt_duplicate_id() {
+    let text = "one id:00000000-0000-0000-0000-000000000001\ntwo id:00000000-0000-0000-0000-000000000001\n";
+    let err = FileFormat::TodoTxt.read(text).unwrap_err();
+    assert!(err.to_string().contains("appears more than once"), "{}", err);
+}
+
+#[test]
+fn test_interchange_file_format() {
+    assert_eq!(FileFormat::from_path(Path::new("todo.txt")), FileFormat::TodoTxt);
+    assert_eq!(FileFormat::from_path(Path::new("notes/list.md")), FileFormat::Markdown);
+    assert_eq!(FileFormat::from_path(Path::new("calendar.ics")), FileFormat::ICal);
+    assert_eq!(FileFormat::from_path(Path::new("backup.json")), FileFormat::Json);
+    assert_eq!(FileFormat::from_path(Path::new("-")), FileFormat::Json);
+
+    assert_eq!("todotxt".parse::<FileFormat>().unwrap(), FileFormat::TodoTxt);
+    assert_eq!("md".parse::<FileFormat>().unwrap(), FileFormat::Markdown);
+    assert!("csv".parse::<FileFormat>().is_err());
+}
+
+#[test]
+/// Tasks and their details are the same after writing and reading iCalendar, including long and
+/// escaped descriptions and priorities above 9.
+fn test_interchange_ical_roundtrip() {
+    let mut export = example_export();
+    let long = "a very long description, with commas; semicolons and a backslash \\ that is folded over several lines";
+    export.tasks.push(Task::from_parts(long.to_string(), 30, false, Uuid::from_u128(30)).unwrap());
+
+    let text = FileFormat::ICal.write(&export).expect("Writing failed");
+    assert!(text.starts_with("BEGIN:VCALENDAR\r\n"), "{}", text);
+    assert!(text.lines().all(|line| line.len() <= 75), "{}", text);
+    assert_eq!(text.matches("BEGIN:VTODO").count(), 4);
+    assert!(text.contains(&format!("UID:{}", example_task_2().uuid())), "{}", text);
+    assert!(text.contains("CATEGORIES:home\r\n"), "{}", text);
+    assert!(text.contains("DUE;VALUE=DATE:20190628\r\n"), "{}", text);
+    assert!(text.contains("CATEGORIES:break\r\n"), "{}", text);
+
+    let read = FileFormat::ICal.read(&text).expect("Reading failed");
+    assert_eq!(read.tasks, export.tasks);
+    assert_eq!(read.details, export.details);
+}
+
+#[test]
+/// Calendars from other programs can have events, alarms, completed to-dos and UIDs that aren't
+/// UUIDs.
+fn test_interchange_ical_read() {
+    let text = "\
+BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example//Example//EN\r
+BEGIN:VEVENT\r
+UID:event-1\r
+SUMMARY:not a to-do\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:00000000-0000-0000-0000-000000000001\r
+SUMMARY;LANGUAGE=en:call mom\\, then dad\r
+PRIORITY:1\r
+CATEGORIES:family,Break\r
+CATEGORIES:phone calls\r
+DUE;TZID=\"America/New_York:Eastern\":20190630T170000\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+DESCRIPTION:reminder\r
+END:VALARM\r
+END:VTODO\r
+BEGIN:VTODO\r
+UID:1234@example.com\r
+SUMMARY:water \r
+ plants\r
+END:VTODO\r
+BEGIN:VTODO\r
+UID:done\r
+SUMMARY:already done\r
+STATUS:COMPLETED\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+    let read = FileFormat::ICal.read(text).expect("Reading failed");
+    assert_eq!(read.tasks.len(), 2);
+
+    let task = &read.tasks[0];
+    assert_eq!((task.task(), task.priority(), task.is_break()), ("call mom, then dad", 9, true));
+    assert_eq!(task.uuid(), &Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap());
+    assert_eq!(read.details.len(), 1);
+    assert_eq!(read.details[0].tags, vec!["family".to_string(), "phone-calls".to_string()]);
+    assert_eq!(read.details[0].due, Some("2019-06-30".to_string()));
+
+    let task = &read.tasks[1];
+    assert_eq!((task.task(), task.priority(), task.is_break()), ("water plants", 1, false));
+
+    let err = FileFormat::ICal.read("BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nPRIORITY:1\r\nEND:VTODO\r\n").unwrap_err();
+    assert!(err.to_string().starts_with("Line 2: VTODO has no SUMMARY"), "{}", err);
+    let err = FileFormat::ICal.read("BEGIN:VTODO\r\nSUMMARY:x\r\nPRIORITY:10\r\n").unwrap_err();
+    assert!(err.to_string().starts_with("Line 3: Invalid PRIORITY 10"), "{}", err);
+    let err = FileFormat::ICal.read("BEGIN:VTODO\r\nSUMMARY:x\r\n").unwrap_err();
+    assert!(err.to_string().contains("not ended"), "{}", err);
+    let err = FileFormat::ICal.read("BEGIN:VTODO\r\nSUMMARY:x\r\nX-TASKERIZER-ESTIMATE:9223372036854775807\r\n").unwrap_err();
+    assert!(err.to_string().starts_with("Line 3: Invalid X-TASKERIZER-ESTIMATE"), "{}", err);
+}
+
+fn taskwarrior_export() -> &'static str {
+    r#"[
+{"id":1,"description":"call mom","entry":"20190601T120000Z","modified":"20190601T120000Z","priority":"H","status":"pending","tags":["family","break"],"due":"20190630T040000Z","urgency":8.9,"uuid":"00000000-0000-0000-0000-000000000001"},
+{"id":2,"description":"water plants","entry":"20190601T120000Z","status":"waiting","urgency":-1.2,"uuid":"00000000-0000-0000-0000-000000000002"},
+{"id":0,"description":"done already","end":"20190602T120000Z","status":"completed","urgency":0,"uuid":"00000000-0000-0000-0000-000000000003"},
+{"id":0,"description":"never mind","status":"deleted","urgency":0,"uuid":"00000000-0000-0000-0000-000000000004"}
+]"#
+}
+
+#[test]
+fn test_interchange_taskwarrior_priority() {
+    let (export, skipped) = taskwarrior::read(taskwarrior_export(), TaskwarriorWeight::Priority, 1.0)
+        .expect("Reading failed");
+
+    let tasks: Vec<_> = export.tasks.iter().map(|task| (task.task(), task.priority(), task.is_break())).collect();
+    assert_eq!(tasks, vec![("call mom", 4, true), ("water plants", 1, false)]);
+    assert_eq!(export.tasks[0].uuid(), &Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap());
+    assert_eq!(export.details.len(), 1);
+    assert_eq!(export.details[0].tags, vec!["family".to_string()]);
+    assert_eq!(export.details[0].due, Some("2019-06-30".to_string()));
+
+    let skipped: Vec<_> = skipped.iter().map(|skipped| (skipped.description.as_str(), skipped.status.as_str())).collect();
+    assert_eq!(skipped, vec![("done already", "completed"), ("never mind", "deleted")]);
+
+    let (export, _) = taskwarrior::read(taskwarrior_export(), TaskwarriorWeight::Priority, 2.5).unwrap();
+    assert_eq!(export.tasks[0].priority(), 10);
+}
+
+#[test]
+fn test_interchange_taskwarrior_urgency() {
+    let (export, _) = taskwarrior::read(taskwarrior_export(), TaskwarriorWeight::Urgency, 1.0).unwrap();
+    // negative urgencies are priority 1
+    let priorities: Vec<_> = export.tasks.iter().map(|task| task.priority()).collect();
+    assert_eq!(priorities, vec![9, 1]);
+
+    let (export, _) = taskwarrior::read(taskwarrior_export(), TaskwarriorWeight::Urgency, 10.0).unwrap();
+    assert_eq!(export.tasks[0].priority(), 89);
+}
+
+#[test]
+/// Older versions of Taskwarrior write one task per line, with commas between them.
+fn test_interchange_taskwarrior_lines() {
+    let input = r#"{"description":"call mom","status":"pending","uuid":"00000000-0000-0000-0000-000000000001"},
+{"description":"done","status":"completed","uuid":"00000000-0000-0000-0000-000000000002"}
+"#;
+    let (export, skipped) = taskwarrior::read(input, TaskwarriorWeight::Priority, 1.0).expect("Reading failed");
+    assert_eq!(export.tasks.len(), 1);
+    assert_eq!(skipped.len(), 1);
+
+    let input = r#"[{"description":"call mom","priority":"X","status":"pending","uuid":"00000000-0000-0000-0000-000000000001"}]"#;
+    let err = taskwarrior::read(input, TaskwarriorWeight::Priority, 1.0).unwrap_err();
+    assert!(err.to_string().contains("Unknown priority \"X\""), "{}", err);
+    assert!(taskwarrior::read("[{\"description\":\"no uuid\"}]", TaskwarriorWeight::Priority, 1.0).is_err());
+}
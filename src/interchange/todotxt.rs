@@ -0,0 +1,176 @@
+use chrono::{Duration, NaiveDate};
+use failure::Error;
+use uuid::Uuid;
+
+use crate::task::{Task, TaskDetails};
+use crate::timer::parse_duration;
+
+use super::DatabaseExport;
+
+/// The number of todo.txt priorities, `(A)` to `(Z)`.
+const LETTER_PRIORITIES: u32 = 26;
+
+/// The priority of a todo.txt priority like `(A)`. `(A)` is the highest, 26, down to 1 for `(Z)`.
+fn letter_priority(word: &str) -> Option<u32> {
+    match word.as_bytes() {
+        [b'(', letter @ b'A'..=b'Z', b')'] => Some(LETTER_PRIORITIES - u32::from(letter - b'A')),
+        _ => None,
+    }
+}
+
+/// The todo.txt priority for `priority`, or `None` for priority 1, which is the priority of tasks
+/// without one. Priorities above 26 are `(A)`.
+fn priority_letter(priority: u32) -> Option<char> {
+    match priority {
+        0 | 1 => None,
+        p => Some(char::from(b'A' + (LETTER_PRIORITIES - p.min(LETTER_PRIORITIES)) as u8)),
+    }
+}
+
+/// Format an estimate so that `parse_duration` reads it back, e.g. "10m".
+fn format_estimate(estimate: Duration) -> String {
+    let seconds = estimate.num_seconds();
+    if seconds % 3600 == 0 {
+        format!("{}h", seconds / 3600)
+    }
+    else if seconds % 60 == 0 {
+        format!("{}m", seconds / 60)
+    }
+    else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Whether `word` anywhere in a line would be read as something other than part of the
+/// description. Words starting with a backslash count, so that the backslash is kept.
+fn is_marker(word: &str) -> bool {
+    word.starts_with('\\')
+        || (word.len() > 1 && (word.starts_with('+') || word.starts_with('@')))
+        || ["due:", "est:", "pri:", "id:"].iter().any(|key| word.starts_with(key))
+}
+
+/// Whether `word` at the start of a line would be read as a completion mark, priority or creation
+/// date.
+fn is_leading_marker(word: &str) -> bool {
+    word == "x" || letter_priority(word).is_some() || NaiveDate::parse_from_str(word, "%Y-%m-%d").is_ok()
+}
+
+/// Format a task as a todo.txt line, e.g. `(A) call mom +family @break due:2019-06-30 id:<uuid>`.
+///
+/// Tags are written as `+tag`, breaks get `@break`, and the estimate is written as `est:10m`.
+/// Priorities above 26 are written as `(A)` with a `pri:` key holding the exact priority. Words of
+/// the description that would be read as one of these are escaped with a backslash, like in quick
+/// add, e.g. `\+1`. It is an error if the description has a line break.
+pub(super) fn format_task(task: &Task, details: &TaskDetails) -> Result<String, Error> {
+    if task.task().contains(['\n', '\r']) {
+        return Err(format_err!("Task {} can't be written on one line: its description has a line break.", task.uuid()));
+    }
+
+    let mut words = Vec::new();
+    if let Some(letter) = priority_letter(task.priority()) {
+        words.push(format!("({})", letter));
+    }
+    for (i, word) in task.task().split_whitespace().enumerate() {
+        if is_marker(word) || (i == 0 && is_leading_marker(word)) {
+            words.push(format!("\\{}", word));
+        }
+        else {
+            words.push(word.to_string());
+        }
+    }
+    words.extend(details.tags.iter().map(|tag| format!("+{}", tag)));
+    if task.is_break() {
+        words.push("@break".to_string());
+    }
+    if let Some(due) = details.due {
+        words.push(format!("due:{}", due));
+    }
+    if let Some(estimate) = details.estimate {
+        words.push(format!("est:{}", format_estimate(estimate)));
+    }
+    if task.priority() > LETTER_PRIORITIES {
+        words.push(format!("pri:{}", task.priority()));
+    }
+    words.push(format!("id:{}", task.uuid()));
+
+    Ok(words.join(" "))
+}
+
+/// Parse a todo.txt line. Returns `None` for blank lines and completed tasks, which start with
+/// `x `.
+///
+/// A leading creation date is skipped. `+project` and `@context` words become tags, except
+/// `@break`, which makes the task a break. `due:`, `est:`, `pri:` and `id:` keys are read, and
+/// tasks without an `id:` get a new UUID. Every other word is part of the description, as is a
+/// word starting with a backslash, without the backslash.
+pub(super) fn parse_task(line: &str) -> Result<Option<(Task, TaskDetails)>, Error> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with("x ") {
+        return Ok(None);
+    }
+
+    let mut words = line.split_whitespace().peekable();
+    let mut priority = words.peek().and_then(|word| letter_priority(word));
+    if priority.is_some() {
+        words.next();
+    }
+    if words.peek().is_some_and(|word| NaiveDate::parse_from_str(word, "%Y-%m-%d").is_ok()) {
+        words.next();
+    }
+
+    let mut description = Vec::new();
+    let mut reward = false;
+    let mut uuid = None;
+    let mut details = TaskDetails::default();
+    for word in words {
+        if let Some(literal) = word.strip_prefix('\\') {
+            description.push(literal);
+        }
+        else if word.eq_ignore_ascii_case("@break") {
+            reward = true;
+        }
+        else if let Some(tag) = word.strip_prefix('+').or_else(|| word.strip_prefix('@')).filter(|tag| !tag.is_empty()) {
+            details.add_tag(tag);
+        }
+        else if let Some(due) = word.strip_prefix("due:") {
+            details.due = Some(NaiveDate::parse_from_str(due, "%Y-%m-%d")
+                .map_err(|e| format_err!("Invalid due date \"{}\": {}", due, e))?);
+        }
+        else if let Some(estimate) = word.strip_prefix("est:") {
+            details.estimate = Some(parse_duration(estimate)?);
+        }
+        else if let Some(p) = word.strip_prefix("pri:") {
+            priority = Some(p.parse().map_err(|e| format_err!("Invalid priority \"{}\": {}", p, e))?);
+        }
+        else if let Some(id) = word.strip_prefix("id:") {
+            uuid = Some(Uuid::parse_str(id).map_err(|e| format_err!("Invalid id \"{}\": {}", id, e))?);
+        }
+        else {
+            description.push(word);
+        }
+    }
+
+    let task = Task::from_parts(description.join(" "), priority.unwrap_or(1), reward, uuid.unwrap_or_else(Uuid::new_v4))
+        .map_err(|e| format_err!("Task was invalid: {}", e))?;
+
+    Ok(Some((task, details)))
+}
+
+/// Write every task in `export` as a todo.txt file.
+pub fn write(export: &DatabaseExport) -> Result<String, Error> {
+    export.tasks_with_details()?.iter()
+        .map(|(task, details)| Ok(format_task(task, details)? + "\n"))
+        .collect()
+}
+
+/// Read the tasks in a todo.txt file. Completed tasks are skipped.
+pub fn read(input: &str) -> Result<DatabaseExport, Error> {
+    let mut tasks = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        if let Some(task) = parse_task(line).map_err(|e| format_err!("Line {}: {}", idx + 1, e))? {
+            tasks.push(task);
+        }
+    }
+
+    DatabaseExport::from_tasks(tasks)
+}
@@ -0,0 +1,274 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use failure::Error;
+use uuid::Uuid;
+
+use crate::db::{DBBackend, RemovalSource, TrashedTask};
+use crate::sync::{self, USetOp};
+use crate::task::{Task, TaskDetails};
+use crate::timer::duration_from_seconds;
+
+#[cfg(test)]
+mod tests;
+
+/// The number of commands kept in the journal, and so the number that can be undone.
+pub const JOURNAL_LENGTH: u32 = 100;
+
+/// Every task in the database with its details, the current task, and the tasks in the trash, at
+/// some point during a command. Comparing snapshots from before and after a command gives its
+/// `JournalEntry`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalSnapshot {
+    current: Option<Uuid>,
+    tasks: HashMap<Uuid, (Task, TaskDetails)>,
+    trash: HashMap<Uuid, TrashedTask>,
+}
+
+impl JournalSnapshot {
+    /// Take a snapshot of the tasks in the database.
+    pub fn capture(tx: &impl DBBackend) -> Result<JournalSnapshot, Error> {
+        let current = tx.fetch_current_task()
+            .map_err(|e| format_err!("Could not get current task from database. {}", e))?
+            .map(|task| *task.uuid());
+
+        let mut tasks = HashMap::new();
+        for task in tx.fetch_all_tasks().map_err(|e| format_err!("Could not get tasks from database. {}", e))? {
+            let details = tx.fetch_task_details(task.uuid())
+                .map_err(|e| format_err!("Could not get task details from database. {}", e))?;
+            tasks.insert(*task.uuid(), (task, details));
+        }
+
+        let trash = tx.fetch_trash()
+            .map_err(|e| format_err!("Could not get trash from database. {}", e))?
+            .into_iter()
+            .map(|trashed| (*trashed.task.uuid(), trashed))
+            .collect();
+
+        Ok(JournalSnapshot { current, tasks, trash })
+    }
+}
+
+/// The current task and the tasks that a command changed, as they were before or after it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct JournalState {
+    pub current: Option<Uuid>,
+    pub tasks: Vec<(Task, TaskDetails)>,
+    /// The tasks the command took out of the trash, e.g. by restoring them, as they were in the
+    /// trash. This is always empty after the command.
+    pub trashed: Vec<TrashedTask>,
+}
+
+/// How a `JournalState` is stored in the database.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredState {
+    current: Option<Uuid>,
+    tasks: Vec<StoredTask>,
+    // entries written before the trash was recorded don't have any
+    #[serde(default)]
+    trashed: Vec<StoredTrashedTask>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredTask {
+    task: Task,
+    tags: Vec<String>,
+    /// The due date as "YYYY-MM-DD".
+    due: Option<String>,
+    estimate_seconds: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredTrashedTask {
+    #[serde(flatten)]
+    task: StoredTask,
+    /// The `RemovalSource` name, e.g. "completed".
+    source: String,
+    replica: Option<Uuid>,
+    /// The date removed in RFC 3339 format.
+    date_removed: String,
+    note: Option<String>,
+}
+
+impl StoredTask {
+    fn new(task: &Task, details: &TaskDetails) -> StoredTask {
+        StoredTask {
+            task: task.clone(),
+            tags: details.tags.clone(),
+            due: details.due.map(|due| due.to_string()),
+            estimate_seconds: details.estimate.map(|estimate| estimate.num_seconds()),
+        }
+    }
+
+    fn into_parts(self) -> Result<(Task, TaskDetails), Error> {
+        let due = self.due
+            .map(|due| NaiveDate::parse_from_str(&due, "%Y-%m-%d"))
+            .transpose()
+            .map_err(|e| format_err!("Invalid due date in journal entry: {}", e))?;
+        let estimate = self.estimate_seconds
+            .map(|seconds| duration_from_seconds(seconds)
+                 .ok_or_else(|| format_err!("Invalid estimate in journal entry: {} seconds", seconds)))
+            .transpose()?;
+        Ok((self.task, TaskDetails { tags: self.tags, due, estimate }))
+    }
+}
+
+impl JournalState {
+    pub fn to_json(&self) -> Result<String, Error> {
+        let stored = StoredState {
+            current: self.current,
+            tasks: self.tasks.iter().map(|(task, details)| StoredTask::new(task, details)).collect(),
+            trashed: self.trashed.iter().map(|trashed| StoredTrashedTask {
+                task: StoredTask::new(&trashed.task, &trashed.details),
+                source: trashed.source.name().to_string(),
+                replica: trashed.source.replica().cloned(),
+                date_removed: trashed.date_removed.to_rfc3339(),
+                note: trashed.note.clone(),
+            }).collect(),
+        };
+
+        serde_json::to_string(&stored).map_err(|e| format_err!("Could not write journal entry: {}", e))
+    }
+
+    pub fn from_json(json: &str) -> Result<JournalState, Error> {
+        let stored: StoredState = serde_json::from_str(json)
+            .map_err(|e| format_err!("Could not read journal entry: {}", e))?;
+
+        let tasks = stored.tasks.into_iter()
+            .map(StoredTask::into_parts)
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut trashed = Vec::new();
+        for stored_trashed in stored.trashed {
+            let (task, details) = stored_trashed.task.into_parts()?;
+            let source = RemovalSource::from_parts(&stored_trashed.source, stored_trashed.replica)?;
+            let date_removed = DateTime::parse_from_rfc3339(&stored_trashed.date_removed)
+                .map_err(|e| format_err!("Invalid date removed in journal entry: {}", e))?
+                .with_timezone(&Utc);
+            trashed.push(TrashedTask { task, details, source, date_removed, note: stored_trashed.note });
+        }
+
+        Ok(JournalState { current: stored.current, tasks, trashed })
+    }
+}
+
+/// A command that changed tasks or the current task, which can be undone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    /// The name of the command, e.g. "complete".
+    pub command: String,
+    pub date: DateTime<Utc>,
+    /// The tasks the command changed or removed, as they were before it.
+    pub before: JournalState,
+    /// The tasks the command changed or added, as they were after it.
+    pub after: JournalState,
+}
+
+impl JournalEntry {
+    /// The entry for a command that turned `before` into `after`, or `None` if it didn't change any
+    /// tasks or the current task.
+    pub fn between(command: &str, before: &JournalSnapshot, after: &JournalSnapshot) -> Option<JournalEntry> {
+        let mut entry = JournalEntry {
+            command: command.to_string(),
+            date: Utc::now(),
+            before: JournalState { current: before.current, tasks: Vec::new(), trashed: Vec::new() },
+            after: JournalState { current: after.current, tasks: Vec::new(), trashed: Vec::new() },
+        };
+
+        for (uuid, old) in &before.tasks {
+            if after.tasks.get(uuid) != Some(old) {
+                entry.before.tasks.push(old.clone());
+            }
+        }
+        for (uuid, new) in &after.tasks {
+            if before.tasks.get(uuid) != Some(new) {
+                entry.after.tasks.push(new.clone());
+            }
+        }
+        // tasks the command put in the trash are taken out again when they are restored
+        for (uuid, trashed) in &before.trash {
+            if after.trash.get(uuid) != Some(trashed) {
+                entry.before.trashed.push(trashed.clone());
+            }
+        }
+        // keep the order stable, so the same changes always give the same entry
+        entry.before.tasks.sort_by_key(|(task, _)| *task.uuid());
+        entry.after.tasks.sort_by_key(|(task, _)| *task.uuid());
+        entry.before.trashed.sort_by_key(|trashed| *trashed.task.uuid());
+
+        if entry.before.tasks.is_empty() && entry.after.tasks.is_empty() && entry.before.trashed.is_empty()
+            && before.current == after.current {
+            return None;
+        }
+        Some(entry)
+    }
+
+    /// Put the tasks the command changed back the way they were, including any it took out of the
+    /// trash, and make the previous current task current again. Tasks the command added are
+    /// deleted rather than moved to the trash. Returns the `USetOp`s that tell other replicas about
+    /// the tasks that were added back or deleted. Statistics, selection history and time tracking
+    /// are not reverted.
+    fn revert(&self, tx: &impl DBBackend) -> Result<Vec<USetOp>, Error> {
+        let mut ops = Vec::new();
+
+        let before_uuids: HashSet<&Uuid> = self.before.tasks.iter().map(|(task, _)| task.uuid()).collect();
+        for (task, _) in &self.after.tasks {
+            if !before_uuids.contains(task.uuid()) {
+                tx.delete_task_by_uuid(task.uuid())?;
+                ops.push(USetOp::Remove(*task.uuid()));
+            }
+        }
+
+        for (task, details) in &self.before.tasks {
+            if !tx.update_task(task)? {
+                tx.add_task(task)?;
+                ops.push(USetOp::Add(task.clone()));
+            }
+            tx.set_task_details(task.uuid(), details)?;
+        }
+
+        for trashed in &self.before.trashed {
+            tx.return_to_trash(trashed)?;
+        }
+
+        if let Some(uuid) = &self.before.current {
+            let current = tx.fetch_current_task()?;
+            if current.as_ref().map(|task| task.uuid()) != Some(uuid) {
+                tx.set_current_task_by_uuid(uuid)?;
+            }
+        }
+
+        Ok(ops)
+    }
+}
+
+/// Record the changes made by `command` since `before` was captured in the journal, if it made any.
+pub fn record(tx: &impl DBBackend, command: &str, before: &JournalSnapshot) -> Result<(), Error> {
+    let after = JournalSnapshot::capture(tx)?;
+    match JournalEntry::between(command, before, &after) {
+        Some(entry) => tx.store_journal_entry(&entry),
+        None => Ok(()),
+    }
+}
+
+/// Undo the last `count` commands in the journal, newest first, and queue operations for every
+/// replica that remove the tasks they added and add back the tasks they removed. Returns the
+/// entries that were undone, which may be fewer than `count` if the journal is shorter.
+pub fn undo(tx: &impl DBBackend, count: usize) -> Result<Vec<JournalEntry>, Error> {
+    let mut undone = Vec::new();
+    let mut ops = Vec::new();
+    while undone.len() < count {
+        let entry = match tx.pop_journal_entry()? {
+            Some(entry) => entry,
+            None => break,
+        };
+        ops.extend(entry.revert(tx)
+            .map_err(|e| format_err!("Could not undo {} from {}: {}", entry.command, entry.date, e))?);
+        undone.push(entry);
+    }
+
+    sync::queue_for_all_replicas(tx, &ops)
+        .map_err(|e| format_err!("Could not queue undo operations: {}", e))?;
+
+    Ok(undone)
+}
@@ -0,0 +1,228 @@
+use chrono::{Duration, NaiveDate, Utc};
+
+use crate::db::{DBBackend, RemovalSource, TrashedTask};
+use crate::db::tests::open_test_db;
+use crate::selection::WeightedRandom;
+use crate::sync::{self, USetOp};
+use crate::sync::test_utils::{example_replica_1, example_replica_2};
+use crate::task::{Task, TaskDetails};
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_3};
+
+use super::{JournalEntry, JournalSnapshot, JournalState, record, undo};
+
+fn example_details() -> TaskDetails {
+    TaskDetails {
+        tags: vec!["home".to_string(), "phone".to_string()],
+        due: Some(NaiveDate::from_ymd(2019, 6, 30)),
+        estimate: Some(Duration::minutes(10)),
+    }
+}
+
+#[test]
+/// A state is read back from JSON unchanged.
+fn test_journal_state_json() {
+    let state = JournalState {
+        current: Some(*example_task_1().uuid()),
+        tasks: vec![(example_task_1(), example_details()), (example_task_2(), TaskDetails::default())],
+        trashed: vec![TrashedTask {
+            task: example_task_3(),
+            details: example_details(),
+            source: RemovalSource::Replica(example_replica_1()),
+            date_removed: Utc::now(),
+            note: None,
+        }],
+    };
+
+    let json = state.to_json().expect("Writing state failed");
+    assert_eq!(JournalState::from_json(&json).expect("Reading state failed"), state);
+    assert!(JournalState::from_json("{\"current\": null, \"tasks\": [1]}").is_err());
+    // entries from before the trash was recorded, some with the operations that were queued
+    assert_eq!(JournalState::from_json("{\"current\": null, \"tasks\": []}").expect("Reading state failed"),
+               JournalState::default());
+    assert_eq!(JournalState::from_json("{\"current\": null, \"tasks\": [], \"queued\": []}").expect("Reading state failed"),
+               JournalState::default());
+
+    // an estimate too long for a Duration is an error rather than a panic
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["tasks"][0]["estimate_seconds"] = serde_json::json!(i64::MAX);
+    assert!(JournalState::from_json(&value.to_string()).is_err());
+}
+
+#[test]
+/// A command that changes nothing has no entry.
+fn test_journal_between_unchanged() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+
+    let snapshot = JournalSnapshot::capture(&tx).unwrap();
+    assert_eq!(JournalEntry::between("list", &snapshot, &snapshot), None);
+}
+
+#[test]
+/// Only the tasks that were added, removed or changed are kept, along with the current task.
+fn test_journal_between_changes() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    let mut selector = WeightedRandom::new(0.0);
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.add_task(&example_task_2()).expect("Adding task failed");
+    tx.set_current_task_by_uuid(example_task_1().uuid()).expect("Setting current task failed");
+    let before = JournalSnapshot::capture(&tx).unwrap();
+
+    tx.complete_current_task().expect("Completing task failed");
+    tx.set_task_details(example_task_2().uuid(), &example_details()).expect("Setting details failed");
+    tx.add_task(&example_task_3()).expect("Adding task failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+    let after = JournalSnapshot::capture(&tx).unwrap();
+
+    let entry = JournalEntry::between("complete", &before, &after).expect("Changes were not found");
+    assert_eq!(entry.command, "complete");
+    assert_eq!(entry.before.current, Some(*example_task_1().uuid()));
+    assert_eq!(entry.before.tasks, vec![
+        (example_task_1(), TaskDetails::default()),
+        (example_task_2(), TaskDetails::default()),
+    ]);
+    assert!(entry.after.current.is_some());
+    assert_eq!(entry.after.tasks, vec![
+        (example_task_2(), example_details()),
+        (example_task_3(), TaskDetails::default()),
+    ]);
+}
+
+#[test]
+/// Undoing a complete restores the task with its details and makes it current again.
+fn test_journal_undo_complete() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    let mut selector = WeightedRandom::new(0.0);
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.add_task(&example_task_2()).expect("Adding task failed");
+    tx.set_task_details(example_task_1().uuid(), &example_details()).expect("Setting details failed");
+    tx.set_current_task_by_uuid(example_task_1().uuid()).expect("Setting current task failed");
+
+    let before = JournalSnapshot::capture(&tx).unwrap();
+    tx.complete_current_task().expect("Completing task failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+    record(&tx, "complete", &before).expect("Recording failed");
+    assert_eq!(tx.fetch_current_task().unwrap(), Some(example_task_2()));
+
+    let undone = undo(&tx, 1).expect("Undo failed");
+    assert_eq!(undone.len(), 1);
+    assert_eq!(undone[0].command, "complete");
+
+    assert_eq!(tx.fetch_current_task().unwrap(), Some(example_task_1()));
+    assert_eq!(tx.fetch_task_details(example_task_1().uuid()).unwrap(), example_details());
+    assert_eq!(tx.fetch_all_tasks().unwrap().len(), 2);
+    assert_eq!(tx.fetch_trash().unwrap(), Vec::new());
+
+    assert_eq!(undo(&tx, 1).expect("Undo failed"), Vec::new());
+}
+
+#[test]
+/// Undoing several commands undoes the newest first: an edit is reverted, and an add is deleted
+/// without going to the trash.
+fn test_journal_undo_add_and_edit() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    let mut selector = WeightedRandom::new(0.0);
+
+    let before = JournalSnapshot::capture(&tx).unwrap();
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.select_current_task(&mut selector).expect("Selecting task failed");
+    record(&tx, "add", &before).expect("Recording failed");
+
+    let edited = Task::from_parts("edited".to_string(), 7, false, *example_task_1().uuid()).unwrap();
+    let before = JournalSnapshot::capture(&tx).unwrap();
+    tx.update_task(&edited).expect("Updating task failed");
+    record(&tx, "edit", &before).expect("Recording failed");
+
+    let undone = undo(&tx, 1).expect("Undo failed");
+    assert_eq!(undone[0].command, "edit");
+    assert_eq!(tx.fetch_all_tasks().unwrap(), vec![example_task_1()]);
+    assert_eq!(tx.fetch_current_task().unwrap(), Some(example_task_1()));
+
+    let undone = undo(&tx, 5).expect("Undo failed");
+    assert_eq!(undone.len(), 1);
+    assert_eq!(undone[0].command, "add");
+    assert_eq!(tx.fetch_all_tasks().unwrap(), Vec::new());
+    assert_eq!(tx.fetch_current_task().unwrap(), None);
+    assert_eq!(tx.fetch_trash().unwrap(), Vec::new());
+}
+
+#[test]
+/// Undoing a skip makes the skipped task current again.
+fn test_journal_undo_skip() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.add_task(&example_task_2()).expect("Adding task failed");
+    tx.set_current_task_by_uuid(example_task_1().uuid()).expect("Setting current task failed");
+
+    let before = JournalSnapshot::capture(&tx).unwrap();
+    tx.set_current_task_by_uuid(example_task_2().uuid()).expect("Setting current task failed");
+    record(&tx, "skip", &before).expect("Recording failed");
+
+    undo(&tx, 1).expect("Undo failed");
+    assert_eq!(tx.fetch_current_task().unwrap(), Some(example_task_1()));
+}
+
+#[test]
+/// Undoing a command queues operations for every replica that remove the tasks it added and add
+/// back the tasks it removed. Edits aren't sent.
+fn test_journal_undo_uset_ops() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    tx.store_replica_client(&example_replica_1()).expect("Storing replica failed");
+    tx.store_replica_client(&example_replica_2()).expect("Storing replica failed");
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.add_task(&example_task_2()).expect("Adding task failed");
+    tx.set_current_task_by_uuid(example_task_1().uuid()).expect("Setting current task failed");
+
+    let before = JournalSnapshot::capture(&tx).unwrap();
+    tx.complete_current_task().expect("Completing task failed");
+    tx.add_task(&example_task_3()).expect("Adding task failed");
+    tx.set_current_task_by_uuid(example_task_3().uuid()).expect("Setting current task failed");
+    let edited = Task::from_parts("edited".to_string(), 7, false, *example_task_2().uuid()).unwrap();
+    tx.update_task(&edited).expect("Updating task failed");
+    record(&tx, "batch", &before).expect("Recording failed");
+
+    undo(&tx, 1).expect("Undo failed");
+    for replica in &[example_replica_1(), example_replica_2()] {
+        let ops: Vec<USetOp> = tx.fetch_uset_op_msgs(replica).unwrap()
+            .into_iter().map(|msg| msg.op).collect();
+        assert_eq!(ops, vec![
+            USetOp::Remove(*example_task_3().uuid()),
+            USetOp::Add(example_task_1()),
+        ]);
+    }
+}
+
+#[test]
+/// Undoing a restore from the trash puts the task back in the trash as it was, and tells the
+/// replicas the restore was sent to that it is removed again.
+fn test_journal_undo_trash_restore() {
+    let mut db = open_test_db();
+    let tx = db.transaction().expect("Failed to begin transaction");
+    tx.store_replica_client(&example_replica_1()).expect("Storing replica failed");
+    tx.add_task(&example_task_1()).expect("Adding task failed");
+    tx.set_task_details(example_task_1().uuid(), &example_details()).expect("Setting details failed");
+    tx.remove_task_by_uuid(example_task_1().uuid(), &RemovalSource::Replica(example_replica_2()))
+        .expect("Removing task failed");
+    let trash = tx.fetch_trash().unwrap();
+
+    let before = JournalSnapshot::capture(&tx).unwrap();
+    tx.restore_from_trash(example_task_1().uuid()).expect("Restoring task failed");
+    sync::queue_for_all_replicas(&tx, &[USetOp::Add(example_task_1())]).expect("Queueing operation failed");
+    record(&tx, "trash restore", &before).expect("Recording failed");
+
+    undo(&tx, 1).expect("Undo failed");
+    assert_eq!(tx.fetch_all_tasks().unwrap(), Vec::new());
+    assert_eq!(tx.fetch_trash().unwrap(), trash);
+    let ops: Vec<USetOp> = tx.fetch_uset_op_msgs(&example_replica_1()).unwrap()
+        .into_iter().map(|msg| msg.op).collect();
+    assert_eq!(ops, vec![
+        USetOp::Add(example_task_1()),
+        USetOp::Remove(*example_task_1().uuid()),
+    ]);
+}
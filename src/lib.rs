@@ -10,9 +10,17 @@ extern crate serde_derive;
 extern crate proptest;
 
 pub mod commands;
+pub mod render;
+pub mod hooks;
+pub mod journal;
+pub mod interchange;
 pub mod config;
 pub mod task;
 pub mod selection;
+pub mod shell;
 pub mod sync;
+pub mod timer;
+pub mod tracking;
+pub mod tui;
 
 pub(crate) mod db;
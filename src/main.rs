@@ -1,20 +1,46 @@
 extern crate taskerizer_prototype;
-use taskerizer_prototype::{commands, config::Config};
+use taskerizer_prototype::{commands, config::{Config, SelectionConfig, TimerConfig, BackupConfig, TrashConfig}};
 
+use std::env;
 use std::path::PathBuf;
 
 fn main() {
-    let args = commands::TKZArgs::get_args();
-    let cmd = args.cmd();
-
     let config = Config {
         db_path: PathBuf::from("/tmp/tkzr"),
         break_cutoff: 0.33,
+        hooks_path: Config::default_hooks_path(),
+        selection: SelectionConfig::default(),
+        timer: TimerConfig::default(),
+        backup: BackupConfig::default(),
+        trash: TrashConfig::default(),
     };
 
-    match cmd.dispatch(&config) {
+    // the hidden command used by completion scripts, which isn't parsed by clap
+    let mut argv = env::args().skip(1);
+    if argv.next().as_deref() == Some(commands::COMPLETE_IDS) {
+        // errors are ignored since there is nowhere to display them while completing
+        let command = argv.next().unwrap_or_default();
+        if let Ok(ids) = commands::complete_ids(&config, &command, &argv.next().unwrap_or_default()) {
+            for id in ids {
+                println!("{}", id);
+            }
+        }
+        return;
+    }
+
+    let args = commands::TKZArgs::get_args();
+    let cmd = args.cmd();
+    let renderer = args.format.renderer(args.color);
+
+    // progress goes to stderr so that it doesn't mix with json or tsv output
+    let mut progress = |output: &commands::CommandOutput| {
+        for line in renderer.render(output) {
+            eprintln!("{}", line);
+        }
+    };
+    match cmd.dispatch_with_progress(&config, &mut progress) {
         Ok(output) => {
-            for line in output {
+            for line in renderer.render(&output) {
                 println!("{}", line);
             }
         },
@@ -0,0 +1,75 @@
+use ansi_term::{Colour, Style};
+
+use crate::commands::CommandOutput;
+use crate::task::Task;
+
+use super::{Plain, Renderer};
+
+/// `Renderer` for terminals that support ANSI colors. The text is the same as `Plain`, with
+/// headers in bold and breaks in green.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Colored;
+
+impl Colored {
+    fn task_style(task: &Task) -> Style {
+        if task.is_break() {
+            Colour::Green.normal()
+        }
+        else {
+            Style::new()
+        }
+    }
+
+    /// Render a table with a header on the line at `header`, followed by one row per task.
+    fn table(lines: Vec<String>, header: usize, tasks: &[&Task]) -> Vec<String> {
+        lines.into_iter().enumerate().map(|(i, line)| {
+            if i == header {
+                Style::new().bold().paint(line).to_string()
+            }
+            else if i > header {
+                Colored::task_style(tasks[i - header - 1]).paint(line).to_string()
+            }
+            else {
+                line
+            }
+        }).collect()
+    }
+}
+
+impl Renderer for Colored {
+    fn render(&self, output: &CommandOutput) -> Vec<String> {
+        let lines = Plain.render(output);
+
+        match output {
+            CommandOutput::TaskList { tasks, .. } => {
+                let tasks: Vec<&Task> = tasks.iter().collect();
+                Colored::table(lines, 0, &tasks)
+            }
+            CommandOutput::Probabilities(probabilities) => {
+                let tasks: Vec<&Task> = probabilities.tasks.iter().map(|(task, _)| task).collect();
+                Colored::table(lines, 1, &tasks)
+            }
+            CommandOutput::Current { task, .. } => {
+                let style = Colored::task_style(task).bold();
+                lines.into_iter().enumerate()
+                    .map(|(i, line)| if i == 0 { style.paint(line).to_string() } else { line })
+                    .collect()
+            }
+            CommandOutput::Completed(_) => {
+                lines.into_iter().map(|line| Colour::Green.paint(line).to_string()).collect()
+            }
+            CommandOutput::TimerFinished { notify_error: Some(_), .. } => {
+                // the notification error is always the last line
+                let last = lines.len() - 1;
+                lines.into_iter().enumerate()
+                    .map(|(i, line)| if i == last { Colour::Red.paint(line).to_string() } else { line })
+                    .collect()
+            }
+            CommandOutput::Failed { .. } => {
+                lines.into_iter().map(|line| Colour::Red.paint(line).to_string()).collect()
+            }
+            CommandOutput::Sequence(outputs) => outputs.iter().flat_map(|o| self.render(o)).collect(),
+            _ => lines,
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use serde_json::{json, Value};
+
+use crate::commands::CommandOutput;
+use crate::task::Task;
+
+use super::{Renderer, category_name, task_category};
+
+/// `Renderer` that outputs a single line of JSON. Every output except `Sequence` is an object
+/// with a `type` field naming the kind of output; a `Sequence` is an array of outputs.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Json;
+
+impl Json {
+    /// The output as a JSON value.
+    pub fn value(&self, output: &CommandOutput) -> Value {
+        match output {
+            CommandOutput::NoTasks => json!({"type": "no_tasks"}),
+            CommandOutput::Message(message) => json!({"type": "message", "message": message}),
+            CommandOutput::Added(task) => json!({"type": "added", "task": task_json(task)}),
+            CommandOutput::QuickAdded { task, details, added } => json!({
+                "type": "quick_added",
+                "added": added,
+                "task": task_json(task),
+                "tags": details.tags,
+                "due": details.due.map(|due| due.to_string()),
+                "estimate_seconds": details.estimate.map(|d| d.num_seconds()),
+            }),
+            CommandOutput::TaskList { tasks, effective_weights, current } => {
+                let tasks: Vec<Value> = tasks.iter().enumerate().map(|(i, task)| {
+                    let mut value = task_json(task);
+                    value["current"] = json!(current.as_ref() == Some(task.uuid()));
+                    if let Some(weights) = effective_weights {
+                        value["effective_weight"] = json!(weights[i]);
+                    }
+                    value
+                }).collect();
+                json!({"type": "task_list", "tasks": tasks})
+            }
+            CommandOutput::Current { task, timer_remaining } => json!({
+                "type": "current",
+                "task": task_json(task),
+                "timer_remaining_seconds": timer_remaining.map(|d| d.num_seconds()),
+            }),
+            CommandOutput::Completed(task) => json!({"type": "completed", "task": task_json(task)}),
+            CommandOutput::Skipped(current) => json!({"type": "skipped", "current": task_json(current)}),
+            CommandOutput::Simulation(simulation) => json!({
+                "type": "simulation",
+                "selections": simulation.selections,
+                "breaks": simulation.breaks,
+                "tasks": simulation.counts.iter()
+                    .map(|(task, count)| json!({"task": task_json(task), "count": count}))
+                    .collect::<Vec<Value>>(),
+                "completion_order": simulation.completion_order.as_ref()
+                    .map(|order| order.iter().map(task_json).collect::<Vec<Value>>()),
+            }),
+            CommandOutput::Probabilities(probabilities) => json!({
+                "type": "probabilities",
+                "break_probability": probabilities.break_probability,
+                "tasks": probabilities.tasks.iter()
+                    .map(|(task, p)| json!({"task": task_json(task), "probability": p}))
+                    .collect::<Vec<Value>>(),
+            }),
+            CommandOutput::TimerStarted { task, length } => json!({
+                "type": "timer_started",
+                "task": task_json(task),
+                "length_seconds": length.num_seconds(),
+            }),
+            CommandOutput::TimerFinished { task, current, notify_error } => json!({
+                "type": "timer_finished",
+                "task": task_json(task),
+                "current": current.as_ref().map(task_json),
+                "notify_error": notify_error,
+            }),
+            CommandOutput::Paused(task) => json!({"type": "paused", "task": task_json(task)}),
+            CommandOutput::Resumed(task) => json!({"type": "resumed", "task": task_json(task)}),
+            CommandOutput::Report(report) => json!({
+                "type": "report",
+                "total_seconds": report.total.num_seconds(),
+                "categories": report.categories.iter()
+                    .map(|(category, time)| json!({"category": category_name(*category), "seconds": time.num_seconds()}))
+                    .collect::<Vec<Value>>(),
+                "tags": report.tags.iter()
+                    .map(|(tag, time)| json!({"tag": tag, "seconds": time.num_seconds()}))
+                    .collect::<Vec<Value>>(),
+                "tasks": report.tasks.iter()
+                    .map(|(task, time)| json!({"task": task, "seconds": time.num_seconds()}))
+                    .collect::<Vec<Value>>(),
+            }),
+            CommandOutput::Export { export, .. } => json!({"type": "export", "export": export}),
+            CommandOutput::Imported { summary, tasks, skipped, dry_run } => json!({
+                "type": "imported",
+                "dry_run": dry_run,
+                "added": summary.added.len(),
+                "updated": summary.updated,
+                "replicas": summary.replicas,
+                "unsynced_ops": summary.unsynced_ops,
+                "tasks": tasks.iter().map(task_json).collect::<Vec<_>>(),
+                "skipped": skipped.iter().map(|skipped| json!({
+                    "uuid": skipped.uuid.to_string(),
+                    "task": skipped.description,
+                    "status": skipped.status,
+                })).collect::<Vec<_>>(),
+            }),
+            CommandOutput::Undone { commands, current } => json!({
+                "type": "undone",
+                "commands": commands,
+                "current": current.as_ref().map(task_json),
+            }),
+            CommandOutput::Doctor { problems, repaired } => json!({
+                "type": "doctor",
+                "repaired": repaired,
+                "problems": problems.iter().map(|problem| json!({
+                    "check": problem.check,
+                    "description": problem.description,
+                    "status": problem.status(*repaired),
+                })).collect::<Vec<_>>(),
+            }),
+            CommandOutput::Trash(trash) => json!({
+                "type": "trash",
+                "tasks": trash.iter().map(|trashed| {
+                    let mut value = task_json(&trashed.task);
+                    value["tags"] = json!(trashed.details.tags);
+                    value["due"] = json!(trashed.details.due.map(|due| due.to_string()));
+                    value["estimate_seconds"] = json!(trashed.details.estimate.map(|d| d.num_seconds()));
+                    value["source"] = json!(trashed.source.name());
+                    value["replica"] = json!(trashed.source.replica().map(|replica| replica.to_string()));
+                    value["date_removed"] = json!(trashed.date_removed.to_rfc3339());
+                    value["note"] = json!(trashed.note);
+                    value
+                }).collect::<Vec<_>>(),
+            }),
+            CommandOutput::Restored(task) => json!({"type": "restored", "task": task_json(task)}),
+            CommandOutput::Edited(task) => json!({"type": "edited", "task": task_json(task)}),
+            CommandOutput::Removed(task) => json!({"type": "removed", "task": task_json(task)}),
+            CommandOutput::TrashEmptied(removed) => json!({"type": "trash_emptied", "removed": removed}),
+            CommandOutput::SearchResults(results) => json!({
+                "type": "search_results",
+                "results": results.iter().enumerate().map(|(i, result)| {
+                    let mut value = task_json(&result.task);
+                    value["rank"] = json!(i + 1);
+                    value["score"] = json!(result.score);
+                    value["removed"] = json!(result.removed.map(|source| source.name()));
+                    value["note"] = json!(result.note);
+                    value
+                }).collect::<Vec<_>>(),
+            }),
+            CommandOutput::Picked { task, action } => json!({"type": "picked", "action": action, "task": task_json(task)}),
+            CommandOutput::Failed { line, command, error } => json!({
+                "type": "failed",
+                "line": line,
+                "command": command,
+                "error": error,
+            }),
+            CommandOutput::HookOutput(lines) => json!({"type": "hook_output", "lines": lines}),
+            CommandOutput::Sequence(outputs) => Value::Array(outputs.iter().map(|o| self.value(o)).collect()),
+        }
+    }
+}
+
+impl Renderer for Json {
+    fn render(&self, output: &CommandOutput) -> Vec<String> {
+        vec![self.value(output).to_string()]
+    }
+}
+
+fn task_json(task: &Task) -> Value {
+    json!({
+        "uuid": task.uuid().to_string(),
+        "task": task.task(),
+        "priority": task.priority(),
+        "category": category_name(task_category(task)),
+    })
+}
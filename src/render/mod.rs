@@ -0,0 +1,120 @@
+//! Renderers turn the `CommandOutput` returned by commands into lines of text. Commands don't
+//! format their own output, so the same results can be displayed in different `OutputFormat`s.
+
+use std::str::FromStr;
+
+use failure::Error;
+
+use crate::commands::CommandOutput;
+use crate::task::{Category, Task};
+
+mod plain;
+mod colored;
+mod json;
+mod tsv;
+mod table;
+pub mod terminal;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::plain::{Plain, quick_add_summary};
+pub use self::colored::Colored;
+pub use self::json::Json;
+pub use self::tsv::Tsv;
+pub use self::table::{Table, truncate};
+
+/// Turns command output into lines of text to be printed.
+pub trait Renderer {
+    fn render(&self, output: &CommandOutput) -> Vec<String>;
+}
+
+/// How command output is displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Text meant to be read by people.
+    Plain,
+    /// Text meant to be read by people, with ANSI colors.
+    Color,
+    /// A single JSON value.
+    Json,
+    /// Tab-separated rows without a header.
+    Tsv,
+}
+
+impl OutputFormat {
+    /// The renderer for this format when printing to stdout. Plain output to a terminal, or with
+    /// colors forced on, is displayed as a `Table`.
+    pub fn renderer(self, color: ColorChoice) -> Box<dyn Renderer> {
+        let use_color = color.use_color(terminal::no_color_env(), terminal::stdout_is_terminal());
+
+        match self {
+            OutputFormat::Plain if terminal::stdout_is_terminal() || use_color => {
+                Box::new(Table { width: terminal::terminal_width(), color: use_color })
+            }
+            OutputFormat::Plain => Box::new(Plain),
+            OutputFormat::Color => Box::new(Table { width: terminal::terminal_width(), color: true }),
+            OutputFormat::Json => Box::new(Json),
+            OutputFormat::Tsv => Box::new(Tsv),
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(OutputFormat::Plain),
+            "color" => Ok(OutputFormat::Color),
+            "json" => Ok(OutputFormat::Json),
+            "tsv" => Ok(OutputFormat::Tsv),
+            _ => Err(format_err!("Unknown output format \"{}\": expected plain, color, json, or tsv", s)),
+        }
+    }
+}
+
+/// When to use colors in plain output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    Never,
+    Always,
+    /// Use colors if stdout is a terminal and `NO_COLOR` is not set.
+    Auto,
+}
+
+impl ColorChoice {
+    /// Whether to use colors, given whether `NO_COLOR` is set and whether stdout is a terminal.
+    pub fn use_color(self, no_color: bool, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Never => false,
+            ColorChoice::Always => true,
+            ColorChoice::Auto => !no_color && is_terminal,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "never" => Ok(ColorChoice::Never),
+            "always" => Ok(ColorChoice::Always),
+            "auto" => Ok(ColorChoice::Auto),
+            _ => Err(format_err!("Unknown color choice \"{}\": expected never, always, or auto", s)),
+        }
+    }
+}
+
+/// The lowercase name of a category, used in machine-readable output.
+fn category_name(category: Category) -> &'static str {
+    match category {
+        Category::Task => "task",
+        Category::Break => "break",
+    }
+}
+
+fn task_category(task: &Task) -> Category {
+    if task.is_break() { Category::Break } else { Category::Task }
+}
@@ -0,0 +1,256 @@
+use crate::commands::{CommandOutput, Simulation};
+use crate::task::{Category, Task, TaskDetails};
+use crate::timer::format_duration;
+use crate::tracking::TimeReport;
+
+use super::Renderer;
+
+/// `Renderer` for text meant to be read by people, without any color.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Plain;
+
+impl Renderer for Plain {
+    fn render(&self, output: &CommandOutput) -> Vec<String> {
+        match output {
+            CommandOutput::NoTasks => vec!["No tasks.".to_string()],
+            CommandOutput::Message(message) => vec![message.clone()],
+            CommandOutput::Added(task) => vec![format!("Task \"{}\" added to task list.", task.task())],
+            CommandOutput::QuickAdded { task, details, added: true } => {
+                vec![format!("Task \"{}\" added to task list ({}).", task.task(), quick_add_summary(task, details))]
+            }
+            CommandOutput::QuickAdded { task, details, added: false } => {
+                vec![format!("Dry run: task \"{}\" would be added to task list ({}).",
+                             task.task(), quick_add_summary(task, details))]
+            }
+            CommandOutput::TaskList { tasks, effective_weights: None, .. } => {
+                let mut output = vec!["Priority \t Task".to_string()];
+                output.extend(tasks.iter().map(|task| task.format_row(4)));
+                output
+            }
+            CommandOutput::TaskList { tasks, effective_weights: Some(weights), .. } => {
+                let mut output = vec!["Priority \t Effective \t Task".to_string()];
+                output.extend(tasks.iter().zip(weights).map(|(task, weight)| {
+                    format!("{:>4} \t {:>9.2} \t {}", task.priority(), weight, task.task())
+                }));
+                output
+            }
+            CommandOutput::Current { task, timer_remaining } => {
+                let mut output = vec![
+                    format!("{}\n", task.task()),
+                    format!("Category: {}", task.category_str()),
+                    format!("Priority: {}", task.priority()),
+                ];
+                if let Some(remaining) = timer_remaining {
+                    output.push(format!("Timer: {} remaining", format_duration(*remaining)));
+                }
+                output
+            }
+            CommandOutput::Completed(task) => vec![format!("Task \"{}\" completed.\n", task.task())],
+            CommandOutput::Skipped(current) => vec![format!("Current task is now \"{}\".\n", current.task())],
+            CommandOutput::Simulation(simulation) => simulation_lines(simulation),
+            CommandOutput::Probabilities(probabilities) => {
+                let mut output = vec![
+                    format!("Category probabilities: Task {:.2}%, Break {:.2}%\n",
+                            100.0 * (1.0 - probabilities.break_probability),
+                            100.0 * probabilities.break_probability),
+                    "Probability \t Category \t Task".to_string(),
+                ];
+                output.extend(probabilities.tasks.iter().map(|(task, p)| {
+                    format!("{:>10.2}% \t {:<8} \t {}", 100.0 * p, task.category_str(), task.task())
+                }));
+                output
+            }
+            CommandOutput::TimerStarted { task, length } => {
+                vec![format!("Timer started for \"{}\": {}.", task.task(), format_duration(*length))]
+            }
+            CommandOutput::TimerFinished { task, current, notify_error } => {
+                let mut output = vec![format!("Timer for \"{}\" finished.", task.task())];
+                if let Some(current) = current {
+                    output.push(format!("Current task is now \"{}\".", current.task()));
+                }
+                if let Some(e) = notify_error {
+                    output.push(format!("Notification failed: {}", e));
+                }
+                output
+            }
+            CommandOutput::Paused(task) => vec![format!("Paused time tracking on \"{}\".", task.task())],
+            CommandOutput::Resumed(task) => vec![format!("Resumed time tracking on \"{}\".", task.task())],
+            CommandOutput::Report(report) => report_lines(report),
+            CommandOutput::Export { format, export } => match format.write(export) {
+                Ok(text) => text.lines().map(String::from).collect(),
+                Err(e) => vec![format!("Error: {}", e)],
+            },
+            CommandOutput::Imported { summary, tasks, skipped, dry_run } => {
+                let mut output = if *dry_run {
+                    vec![format!(
+                        "Dry run: {} tasks would be imported ({} added, {} updated), {} replicas and {} unsynced operations.",
+                        summary.added.len() + summary.updated, summary.added.len(), summary.updated, summary.replicas, summary.unsynced_ops)]
+                }
+                else {
+                    vec![format!(
+                        "Imported {} tasks ({} added, {} updated), {} replicas and {} unsynced operations.",
+                        summary.added.len() + summary.updated, summary.added.len(), summary.updated, summary.replicas, summary.unsynced_ops)]
+                };
+                output.extend(skipped.iter().map(|skipped| {
+                    format!("Skipped {} task \"{}\".", skipped.status, skipped.description)
+                }));
+                if *dry_run && !tasks.is_empty() {
+                    output.push("Priority \t Task".to_string());
+                    output.extend(tasks.iter().map(|task| task.format_row(4)));
+                }
+                output
+            }
+            CommandOutput::Undone { commands, current } => {
+                if commands.is_empty() {
+                    return vec!["Nothing to undo.".to_string()];
+                }
+
+                let mut output = vec![format!("Undid {}.", commands.join(", "))];
+                if let Some(current) = current {
+                    output.push(format!("Current task: {}", current.task()));
+                }
+                output
+            }
+            CommandOutput::Doctor { problems, repaired } => {
+                if problems.is_empty() {
+                    return vec!["No problems found.".to_string()];
+                }
+
+                let fixable = problems.iter().filter(|problem| problem.fixable).count();
+                let mut output = vec![if *repaired {
+                    format!("Found {} problems and fixed {}:", problems.len(), fixable)
+                }
+                else {
+                    format!("Found {} problems, {} can be fixed with `doctor --fix`:", problems.len(), fixable)
+                }];
+                output.extend(problems.iter().map(|problem| {
+                    format!("  {} ({})", problem.description, problem.status(*repaired))
+                }));
+                if fixable < problems.len() {
+                    output.push("Problems that can't be fixed may need the database to be restored from a backup.".to_string());
+                }
+                output
+            }
+            CommandOutput::Trash(trash) => {
+                if trash.is_empty() {
+                    return vec!["The trash is empty.".to_string()];
+                }
+
+                let mut output = vec!["Id \t\t Removed \t\t Source \t Task".to_string()];
+                output.extend(trash.iter().map(|trashed| {
+                    // the start of the UUID is enough to restore it
+                    let id = trashed.task.uuid().to_string();
+                    let note = trashed.note.as_ref().map(|note| format!(" ({})", note)).unwrap_or_default();
+                    format!("{} \t {} \t {} \t {}{}", &id[..8], trashed.date_removed.format("%Y-%m-%d %H:%M"),
+                            trashed.source, trashed.task.task(), note)
+                }));
+                output
+            }
+            CommandOutput::Restored(task) => vec![format!("Task \"{}\" restored from the trash.", task.task())],
+            CommandOutput::Edited(task) => vec![format!("Task \"{}\" updated.", task.task())],
+            CommandOutput::Removed(task) => vec![format!("Task \"{}\" moved to the trash.", task.task())],
+            CommandOutput::TrashEmptied(removed) => vec![format!("Removed {} tasks from the trash.", removed)],
+            CommandOutput::SearchResults(results) => {
+                if results.is_empty() {
+                    return vec!["No tasks found.".to_string()];
+                }
+
+                let mut output = vec!["Rank \t Score \t Priority \t Task".to_string()];
+                output.extend(results.iter().enumerate().map(|(i, result)| {
+                    let removed = result.removed.map(|source| format!(" (in trash: {})", source)).unwrap_or_default();
+                    let note = result.note.as_ref().map(|note| format!(" ({})", note)).unwrap_or_default();
+                    format!("{:>4} \t {:>5.2} \t {:>8} \t {}{}{}", i + 1, result.score, result.task.priority(),
+                            result.task.task(), note, removed)
+                }));
+                output
+            }
+            CommandOutput::Picked { task, action } => match *action {
+                "current" => vec![format!("Current task is now \"{}\".", task.task())],
+                "removed" => vec![format!("Task \"{}\" moved to the trash.", task.task())],
+                _ => vec![format!("Task \"{}\" updated.", task.task())],
+            },
+            CommandOutput::Failed { line, command, error } => {
+                vec![format!("Error on line {} ({}): {}", line, command, error)]
+            }
+            CommandOutput::HookOutput(lines) => lines.clone(),
+            CommandOutput::Sequence(outputs) => outputs.iter().flat_map(|o| self.render(o)).collect(),
+        }
+    }
+}
+
+/// Describe everything about a quick-added task except its description, e.g. "priority 5, break,
+/// tags: phone, due Fri 2019-06-28, estimate 10m 0s".
+pub fn quick_add_summary(task: &Task, details: &TaskDetails) -> String {
+    let mut parts = vec![
+        format!("priority {}", task.priority()),
+        task.category_str().to_lowercase(),
+    ];
+    if !details.tags.is_empty() {
+        parts.push(format!("tags: {}", details.tags.join(" ")));
+    }
+    if let Some(due) = details.due {
+        parts.push(format!("due {}", due.format("%a %Y-%m-%d")));
+    }
+    if let Some(estimate) = details.estimate {
+        parts.push(format!("estimate {}", format_duration(estimate)));
+    }
+    parts.join(", ")
+}
+
+fn simulation_lines(simulation: &Simulation) -> Vec<String> {
+    let n = simulation.selections;
+    let percent = |count: usize| 100.0 * count as f64 / n as f64;
+
+    let mut output = vec![
+        format!("Simulated {} selections.", n),
+        format!("Categories: Task {} ({:.2}%), Break {} ({:.2}%)\n",
+                n - simulation.breaks, percent(n - simulation.breaks),
+                simulation.breaks, percent(simulation.breaks)),
+        "Count \t Percent \t Category \t Task".to_string(),
+    ];
+    output.extend(simulation.counts.iter().map(|(task, count)| {
+        format!("{:>5} \t {:>6.2}% \t {:<8} \t {}", count, percent(*count), task.category_str(), task.task())
+    }));
+
+    if let Some(order) = &simulation.completion_order {
+        output.push(String::new());
+        output.push("Simulated completion order:".to_string());
+        output.extend(order.iter().enumerate().map(|(i, task)| {
+            format!("{:>4}. {}: {}", i + 1, task.category_str(), task.task())
+        }));
+    }
+
+    output
+}
+
+fn report_lines(report: &TimeReport) -> Vec<String> {
+    if report.tasks.is_empty() {
+        return vec!["No time tracked.".to_string()];
+    }
+
+    let mut output = vec![
+        format!("Total: {}\n", format_duration(report.total)),
+        "Time \t\t Category".to_string(),
+    ];
+    output.extend(report.categories.iter().map(|(category, time)| {
+        let category = match category {
+            Category::Task => "Task",
+            Category::Break => "Break",
+        };
+        format!("{:>11} \t {}", format_duration(*time), category)
+    }));
+
+    if !report.tags.is_empty() {
+        output.push("\nTime \t\t Tag".to_string());
+        output.extend(report.tags.iter().map(|(tag, time)| {
+            format!("{:>11} \t +{}", format_duration(*time), tag)
+        }));
+    }
+
+    output.push("\nTime \t\t Task".to_string());
+    output.extend(report.tasks.iter().map(|(task, time)| {
+        format!("{:>11} \t {}", format_duration(*time), task)
+    }));
+
+    output
+}
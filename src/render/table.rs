@@ -0,0 +1,144 @@
+use std::iter;
+
+use ansi_term::{Colour, Style};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use uuid::Uuid;
+
+use crate::commands::CommandOutput;
+use crate::task::Task;
+
+use super::{Colored, Plain, Renderer};
+
+/// Descriptions are never truncated to fewer columns than this, even if the terminal is narrow.
+const MIN_DESCRIPTION_WIDTH: usize = 10;
+
+const PRIORITY_HEADER: &str = "Pri";
+const WEIGHT_HEADER: &str = "Eff";
+const DESCRIPTION_HEADER: &str = "Task";
+
+/// `Renderer` for terminals. The task list is displayed as a table with Tasks and Breaks grouped
+/// under their own headers, columns sized to fit their contents and the current task marked with a
+/// `*`. Other output is rendered the same as `Colored` or `Plain`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Table {
+    /// The width of the terminal in columns. Descriptions that don't fit are truncated with an
+    /// ellipsis. If `None`, descriptions are never truncated.
+    pub width: Option<usize>,
+    /// Whether to use ANSI colors.
+    pub color: bool,
+}
+
+impl Table {
+    fn paint(&self, style: Style, text: String) -> String {
+        if self.color {
+            style.paint(text).to_string()
+        }
+        else {
+            text
+        }
+    }
+
+    fn task_table(&self, tasks: &[Task], effective_weights: Option<&[f64]>, current: Option<&Uuid>) -> Vec<String> {
+        if tasks.is_empty() {
+            return vec!["No tasks.".to_string()];
+        }
+
+        let priorities: Vec<String> = tasks.iter().map(|task| task.priority().to_string()).collect();
+        let priority_width = column_width(PRIORITY_HEADER, &priorities);
+
+        let weights: Option<Vec<String>> = effective_weights
+            .map(|weights| weights.iter().map(|weight| format!("{:.2}", weight)).collect());
+        let weight_width = weights.as_ref().map(|weights| column_width(WEIGHT_HEADER, weights));
+
+        // the current task marker and each column but the last is followed by two spaces
+        let prefix_width = 2 + priority_width + 2 + weight_width.map_or(0, |width| width + 2);
+        let description_width = self.width
+            .map(|width| width.saturating_sub(prefix_width).max(MIN_DESCRIPTION_WIDTH));
+
+        let row = |marker: &str, priority: &str, weight: Option<&str>, description: String| {
+            let mut row = format!("{} {:>width$}  ", marker, priority, width = priority_width);
+            if let (Some(weight), Some(width)) = (weight, weight_width) {
+                row.push_str(&format!("{:>width$}  ", weight, width = width));
+            }
+            row + &description
+        };
+
+        let mut output = Vec::new();
+        for &(is_break, name) in &[(false, "Tasks"), (true, "Breaks")] {
+            let group: Vec<usize> = (0..tasks.len()).filter(|&i| tasks[i].is_break() == is_break).collect();
+            if group.is_empty() {
+                continue;
+            }
+
+            if !output.is_empty() {
+                output.push(String::new());
+            }
+            output.push(self.paint(Style::new().bold().underline(), name.to_string()));
+            let header = row(" ", PRIORITY_HEADER, Some(WEIGHT_HEADER), DESCRIPTION_HEADER.to_string());
+            output.push(self.paint(Style::new().dimmed(), header));
+
+            for i in group {
+                let task = &tasks[i];
+                let is_current = current == Some(task.uuid());
+
+                let marker = if is_current { self.paint(Colour::Yellow.bold(), "*".to_string()) } else { " ".to_string() };
+
+                let description = match description_width {
+                    Some(width) => truncate(task.task(), width),
+                    None => task.task().to_string(),
+                };
+                let style = if is_break { Colour::Green.bold() } else { Style::new().bold() };
+                let description = self.paint(style, description);
+
+                let weight = weights.as_ref().map(|weights| weights[i].as_str());
+                output.push(row(&marker, &priorities[i], weight, description));
+            }
+        }
+
+        output
+    }
+}
+
+impl Renderer for Table {
+    fn render(&self, output: &CommandOutput) -> Vec<String> {
+        match output {
+            CommandOutput::TaskList { tasks, effective_weights, current } => {
+                self.task_table(tasks, effective_weights.as_ref().map(Vec::as_slice), current.as_ref())
+            }
+            CommandOutput::Sequence(outputs) => outputs.iter().flat_map(|o| self.render(o)).collect(),
+            _ if self.color => Colored.render(output),
+            _ => Plain.render(output),
+        }
+    }
+}
+
+/// The display width of a column with the given header and values.
+fn column_width(header: &str, values: &[String]) -> usize {
+    values.iter().map(|value| value.width())
+        .chain(iter::once(header.width()))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Truncate `text` to at most `width` display columns, replacing the end with an ellipsis if it
+/// doesn't fit.
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+
+    let mut truncated = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        // leave room for the ellipsis
+        if used + char_width + 1 > width {
+            break;
+        }
+        truncated.push(c);
+        used += char_width;
+    }
+    truncated.push('…');
+
+    truncated
+}
@@ -0,0 +1,47 @@
+use std::env;
+
+/// Whether stdout is a terminal.
+pub fn stdout_is_terminal() -> bool {
+    atty::is(atty::Stream::Stdout)
+}
+
+/// The width of the terminal attached to stdout in columns, or `None` if stdout is not a terminal.
+/// The `COLUMNS` environment variable takes precedence over the size reported by the terminal.
+pub fn terminal_width() -> Option<usize> {
+    if !stdout_is_terminal() {
+        return None;
+    }
+
+    if let Some(columns) = env::var("COLUMNS").ok().and_then(|columns| columns.parse().ok()) {
+        return Some(columns);
+    }
+
+    terminal_size().map(|(width, _)| width)
+}
+
+/// The size of the terminal attached to stdout as `(columns, rows)`, or `None` if it can't be
+/// determined.
+#[cfg(unix)]
+pub fn terminal_size() -> Option<(usize, usize)> {
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    // safe because TIOCGWINSZ only writes to the winsize we pass it
+    let res = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+
+    if res == 0 && size.ws_col > 0 && size.ws_row > 0 {
+        Some((size.ws_col as usize, size.ws_row as usize))
+    }
+    else {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+pub fn terminal_size() -> Option<(usize, usize)> {
+    None
+}
+
+/// Whether the `NO_COLOR` environment variable is set to a non-empty value. See
+/// https://no-color.org.
+pub fn no_color_env() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
@@ -0,0 +1,449 @@
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::commands::CommandOutput;
+use crate::db::{DBProblem, RemovalSource, SearchResult, TrashedTask};
+use crate::interchange::ImportSummary;
+use crate::interchange::taskwarrior::SkippedTask;
+use crate::render::{ColorChoice, OutputFormat, Renderer, Plain, Colored, Json, Table, Tsv, truncate};
+
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_3, example_task_break_1};
+use crate::task::{Task, TaskDetails};
+
+#[test]
+fn test_output_format_parse() {
+    assert_eq!("plain".parse::<OutputFormat>().unwrap(), OutputFormat::Plain);
+    assert_eq!("color".parse::<OutputFormat>().unwrap(), OutputFormat::Color);
+    assert_eq!("json".parse::<OutputFormat>().unwrap(), OutputFormat::Json);
+    assert_eq!("tsv".parse::<OutputFormat>().unwrap(), OutputFormat::Tsv);
+    assert!("xml".parse::<OutputFormat>().is_err());
+}
+
+#[test]
+fn test_output_json_task() {
+    let task = example_task_1();
+    let output = CommandOutput::Completed(task.clone());
+
+    assert_eq!(Json.value(&output), json!({
+        "type": "completed",
+        "task": {
+            "uuid": task.uuid().to_string(),
+            "task": task.task(),
+            "priority": task.priority(),
+            "category": "task",
+        },
+    }));
+}
+
+#[test]
+fn test_output_json_current_timer() {
+    let output = CommandOutput::Current {
+        task: example_task_break_1(),
+        timer_remaining: Some(Duration::seconds(90)),
+    };
+
+    let value = Json.value(&output);
+    assert_eq!(value["type"], "current");
+    assert_eq!(value["task"]["category"], "break");
+    assert_eq!(value["timer_remaining_seconds"], 90);
+}
+
+#[test]
+fn test_output_timer_started() {
+    let output = CommandOutput::TimerStarted { task: example_task_1(), length: Duration::minutes(25) };
+
+    assert_eq!(Plain.render(&output), vec![format!("Timer started for \"{}\": 25m 0s.", example_task_1().task())]);
+    let value = Json.value(&output);
+    assert_eq!(value["type"], "timer_started");
+    assert_eq!(value["length_seconds"], 1500);
+    let task_row = Tsv.render(&CommandOutput::Added(example_task_1())).remove(0);
+    assert_eq!(Tsv.render(&output), vec![format!("{}\t1500", task_row)]);
+}
+
+#[test]
+/// Every format includes the new current task when a break is chosen as the timer finishes.
+fn test_output_timer_finished() {
+    let output = CommandOutput::TimerFinished {
+        task: example_task_1(),
+        current: Some(example_task_break_1()),
+        notify_error: None,
+    };
+
+    assert_eq!(Plain.render(&output), vec![
+        format!("Timer for \"{}\" finished.", example_task_1().task()),
+        format!("Current task is now \"{}\".", example_task_break_1().task()),
+    ]);
+    let value = Json.value(&output);
+    assert_eq!(value["type"], "timer_finished");
+    assert_eq!(value["current"]["uuid"], example_task_break_1().uuid().to_string());
+    let task_row = Tsv.render(&CommandOutput::Added(example_task_1())).remove(0);
+    let break_row = Tsv.render(&CommandOutput::Added(example_task_break_1())).remove(0);
+    assert_eq!(Tsv.render(&output), vec![task_row, break_row]);
+}
+
+#[test]
+/// Hook output is included in the json as a separate value.
+fn test_output_json_sequence() {
+    let output = CommandOutput::NoTasks.with_hook_output(vec!["hi".to_string()]);
+
+    assert_eq!(Json.value(&output), json!([
+        {"type": "no_tasks"},
+        {"type": "hook_output", "lines": ["hi"]},
+    ]));
+    assert_eq!(OutputFormat::Json.renderer(ColorChoice::Never).render(&output).len(), 1);
+}
+
+#[test]
+fn test_output_with_no_hook_output() {
+    let output = CommandOutput::NoTasks.with_hook_output(Vec::new());
+    assert_eq!(output, CommandOutput::NoTasks);
+}
+
+#[test]
+fn test_output_tsv_list() {
+    let task = example_task_1();
+    let output = CommandOutput::TaskList {
+        tasks: vec![task.clone()],
+        effective_weights: Some(vec![2.5]),
+        current: None,
+    };
+
+    assert_eq!(Tsv.render(&output), vec![
+        format!("{}\t{}\ttask\t{}\t2.5", task.uuid(), task.priority(), task.task()),
+    ]);
+}
+
+#[test]
+fn test_output_tsv_escapes() {
+    let output = CommandOutput::Message("a\tb\nc\\d".to_string());
+
+    assert_eq!(Tsv.render(&output), vec!["a\\tb\\nc\\\\d"]);
+}
+
+#[test]
+fn test_output_plain_matches_lines() {
+    let output = CommandOutput::Added(example_task_1());
+
+    assert_eq!(OutputFormat::Plain.renderer(ColorChoice::Never).render(&output), output.lines());
+    assert_eq!(Plain.render(&output), vec![format!("Task \"{}\" added to task list.", example_task_1().task())]);
+}
+
+#[test]
+/// Colored output has the same text as plain output, with escape codes added.
+fn test_output_colored_list() {
+    let output = CommandOutput::TaskList {
+        tasks: vec![example_task_1(), example_task_break_1()],
+        effective_weights: None,
+        current: None,
+    };
+
+    let plain = Plain.render(&output);
+    let colored = Colored.render(&output);
+    assert_eq!(colored.len(), plain.len());
+    assert_eq!(colored[0], format!("\u{1b}[1m{}\u{1b}[0m", plain[0]));
+    // tasks are not colored, breaks are green
+    assert_eq!(colored[1], plain[1]);
+    assert_eq!(colored[2], format!("\u{1b}[32m{}\u{1b}[0m", plain[2]));
+}
+
+#[test]
+fn test_output_colored_sequence() {
+    let output = CommandOutput::Completed(example_task_1()).with_hook_output(vec!["hi".to_string()]);
+
+    let colored = Colored.render(&output);
+    assert_eq!(colored.len(), 2);
+    assert!(colored[0].starts_with("\u{1b}[32m"));
+    assert_eq!(colored[1], "hi");
+}
+
+#[test]
+fn test_output_color_choice() {
+    assert_eq!("never".parse::<ColorChoice>().unwrap(), ColorChoice::Never);
+    assert_eq!("always".parse::<ColorChoice>().unwrap(), ColorChoice::Always);
+    assert_eq!("auto".parse::<ColorChoice>().unwrap(), ColorChoice::Auto);
+    assert!("sometimes".parse::<ColorChoice>().is_err());
+
+    assert!(!ColorChoice::Never.use_color(false, true));
+    assert!(ColorChoice::Always.use_color(true, false));
+    assert!(ColorChoice::Auto.use_color(false, true));
+    assert!(!ColorChoice::Auto.use_color(true, true));
+    assert!(!ColorChoice::Auto.use_color(false, false));
+}
+
+#[test]
+fn test_output_truncate() {
+    assert_eq!(truncate("short", 10), "short");
+    assert_eq!(truncate("exactly10!", 10), "exactly10!");
+    assert_eq!(truncate("this is too long", 10), "this is t…");
+    // wide characters take two columns each
+    assert_eq!(truncate("日本語のタスク", 6), "日本…");
+}
+
+#[test]
+/// Tasks and breaks are grouped under headers, the priority column is as wide as the widest
+/// priority and the current task is marked.
+fn test_output_table_groups() {
+    let task = Task::from_parts("write the report".to_string(), 100, false, Uuid::new_v4()).unwrap();
+    let output = CommandOutput::TaskList {
+        tasks: vec![example_task_break_1(), task.clone(), example_task_1()],
+        effective_weights: None,
+        current: Some(*task.uuid()),
+    };
+
+    let table = Table { width: None, color: false };
+    assert_eq!(table.render(&output), vec![
+        "Tasks".to_string(),
+        "  Pri  Task".to_string(),
+        "* 100  write the report".to_string(),
+        format!("    {}  {}", example_task_1().priority(), example_task_1().task()),
+        String::new(),
+        "Breaks".to_string(),
+        "  Pri  Task".to_string(),
+        format!("    {}  {}", example_task_break_1().priority(), example_task_break_1().task()),
+    ]);
+}
+
+#[test]
+fn test_output_table_effective_and_truncated() {
+    let output = CommandOutput::TaskList {
+        tasks: vec![example_task_1()],
+        effective_weights: Some(vec![12.5]),
+        current: None,
+    };
+
+    // the description gets the width left over after the marker, priority and weight columns
+    let table = Table { width: Some(25), color: false };
+    assert_eq!(table.render(&output), vec![
+        "Tasks".to_string(),
+        "  Pri    Eff  Task".to_string(),
+        "    1  12.50  test task …".to_string(),
+    ]);
+}
+
+#[test]
+fn test_output_table_empty() {
+    let output = CommandOutput::TaskList { tasks: Vec::new(), effective_weights: None, current: None };
+
+    assert_eq!(Table { width: None, color: true }.render(&output), vec!["No tasks."]);
+}
+
+#[test]
+/// Output other than the task list is the same as plain or colored output.
+fn test_output_table_other_output() {
+    let output = CommandOutput::Completed(example_task_1());
+
+    assert_eq!(Table { width: Some(80), color: false }.render(&output), Plain.render(&output));
+    assert_eq!(Table { width: Some(80), color: true }.render(&output), Colored.render(&output));
+}
+
+#[test]
+fn test_output_failed() {
+    let output = CommandOutput::Failed { line: 3, command: "add ''".to_string(), error: "Empty task.".to_string() };
+
+    assert_eq!(Plain.render(&output), vec!["Error on line 3 (add ''): Empty task."]);
+    assert_eq!(Json.value(&output), json!({"type": "failed", "line": 3, "command": "add ''", "error": "Empty task."}));
+    assert_eq!(Tsv.render(&output), vec!["failed\t3\tadd ''\tEmpty task."]);
+}
+
+#[test]
+fn test_output_quick_added() {
+    let task = example_task_break_1();
+    let details = TaskDetails {
+        tags: vec!["home".to_string(), "phone".to_string()],
+        due: Some(NaiveDate::from_ymd(2019, 6, 28)),
+        estimate: Some(Duration::minutes(10)),
+    };
+    let output = CommandOutput::QuickAdded { task: task.clone(), details, added: true };
+
+    assert_eq!(Plain.render(&output), vec![format!(
+        "Task \"{}\" added to task list (priority {}, break, tags: home phone, due Fri 2019-06-28, estimate 10m 0s).",
+        task.task(), task.priority())]);
+    let value = Json.value(&output);
+    assert_eq!(value["type"], "quick_added");
+    assert_eq!(value["added"], true);
+    assert_eq!(value["tags"], json!(["home", "phone"]));
+    assert_eq!(value["due"], "2019-06-28");
+    assert_eq!(value["estimate_seconds"], 600);
+    assert!(Tsv.render(&output)[0].ends_with("\thome,phone\t2019-06-28\t600"));
+
+    let output = CommandOutput::QuickAdded { task: task.clone(), details: TaskDetails::default(), added: false };
+    assert_eq!(Plain.render(&output), vec![format!(
+        "Dry run: task \"{}\" would be added to task list (priority {}, break).", task.task(), task.priority())]);
+}
+
+#[test]
+fn test_output_imported() {
+    let summary = ImportSummary { added: vec![example_task_2(), example_task_3()], updated: 1, replicas: 1, unsynced_ops: 0 };
+    let tasks = vec![example_task_1()];
+    let output = CommandOutput::Imported { summary: summary.clone(), tasks: tasks.clone(), skipped: Vec::new(), dry_run: false };
+
+    assert_eq!(Plain.render(&output), vec![
+        "Imported 3 tasks (2 added, 1 updated), 1 replicas and 0 unsynced operations.".to_string()]);
+    let value = Json.value(&output);
+    assert_eq!(value["type"], "imported");
+    assert_eq!(value["dry_run"], false);
+    assert_eq!(value["added"], 2);
+    assert_eq!(value["updated"], 1);
+    assert_eq!(value["tasks"][0]["task"], example_task_1().task());
+    assert_eq!(Tsv.render(&output)[0], "imported\t2\t1\t1\t0");
+
+    let output = CommandOutput::Imported { summary: summary.clone(), tasks, skipped: Vec::new(), dry_run: true };
+    assert_eq!(Plain.render(&output), vec![
+        "Dry run: 3 tasks would be imported (2 added, 1 updated), 1 replicas and 0 unsynced operations.".to_string(),
+        "Priority \t Task".to_string(),
+        example_task_1().format_row(4),
+    ]);
+    assert_eq!(Tsv.render(&output)[0], "dry_run\t2\t1\t1\t0");
+
+    let skipped = vec![SkippedTask { uuid: Uuid::nil(), description: "done".to_string(), status: "completed".to_string() }];
+    let output = CommandOutput::Imported { summary, tasks: Vec::new(), skipped, dry_run: false };
+    assert_eq!(Plain.render(&output)[1], "Skipped completed task \"done\".");
+    assert_eq!(Json.value(&output)["skipped"][0]["status"], "completed");
+    assert_eq!(Tsv.render(&output)[1], format!("skipped\tcompleted\t{}\tdone", Uuid::nil()));
+}
+
+#[test]
+fn test_output_doctor() {
+    let output = CommandOutput::Doctor { problems: Vec::new(), repaired: false };
+    assert_eq!(Plain.render(&output), vec!["No problems found.".to_string()]);
+    assert_eq!(Tsv.render(&output), Vec::<String>::new());
+
+    let problems = vec![
+        DBProblem { check: "current_task", description: "there are 2 tasks but no current task".to_string(), fixable: true },
+        DBProblem { check: "integrity", description: "row 3 missing from index".to_string(), fixable: false },
+    ];
+    let output = CommandOutput::Doctor { problems: problems.clone(), repaired: false };
+    assert_eq!(Plain.render(&output), vec![
+        "Found 2 problems, 1 can be fixed with `doctor --fix`:".to_string(),
+        "  there are 2 tasks but no current task (fixable)".to_string(),
+        "  row 3 missing from index (unfixable)".to_string(),
+        "Problems that can't be fixed may need the database to be restored from a backup.".to_string(),
+    ]);
+    assert_eq!(Tsv.render(&output), vec![
+        "problem\tcurrent_task\tfixable\tthere are 2 tasks but no current task".to_string(),
+        "problem\tintegrity\tunfixable\trow 3 missing from index".to_string(),
+    ]);
+
+    let output = CommandOutput::Doctor { problems, repaired: true };
+    assert_eq!(Plain.render(&output)[0], "Found 2 problems and fixed 1:");
+    assert_eq!(Plain.render(&output)[1], "  there are 2 tasks but no current task (fixed)");
+    let value = Json.value(&output);
+    assert_eq!(value["type"], "doctor");
+    assert_eq!(value["repaired"], true);
+    assert_eq!(value["problems"][0]["check"], "current_task");
+    assert_eq!(value["problems"][0]["status"], "fixed");
+    assert_eq!(value["problems"][1]["status"], "unfixable");
+}
+
+#[test]
+fn test_output_undone() {
+    let output = CommandOutput::Undone { commands: Vec::new(), current: None };
+    assert_eq!(Plain.render(&output), vec!["Nothing to undo.".to_string()]);
+    assert_eq!(Json.value(&output)["commands"], json!([]));
+
+    let output = CommandOutput::Undone { commands: vec!["complete".to_string(), "add".to_string()], current: Some(example_task_1()) };
+    assert_eq!(Plain.render(&output), vec![
+        "Undid complete, add.".to_string(),
+        format!("Current task: {}", example_task_1().task()),
+    ]);
+    let value = Json.value(&output);
+    assert_eq!(value["type"], "undone");
+    assert_eq!(value["commands"], json!(["complete", "add"]));
+    assert_eq!(value["current"]["task"], example_task_1().task());
+    let tsv = Tsv.render(&output);
+    assert_eq!(tsv[0], "undone\tcomplete");
+    assert_eq!(tsv[1], "undone\tadd");
+    assert_eq!(tsv.len(), 3);
+}
+
+#[test]
+fn test_output_trash() {
+    let output = CommandOutput::Trash(Vec::new());
+    assert_eq!(Plain.render(&output), vec!["The trash is empty.".to_string()]);
+    assert!(Tsv.render(&output).is_empty());
+
+    let replica = Uuid::from_u128(1);
+    let trashed = TrashedTask {
+        task: example_task_1(),
+        details: TaskDetails { tags: vec!["phone".to_string()], due: None, estimate: Some(Duration::minutes(10)) },
+        source: RemovalSource::Replica(replica),
+        date_removed: Utc.ymd(2019, 6, 28).and_hms(12, 30, 0),
+        note: Some("left a message\twith the time".to_string()),
+    };
+    let output = CommandOutput::Trash(vec![trashed]);
+
+    let plain = Plain.render(&output);
+    assert_eq!(plain.len(), 2);
+    assert_eq!(plain[1], format!("{} \t 2019-06-28 12:30 \t replica {} \t {} (left a message\twith the time)",
+                                 &example_task_1().uuid().to_string()[..8], replica, example_task_1().task()));
+
+    let value = Json.value(&output);
+    assert_eq!(value["type"], "trash");
+    assert_eq!(value["tasks"][0]["uuid"], example_task_1().uuid().to_string());
+    assert_eq!(value["tasks"][0]["tags"], json!(["phone"]));
+    assert_eq!(value["tasks"][0]["estimate_seconds"], 600);
+    assert_eq!(value["tasks"][0]["source"], "replica");
+    assert_eq!(value["tasks"][0]["replica"], replica.to_string());
+    assert_eq!(value["tasks"][0]["date_removed"], "2019-06-28T12:30:00+00:00");
+    assert_eq!(value["tasks"][0]["note"], "left a message\twith the time");
+
+    let task_row = Tsv.render(&CommandOutput::Added(example_task_1())).remove(0);
+    assert_eq!(Tsv.render(&output), vec![format!("{}\treplica\t{}\t2019-06-28T12:30:00+00:00\tleft a message\\twith the time", task_row, replica)]);
+
+    let output = CommandOutput::Restored(example_task_1());
+    assert_eq!(Plain.render(&output), vec![format!("Task \"{}\" restored from the trash.", example_task_1().task())]);
+    assert_eq!(Json.value(&output)["type"], "restored");
+
+    let output = CommandOutput::Edited(example_task_1());
+    assert_eq!(Plain.render(&output), vec![format!("Task \"{}\" updated.", example_task_1().task())]);
+    assert_eq!(Json.value(&output)["type"], "edited");
+    assert_eq!(Tsv.render(&output), vec![task_row.clone()]);
+
+    let output = CommandOutput::Removed(example_task_1());
+    assert_eq!(Plain.render(&output), vec![format!("Task \"{}\" moved to the trash.", example_task_1().task())]);
+    assert_eq!(Json.value(&output)["type"], "removed");
+    assert_eq!(Tsv.render(&output), vec![task_row.clone()]);
+
+    let output = CommandOutput::TrashEmptied(3);
+    assert_eq!(Plain.render(&output), vec!["Removed 3 tasks from the trash.".to_string()]);
+    assert_eq!(Json.value(&output)["removed"], 3);
+    assert_eq!(Tsv.render(&output), vec!["trash_emptied\t3".to_string()]);
+}
+
+#[test]
+fn test_output_search_results() {
+    let output = CommandOutput::SearchResults(Vec::new());
+    assert_eq!(Plain.render(&output), vec!["No tasks found.".to_string()]);
+    assert_eq!(Json.value(&output)["results"], json!([]));
+
+    let output = CommandOutput::SearchResults(vec![
+        SearchResult { task: example_task_1(), removed: None, note: None, score: 1.0 },
+        SearchResult { task: example_task_break_1(), removed: Some(RemovalSource::Completed),
+                       note: Some("went outside".to_string()), score: 0.5 },
+    ]);
+    assert_eq!(Plain.render(&output), vec![
+        "Rank \t Score \t Priority \t Task".to_string(),
+        format!("   1 \t  1.00 \t        1 \t {}", example_task_1().task()),
+        format!("   2 \t  0.50 \t        1 \t {} (went outside) (in trash: completed)", example_task_break_1().task()),
+    ]);
+
+    let value = Json.value(&output);
+    assert_eq!(value["type"], "search_results");
+    assert_eq!(value["results"][0]["rank"], 1);
+    assert_eq!(value["results"][0]["score"], 1.0);
+    assert_eq!(value["results"][0]["removed"], json!(null));
+    assert_eq!(value["results"][1]["removed"], "completed");
+    assert_eq!(value["results"][0]["note"], json!(null));
+    assert_eq!(value["results"][1]["note"], "went outside");
+
+    let tsv = Tsv.render(&output);
+    let task_row = Tsv.render(&CommandOutput::Added(example_task_1())).remove(0);
+    assert_eq!(tsv[0], format!("{}\t1\t1\t\t", task_row));
+    assert!(tsv[1].ends_with("\t2\t0.5\tcompleted\twent outside"));
+
+    let output = CommandOutput::Picked { task: example_task_1(), action: "removed" };
+    assert_eq!(Plain.render(&output), vec![format!("Task \"{}\" moved to the trash.", example_task_1().task())]);
+    assert_eq!(Json.value(&output)["action"], "removed");
+    assert_eq!(Tsv.render(&output), vec![format!("{}\tremoved", task_row)]);
+}
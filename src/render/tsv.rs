@@ -0,0 +1,107 @@
+use crate::commands::CommandOutput;
+use crate::task::Task;
+
+use super::{Renderer, category_name, task_category};
+
+/// `Renderer` that outputs tab-separated rows without a header. Tasks are written as `uuid,
+/// priority, category, description`, followed by any other columns for the output. Tabs, newlines
+/// and backslashes in text are escaped with backslashes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Tsv;
+
+impl Renderer for Tsv {
+    fn render(&self, output: &CommandOutput) -> Vec<String> {
+        match output {
+            CommandOutput::NoTasks => Vec::new(),
+            CommandOutput::Message(message) => vec![tsv_escape(message)],
+            CommandOutput::Added(task) | CommandOutput::Completed(task) | CommandOutput::Skipped(task)
+                | CommandOutput::Paused(task) | CommandOutput::Resumed(task) | CommandOutput::Restored(task)
+                | CommandOutput::Edited(task) | CommandOutput::Removed(task) => vec![task_tsv(task)],
+            CommandOutput::QuickAdded { task, details, .. } => {
+                let due = details.due.map(|due| due.to_string()).unwrap_or_default();
+                let estimate = details.estimate.map(|d| d.num_seconds().to_string()).unwrap_or_default();
+                vec![format!("{}\t{}\t{}\t{}", task_tsv(task), tsv_escape(&details.tags.join(",")), due, estimate)]
+            }
+            CommandOutput::TaskList { tasks, effective_weights: None, .. } => tasks.iter().map(task_tsv).collect(),
+            CommandOutput::TaskList { tasks, effective_weights: Some(weights), .. } => {
+                tasks.iter().zip(weights).map(|(task, weight)| format!("{}\t{}", task_tsv(task), weight)).collect()
+            }
+            CommandOutput::Current { task, timer_remaining } => {
+                let remaining = timer_remaining.map(|d| d.num_seconds().to_string()).unwrap_or_default();
+                vec![format!("{}\t{}", task_tsv(task), remaining)]
+            }
+            CommandOutput::Simulation(simulation) => {
+                simulation.counts.iter().map(|(task, count)| format!("{}\t{}", task_tsv(task), count)).collect()
+            }
+            CommandOutput::Probabilities(probabilities) => {
+                probabilities.tasks.iter().map(|(task, p)| format!("{}\t{}", task_tsv(task), p)).collect()
+            }
+            CommandOutput::TimerStarted { task, length } => vec![format!("{}\t{}", task_tsv(task), length.num_seconds())],
+            CommandOutput::TimerFinished { task, current, .. } => {
+                let mut output = vec![task_tsv(task)];
+                output.extend(current.iter().map(task_tsv));
+                output
+            }
+            CommandOutput::Report(report) => {
+                report.categories.iter()
+                    .map(|(category, time)| format!("category\t{}\t{}", category_name(*category), time.num_seconds()))
+                    .chain(report.tags.iter()
+                        .map(|(tag, time)| format!("tag\t{}\t{}", tsv_escape(tag), time.num_seconds())))
+                    .chain(report.tasks.iter()
+                        .map(|(task, time)| format!("task\t{}\t{}", tsv_escape(task), time.num_seconds())))
+                    .collect()
+            }
+            CommandOutput::Export { export, .. } => export.tasks.iter().map(task_tsv).collect(),
+            CommandOutput::Imported { summary, tasks, skipped, dry_run } => {
+                let mut output = vec![format!("{}\t{}\t{}\t{}\t{}",
+                    if *dry_run { "dry_run" } else { "imported" },
+                    summary.added.len(), summary.updated, summary.replicas, summary.unsynced_ops)];
+                output.extend(tasks.iter().map(task_tsv));
+                output.extend(skipped.iter().map(|skipped| {
+                    format!("skipped\t{}\t{}\t{}", skipped.status, skipped.uuid, tsv_escape(&skipped.description))
+                }));
+                output
+            }
+            CommandOutput::Undone { commands, current } => {
+                let mut output: Vec<String> = commands.iter().map(|command| format!("undone\t{}", command)).collect();
+                output.extend(current.iter().map(task_tsv));
+                output
+            }
+            CommandOutput::Doctor { problems, repaired } => {
+                problems.iter()
+                    .map(|problem| format!("problem\t{}\t{}\t{}", problem.check, problem.status(*repaired), tsv_escape(&problem.description)))
+                    .collect()
+            }
+            CommandOutput::Trash(trash) => {
+                trash.iter().map(|trashed| {
+                    let replica = trashed.source.replica().map(|replica| replica.to_string()).unwrap_or_default();
+                    let note = trashed.note.as_ref().map(|note| tsv_escape(note)).unwrap_or_default();
+                    format!("{}\t{}\t{}\t{}\t{}", task_tsv(&trashed.task), trashed.source.name(), replica,
+                            trashed.date_removed.to_rfc3339(), note)
+                }).collect()
+            }
+            CommandOutput::SearchResults(results) => {
+                results.iter().enumerate().map(|(i, result)| {
+                    let removed = result.removed.map(|source| source.name()).unwrap_or_default();
+                    let note = result.note.as_ref().map(|note| tsv_escape(note)).unwrap_or_default();
+                    format!("{}\t{}\t{}\t{}\t{}", task_tsv(&result.task), i + 1, result.score, removed, note)
+                }).collect()
+            }
+            CommandOutput::Picked { task, action } => vec![format!("{}\t{}", task_tsv(task), action)],
+            CommandOutput::TrashEmptied(removed) => vec![format!("trash_emptied\t{}", removed)],
+            CommandOutput::Failed { line, command, error } => {
+                vec![format!("failed\t{}\t{}\t{}", line, tsv_escape(command), tsv_escape(error))]
+            }
+            CommandOutput::HookOutput(lines) => lines.clone(),
+            CommandOutput::Sequence(outputs) => outputs.iter().flat_map(|o| self.render(o)).collect(),
+        }
+    }
+}
+
+fn task_tsv(task: &Task) -> String {
+    format!("{}\t{}\t{}\t{}", task.uuid(), task.priority(), category_name(task_category(task)), tsv_escape(task.task()))
+}
+
+fn tsv_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
@@ -0,0 +1,93 @@
+use chrono::Duration;
+
+use crate::task::{Category, Task};
+
+use rand::prelude::*;
+use rand::distributions::WeightedIndex;
+
+use super::{SelectionStrategy, SelectionContext};
+
+/// The maximum number of times a task's weight can double due to aging. This keeps the effective
+/// weights finite no matter how old a task gets.
+const MAX_DOUBLINGS: f64 = 64.0;
+
+/// `SelectionStrategy` implementor that chooses randomly like `WeightedRandom`, but a task's weight
+/// grows the longer it sits in the database so that low priority tasks are eventually chosen.
+///
+/// A task's effective weight is `priority * 2^(age / half_life)`, where the age is the time since
+/// the task was last skipped, or since it was added if it has never been skipped. That is, the
+/// weight doubles every `half_life`.
+pub struct Aging {
+    rng: ThreadRng,
+    break_probability: f32,
+    half_life: Duration,
+    context: SelectionContext,
+}
+
+impl Aging {
+    /// Create a new `Aging` selector. `half_life` must be at least one millisecond.
+    pub fn new(break_probability: f32, half_life: Duration) -> Aging {
+        assert!(half_life.num_milliseconds() > 0, "Aging half-life must be at least one millisecond.");
+
+        Aging {
+            rng: thread_rng(),
+            break_probability,
+            half_life,
+            context: SelectionContext::empty(),
+        }
+    }
+
+    /// The time `task` has been aging for, as of the current context. Tasks without stats have an
+    /// age of zero.
+    fn age(&self, task: &Task) -> Duration {
+        let stats = match self.context.task_stats.get(task.uuid()) {
+            Some(stats) => stats,
+            None => return Duration::zero(),
+        };
+
+        let since = match stats.last_skipped {
+            Some(last_skipped) if last_skipped > stats.date_added => last_skipped,
+            _ => stats.date_added,
+        };
+
+        // clamp at zero in case of clock skew between replicas
+        std::cmp::max(self.context.now.signed_duration_since(since), Duration::zero())
+    }
+}
+
+impl SelectionStrategy for Aging {
+    fn select_category(&mut self) -> Category {
+        if self.rng.gen_bool(self.break_probability.into()) {
+            Category::Break
+        }
+        else {
+            Category::Task
+        }
+    }
+
+    fn select_task(&mut self, tasks: &[&Task]) -> usize {
+        assert!(!tasks.is_empty(), "Tasks slice is empty, nothing to select.");
+
+        let weights: Vec<f64> = tasks.iter().map(|t| self.effective_weight(t)).collect();
+        let dist = WeightedIndex::new(weights).expect("Error creating distribution that should never occur");
+
+        dist.sample(&mut self.rng)
+    }
+
+    fn break_probability(&self) -> f64 {
+        self.break_probability.into()
+    }
+
+    fn set_context(&mut self, context: &SelectionContext) {
+        self.context = context.clone();
+    }
+
+    fn effective_weight(&self, task: &Task) -> f64 {
+        // use milliseconds so that we don't lose precision on short half-lives
+        let age = self.age(task).num_milliseconds() as f64;
+        let half_life = self.half_life.num_milliseconds() as f64;
+        let doublings = (age / half_life).min(MAX_DOUBLINGS);
+
+        f64::from(task.priority()) * doublings.exp2()
+    }
+}
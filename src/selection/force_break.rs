@@ -0,0 +1,60 @@
+use crate::task::{Category, Task};
+
+use rand::prelude::*;
+
+use super::{SelectionStrategy, SelectionContext};
+
+/// `SelectionStrategy` wrapper that always chooses a break, or chooses one with a fixed
+/// probability, delegating the choice of which break or task to the wrapped strategy. As with any
+/// strategy, if there are no breaks in the database a task is selected instead.
+pub struct ForceBreak<'s> {
+    inner: &'s mut dyn SelectionStrategy,
+    rng: ThreadRng,
+    break_probability: f64,
+}
+
+impl<'s> ForceBreak<'s> {
+    /// Always choose a break.
+    pub fn new(inner: &'s mut dyn SelectionStrategy) -> ForceBreak<'s> {
+        ForceBreak::with_probability(inner, 1.0)
+    }
+
+    /// Choose a break with probability `break_probability`, which must be between 0 and 1, and a
+    /// task otherwise.
+    pub fn with_probability(inner: &'s mut dyn SelectionStrategy, break_probability: f64) -> ForceBreak<'s> {
+        assert!((0.0..=1.0).contains(&break_probability), "Break probability must be between 0 and 1.");
+
+        ForceBreak { inner, rng: thread_rng(), break_probability }
+    }
+}
+
+impl<'s> SelectionStrategy for ForceBreak<'s> {
+    fn select_category(&mut self) -> Category {
+        if self.rng.gen_bool(self.break_probability) {
+            Category::Break
+        }
+        else {
+            Category::Task
+        }
+    }
+
+    fn select_task(&mut self, tasks: &[&Task]) -> usize {
+        self.inner.select_task(tasks)
+    }
+
+    fn set_context(&mut self, context: &SelectionContext) {
+        self.inner.set_context(context)
+    }
+
+    fn effective_weight(&self, task: &Task) -> f64 {
+        self.inner.effective_weight(task)
+    }
+
+    fn break_probability(&self) -> f64 {
+        self.break_probability
+    }
+
+    fn task_probabilities(&self, tasks: &[&Task]) -> Vec<f64> {
+        self.inner.task_probabilities(tasks)
+    }
+}
@@ -1,9 +1,95 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
 use crate::task::{Category, Task};
 
 mod weighted_random;
 pub use self::weighted_random::WeightedRandom;
 mod top;
 pub use self::top::Top;
+mod aging;
+pub use self::aging::Aging;
+mod no_repeat;
+pub use self::no_repeat::NoRepeat;
+mod pomodoro;
+pub use self::pomodoro::Pomodoro;
+mod force_break;
+pub use self::force_break::ForceBreak;
+mod probabilities;
+pub use self::probabilities::{SelectionProbabilities, selection_probabilities};
+
+/// Per-task information stored in the database alongside each task that isn't part of the `Task`
+/// itself, e.g. because it is local to this replica and not synced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskStats {
+    /// When the task was added to this database.
+    pub date_added: DateTime<Utc>,
+    /// When the task was last skipped, if ever.
+    pub last_skipped: Option<DateTime<Utc>>,
+}
+
+/// Counters about breaks stored in the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakStats {
+    /// The number of tasks (not breaks) completed since a break was last completed.
+    pub tasks_completed: u32,
+    /// When a break was last completed, or when counting started if no break has been completed.
+    pub last_break: DateTime<Utc>,
+}
+
+/// Database state that a `SelectionStrategy` may use when selecting tasks. It is loaded by
+/// `DBBackend::select_current_task` and passed to `SelectionStrategy::set_context` before each
+/// selection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectionContext {
+    /// The time the selection is being made.
+    pub now: DateTime<Utc>,
+    /// `TaskStats` for every task in the database, keyed by task UUID.
+    pub task_stats: HashMap<Uuid, TaskStats>,
+    /// The UUIDs of the most recently selected tasks, most recent first. These may include tasks
+    /// that are no longer in the database.
+    pub recent_selections: Vec<Uuid>,
+    /// Counters about breaks.
+    pub break_stats: BreakStats,
+    /// The time spent in work sessions on tasks, not breaks, since the last break. Time when no
+    /// session was running, e.g. while paused, isn't counted.
+    pub work_since_break: Duration,
+}
+
+impl SelectionContext {
+    /// A context at the current time with no task stats, no selection history, and no time or
+    /// tasks since the last break.
+    pub fn empty() -> SelectionContext {
+        let now = Utc::now();
+        SelectionContext {
+            now,
+            task_stats: HashMap::new(),
+            recent_selections: Vec::new(),
+            break_stats: BreakStats {
+                tasks_completed: 0,
+                last_break: now,
+            },
+            work_since_break: Duration::zero(),
+        }
+    }
+
+    /// Update the context as if `task` had been selected and then completed `time_taken` later,
+    /// e.g. to simulate completing several tasks in a row.
+    pub fn record_completion(&mut self, task: &Task, time_taken: Duration) {
+        self.now = self.now + time_taken;
+        self.recent_selections.insert(0, *task.uuid());
+        if task.is_break() {
+            self.break_stats = BreakStats { tasks_completed: 0, last_break: self.now };
+            self.work_since_break = Duration::zero();
+        }
+        else {
+            self.break_stats.tasks_completed = self.break_stats.tasks_completed.saturating_add(1);
+            self.work_since_break = self.work_since_break + time_taken;
+        }
+    }
+}
 
 pub trait SelectionStrategy {
     /// Select a `Category` via some method determined by the implementor.
@@ -14,6 +100,51 @@ pub trait SelectionStrategy {
     // FIXME: is there a better way of doing the tasks parameter so it can come from a
     // Vec<(T, Task)> but still be passed as a trait object
     fn select_task(&mut self, tasks: &[&Task]) -> usize;
+
+    /// Give the strategy the current database state before a selection is made. Strategies that
+    /// only look at the tasks themselves can ignore it, which is the default.
+    fn set_context(&mut self, _context: &SelectionContext) {}
+
+    /// The weight the strategy currently gives to `task`. By default this is just the task's
+    /// priority.
+    fn effective_weight(&self, task: &Task) -> f64 {
+        f64::from(task.priority())
+    }
+
+    /// The probability that `select_category` returns `Category::Break`.
+    fn break_probability(&self) -> f64;
+
+    /// The probability that `select_task` returns each index of `tasks`. By default this is each
+    /// task's `effective_weight` divided by the sum of all of them. If the slice is empty, the
+    /// result is empty.
+    fn task_probabilities(&self, tasks: &[&Task]) -> Vec<f64> {
+        let weights: Vec<f64> = tasks.iter().map(|t| self.effective_weight(t)).collect();
+        let total: f64 = weights.iter().sum();
+
+        weights.into_iter().map(|w| w / total).collect()
+    }
+}
+
+/// Select a category and then the index of a task within that category, in the way a new current
+/// task is selected: if there are no tasks of one category, the other is always used, and otherwise
+/// `selector` chooses the category. Returns `None` if there are no tasks or breaks.
+pub fn select_category_and_task(selector: &mut dyn SelectionStrategy, tasks: &[&Task], breaks: &[&Task])
+    -> Option<(Category, usize)> {
+    let category = match (tasks.len(), breaks.len()) {
+        // None of either => nothing to select
+        (0, 0) => return None,
+        // no tasks, use breaks
+        (0, _) => Category::Break,
+        (_, 0) => Category::Task,
+        (_, _) => selector.select_category(),
+    };
+
+    let idx = match category {
+        Category::Break => selector.select_task(breaks),
+        Category::Task => selector.select_task(tasks),
+    };
+
+    Some((category, idx))
 }
 
 #[cfg(test)]
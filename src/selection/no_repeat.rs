@@ -0,0 +1,101 @@
+use crate::task::{Category, Task};
+
+use rand::prelude::*;
+use rand::distributions::WeightedIndex;
+
+use super::{SelectionStrategy, SelectionContext};
+
+/// `SelectionStrategy` implementor that chooses randomly like `WeightedRandom`, but penalizes
+/// tasks that were selected recently so that skipping actually moves on to something else.
+///
+/// The weight of the task selected `k` selections ago, for `k` from 1 to `window`, is multiplied
+/// by `decay^(window - k + 1)`. That is, the most recently selected task is penalized the most,
+/// and the penalty shrinks by a factor of `decay` with each later selection until the task falls
+/// out of the window. A `decay` of 0 excludes recently selected tasks entirely.
+///
+/// If every task in a category has been penalized to a weight of zero, the penalty is ignored for
+/// that selection so that something can still be chosen.
+pub struct NoRepeat {
+    rng: ThreadRng,
+    break_probability: f32,
+    window: usize,
+    decay: f64,
+    context: SelectionContext,
+}
+
+impl NoRepeat {
+    /// Create a new `NoRepeat` selector. `decay` must be between 0 and 1.
+    pub fn new(break_probability: f32, window: usize, decay: f64) -> NoRepeat {
+        assert!((0.0..=1.0).contains(&decay), "No-repeat decay must be between 0 and 1.");
+
+        NoRepeat {
+            rng: thread_rng(),
+            break_probability,
+            window,
+            decay,
+            context: SelectionContext::empty(),
+        }
+    }
+
+    /// The weights of `tasks`, falling back to their priorities if the penalties leave no task
+    /// with a nonzero weight.
+    fn weights(&self, tasks: &[&Task]) -> Vec<f64> {
+        let weights: Vec<f64> = tasks.iter().map(|t| self.effective_weight(t)).collect();
+
+        if weights.iter().all(|&w| w == 0.0) {
+            tasks.iter().map(|t| f64::from(t.priority())).collect()
+        }
+        else {
+            weights
+        }
+    }
+}
+
+impl SelectionStrategy for NoRepeat {
+    fn select_category(&mut self) -> Category {
+        if self.rng.gen_bool(self.break_probability.into()) {
+            Category::Break
+        }
+        else {
+            Category::Task
+        }
+    }
+
+    fn select_task(&mut self, tasks: &[&Task]) -> usize {
+        assert!(!tasks.is_empty(), "Tasks slice is empty, nothing to select.");
+
+        let dist = WeightedIndex::new(self.weights(tasks)).expect("Error creating distribution that should never occur");
+
+        dist.sample(&mut self.rng)
+    }
+
+    fn break_probability(&self) -> f64 {
+        self.break_probability.into()
+    }
+
+    fn set_context(&mut self, context: &SelectionContext) {
+        self.context = context.clone();
+    }
+
+    fn effective_weight(&self, task: &Task) -> f64 {
+        let priority = f64::from(task.priority());
+
+        // only the most recent selection of the task matters
+        let recent = self.context.recent_selections.iter()
+            .take(self.window)
+            .position(|uuid| uuid == task.uuid());
+
+        match recent {
+            // position 0 was selected 1 selection ago
+            Some(position) => priority * self.decay.powi((self.window - position) as i32),
+            None => priority,
+        }
+    }
+
+    fn task_probabilities(&self, tasks: &[&Task]) -> Vec<f64> {
+        let weights = self.weights(tasks);
+        let total: f64 = weights.iter().sum();
+
+        weights.into_iter().map(|w| w / total).collect()
+    }
+}
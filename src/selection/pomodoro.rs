@@ -0,0 +1,87 @@
+use chrono::Duration;
+
+use crate::task::{Category, Task};
+
+use rand::prelude::*;
+use rand::distributions::WeightedIndex;
+
+use super::{SelectionStrategy, SelectionContext};
+
+/// `SelectionStrategy` implementor that schedules breaks like the pomodoro technique instead of
+/// only flipping a coin. Tasks within a category are chosen randomly, weighted by priority, like
+/// `WeightedRandom`.
+///
+/// A break is always chosen once `tasks_per_break` tasks have been completed since the last break,
+/// or once `work_time` has been spent working on tasks since the last break. Work time is the time
+/// in work sessions on tasks rather than breaks, so time spent paused or away doesn't count.
+/// Before then, the break probability ramps up linearly from `break_probability` right after a
+/// break to 1 when `work_time` has been worked. If `work_time` is `None`, it stays at
+/// `break_probability`, and if `tasks_per_break` is 0, breaks are not forced after any number of
+/// tasks.
+pub struct Pomodoro {
+    rng: ThreadRng,
+    break_probability: f32,
+    tasks_per_break: u32,
+    work_time: Option<Duration>,
+    context: SelectionContext,
+}
+
+impl Pomodoro {
+    /// Create a new `Pomodoro` selector. `work_time`, if given, must be at least one millisecond.
+    pub fn new(break_probability: f32, tasks_per_break: u32, work_time: Option<Duration>) -> Pomodoro {
+        if let Some(work_time) = work_time {
+            assert!(work_time.num_milliseconds() > 0, "Pomodoro work time must be at least one millisecond.");
+        }
+
+        Pomodoro {
+            rng: thread_rng(),
+            break_probability,
+            tasks_per_break,
+            work_time,
+            context: SelectionContext::empty(),
+        }
+    }
+}
+
+impl SelectionStrategy for Pomodoro {
+    fn select_category(&mut self) -> Category {
+        if self.rng.gen_bool(self.break_probability()) {
+            Category::Break
+        }
+        else {
+            Category::Task
+        }
+    }
+
+    fn select_task(&mut self, tasks: &[&Task]) -> usize {
+        assert!(!tasks.is_empty(), "Tasks slice is empty, nothing to select.");
+
+        let priorities = tasks.iter().map(|t| f64::from(t.priority()));
+        let dist = WeightedIndex::new(priorities).expect("Error creating distribution that should never occur");
+
+        dist.sample(&mut self.rng)
+    }
+
+    fn break_probability(&self) -> f64 {
+        let stats = &self.context.break_stats;
+        let base = f64::from(self.break_probability);
+
+        if self.tasks_per_break > 0 && stats.tasks_completed >= self.tasks_per_break {
+            return 1.0;
+        }
+
+        let work_time = match self.work_time {
+            Some(work_time) => work_time,
+            None => return base,
+        };
+
+        let worked = self.context.work_since_break;
+        let fraction = worked.num_milliseconds() as f64 / work_time.num_milliseconds() as f64;
+
+        (base + (1.0 - base) * fraction).min(1.0)
+    }
+
+    fn set_context(&mut self, context: &SelectionContext) {
+        self.context = context.clone();
+    }
+}
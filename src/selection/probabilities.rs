@@ -0,0 +1,47 @@
+use crate::task::Task;
+
+use super::SelectionStrategy;
+
+/// The probabilities of each outcome when selecting a new current task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectionProbabilities {
+    /// The probability that a break is selected instead of a task.
+    pub break_probability: f64,
+    /// Each task along with the probability that it is selected, in the order they were given.
+    pub tasks: Vec<(Task, f64)>,
+}
+
+/// Compute the probability that `selector` selects each of `tasks` as the new current task, in the
+/// same way as `DBBackend::select_current_task`: if there are no tasks of one category, the other
+/// is always used, and otherwise the category is chosen with `SelectionStrategy::break_probability`.
+///
+/// The selector's context should already be set, as it is when selecting a task.
+pub fn selection_probabilities(selector: &dyn SelectionStrategy, tasks: &[Task]) -> SelectionProbabilities {
+    let task_refs: Vec<&Task> = tasks.iter().filter(|t| !t.is_break()).collect();
+    let break_refs: Vec<&Task> = tasks.iter().filter(|t| t.is_break()).collect();
+
+    let break_probability = match (task_refs.len(), break_refs.len()) {
+        (_, 0) => 0.0,
+        (0, _) => 1.0,
+        (_, _) => selector.break_probability(),
+    };
+
+    let mut task_probabilities = selector.task_probabilities(&task_refs).into_iter();
+    let mut break_probabilities = selector.task_probabilities(&break_refs).into_iter();
+
+    let tasks = tasks.iter().map(|task| {
+        let p = if task.is_break() {
+            break_probability * break_probabilities.next().expect("Missing break probability")
+        }
+        else {
+            (1.0 - break_probability) * task_probabilities.next().expect("Missing task probability")
+        };
+
+        (task.clone(), p)
+    }).collect();
+
+    SelectionProbabilities {
+        break_probability,
+        tasks,
+    }
+}
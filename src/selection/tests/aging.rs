@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, TimeZone, Utc};
+
+use crate::task::{Category, Task};
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_3};
+
+use crate::selection::{Aging, SelectionStrategy, SelectionContext, TaskStats};
+
+/// Make a context at a fixed time where each task was added `age` hours ago and optionally
+/// skipped `skipped` hours ago.
+fn context_with_ages(tasks: &[(&Task, i64, Option<i64>)]) -> SelectionContext {
+    let now = Utc.ymd(2019, 6, 1).and_hms(12, 0, 0);
+
+    let mut task_stats = HashMap::new();
+    for (task, age, skipped) in tasks {
+        let stats = TaskStats {
+            date_added: now - Duration::hours(*age),
+            last_skipped: skipped.map(|s| now - Duration::hours(s)),
+        };
+        task_stats.insert(*task.uuid(), stats);
+    }
+
+    SelectionContext { now, task_stats, ..SelectionContext::empty() }
+}
+
+#[test]
+fn test_select_category_works() {
+    let mut selector = Aging::new(1.0, Duration::hours(1));
+    assert_eq!(Category::Break, selector.select_category());
+
+    let mut selector = Aging::new(0.0, Duration::hours(1));
+    assert_eq!(Category::Task, selector.select_category());
+}
+
+#[test]
+/// Without any stats, the effective weight is the priority.
+fn test_aging_no_stats() {
+    let selector = Aging::new(0.0, Duration::hours(1));
+
+    let task = example_task_2();
+    assert_eq!(selector.effective_weight(&task), 12.0);
+}
+
+#[test]
+/// The effective weight doubles every half-life.
+fn test_aging_weight_doubles() {
+    let mut selector = Aging::new(0.0, Duration::hours(10));
+
+    let task1 = example_task_1();
+    let task2 = example_task_2();
+    let task3 = example_task_3();
+    let context = context_with_ages(&[(&task1, 0, None), (&task2, 10, None), (&task3, 30, None)]);
+    selector.set_context(&context);
+
+    // priority 1, 0 half-lives
+    assert_eq!(selector.effective_weight(&task1), 1.0);
+    // priority 12, 1 half-life
+    assert_eq!(selector.effective_weight(&task2), 24.0);
+    // priority 2, 3 half-lives
+    assert_eq!(selector.effective_weight(&task3), 16.0);
+}
+
+#[test]
+/// Skipping a task resets its age.
+fn test_aging_skip_resets_age() {
+    let mut selector = Aging::new(0.0, Duration::hours(10));
+
+    let task = example_task_3();
+    let context = context_with_ages(&[(&task, 30, Some(10))]);
+    selector.set_context(&context);
+
+    // priority 2, 1 half-life since skip
+    assert_eq!(selector.effective_weight(&task), 4.0);
+}
+
+#[test]
+/// Ages are capped so that very old tasks don't produce infinite weights.
+fn test_aging_weight_is_finite() {
+    let mut selector = Aging::new(0.0, Duration::milliseconds(1));
+
+    let task = example_task_2();
+    let context = context_with_ages(&[(&task, 24*365*100, None)]);
+    selector.set_context(&context);
+
+    assert!(selector.effective_weight(&task).is_finite());
+}
+
+#[test]
+/// A low priority task that has aged enough gets selected more often than a high priority task
+/// that was just added.
+fn test_aging_old_task_chosen_more() {
+    let mut selector = Aging::new(0.0, Duration::hours(1));
+
+    // task 1: priority 1, aged 10 half-lives -> weight 1024
+    // task 2: priority 12, new -> weight 12
+    let task1 = example_task_1();
+    let task2 = example_task_2();
+    let context = context_with_ages(&[(&task1, 10, None), (&task2, 0, None)]);
+    selector.set_context(&context);
+
+    let task_refs = vec![&task1, &task2];
+
+    let mut counts: [usize; 2] = [0,0];
+    for _ in 0..100 {
+        counts[selector.select_task(&task_refs)] += 1;
+    }
+
+    // completely arbitrary heuristic, as in the weighted random tests
+    assert!(counts[0] >= 80, "Old task was not chosen enough: {:?}", counts);
+}
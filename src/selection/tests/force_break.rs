@@ -0,0 +1,39 @@
+use crate::task::{Category, Task};
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_3};
+
+use crate::selection::{ForceBreak, Top, SelectionStrategy};
+
+#[test]
+fn test_force_break_selects_break() {
+    let mut top = Top::new();
+    let mut selector = ForceBreak::new(&mut top);
+
+    assert_eq!(selector.select_category(), Category::Break);
+    assert_eq!(selector.break_probability(), 1.0);
+}
+
+#[test]
+/// The task within the category is chosen by the wrapped strategy.
+fn test_force_break_delegates_task() {
+    let mut top = Top::new();
+    let mut selector = ForceBreak::new(&mut top);
+
+    let tasks = [example_task_1(), example_task_2(), example_task_3()];
+    let task_refs: Vec<&Task> = tasks.iter().collect();
+
+    // example task 2 has the highest priority
+    assert_eq!(selector.select_task(&task_refs), 1);
+    assert_eq!(selector.task_probabilities(&task_refs), vec![0.0, 1.0, 0.0]);
+}
+
+#[test]
+/// With a break probability of 0, a task is always chosen.
+fn test_force_break_probability() {
+    let mut top = Top::new();
+    let mut selector = ForceBreak::with_probability(&mut top, 0.0);
+
+    assert_eq!(selector.break_probability(), 0.0);
+    for _ in 0..100 {
+        assert_eq!(selector.select_category(), Category::Task);
+    }
+}
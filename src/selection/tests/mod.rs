@@ -1,2 +1,7 @@
 mod weighted_random;
 mod top;
+mod aging;
+mod no_repeat;
+mod pomodoro;
+mod force_break;
+mod probabilities;
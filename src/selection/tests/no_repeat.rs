@@ -0,0 +1,115 @@
+use uuid::Uuid;
+
+use crate::task::{Category, Task};
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_3};
+
+use crate::selection::{NoRepeat, SelectionStrategy, SelectionContext};
+
+/// Make a context where the given tasks were the most recently selected, most recent first.
+fn context_with_recent(recent: &[&Task]) -> SelectionContext {
+    let mut context = SelectionContext::empty();
+    context.recent_selections = recent.iter().map(|t| *t.uuid()).collect();
+
+    context
+}
+
+#[test]
+fn test_select_category_works() {
+    let mut selector = NoRepeat::new(1.0, 3, 0.0);
+    assert_eq!(Category::Break, selector.select_category());
+
+    let mut selector = NoRepeat::new(0.0, 3, 0.0);
+    assert_eq!(Category::Task, selector.select_category());
+}
+
+#[test]
+/// Without any selection history, the effective weight is the priority.
+fn test_no_repeat_no_history() {
+    let selector = NoRepeat::new(0.0, 3, 0.0);
+
+    let task = example_task_2();
+    assert_eq!(selector.effective_weight(&task), 12.0);
+}
+
+#[test]
+/// Recently selected tasks are never selected with a decay of 0.
+fn test_no_repeat_excludes_recent() {
+    let mut selector = NoRepeat::new(0.0, 2, 0.0);
+
+    let task1 = example_task_1();
+    let task2 = example_task_2();
+    let task3 = example_task_3();
+    selector.set_context(&context_with_recent(&[&task2, &task3]));
+
+    assert_eq!(selector.effective_weight(&task2), 0.0);
+    assert_eq!(selector.effective_weight(&task3), 0.0);
+
+    let task_refs = vec![&task1, &task2, &task3];
+    for _ in 0..100 {
+        assert_eq!(selector.select_task(&task_refs), 0);
+    }
+    assert_eq!(selector.task_probabilities(&task_refs), vec![1.0, 0.0, 0.0]);
+}
+
+#[test]
+/// Selections outside the window are not penalized.
+fn test_no_repeat_outside_window() {
+    let mut selector = NoRepeat::new(0.0, 1, 0.0);
+
+    let task1 = example_task_1();
+    let task2 = example_task_2();
+    selector.set_context(&context_with_recent(&[&task1, &task2]));
+
+    assert_eq!(selector.effective_weight(&task1), 0.0);
+    assert_eq!(selector.effective_weight(&task2), 12.0);
+}
+
+#[test]
+/// The penalty shrinks with each later selection.
+fn test_no_repeat_decay() {
+    let mut selector = NoRepeat::new(0.0, 3, 0.5);
+
+    // task 1: priority 1, selected 3 selections ago
+    // task 2: priority 12, selected 1 selection ago
+    // task 3: priority 2, selected 2 selections ago
+    let task1 = example_task_1();
+    let task2 = example_task_2();
+    let task3 = example_task_3();
+    selector.set_context(&context_with_recent(&[&task2, &task3, &task1]));
+
+    assert_eq!(selector.effective_weight(&task1), 0.5);
+    assert_eq!(selector.effective_weight(&task2), 12.0 * 0.125);
+    assert_eq!(selector.effective_weight(&task3), 2.0 * 0.25);
+}
+
+#[test]
+/// Only the most recent selection of a task counts.
+fn test_no_repeat_duplicate_history() {
+    let mut selector = NoRepeat::new(0.0, 3, 0.5);
+
+    let task = example_task_2();
+    let other = Uuid::new_v4();
+    let mut context = SelectionContext::empty();
+    context.recent_selections = vec![other, *task.uuid(), *task.uuid()];
+    selector.set_context(&context);
+
+    // selected 2 selections ago
+    assert_eq!(selector.effective_weight(&task), 12.0 * 0.25);
+}
+
+#[test]
+/// If every task is excluded, the penalty is ignored.
+fn test_no_repeat_all_excluded() {
+    let mut selector = NoRepeat::new(0.0, 3, 0.0);
+
+    let task1 = example_task_1();
+    let task2 = example_task_2();
+    selector.set_context(&context_with_recent(&[&task1, &task2]));
+
+    let task_refs = vec![&task1, &task2];
+    let probabilities = selector.task_probabilities(&task_refs);
+    assert_eq!(probabilities, vec![1.0/13.0, 12.0/13.0]);
+
+    // doesn't panic
+    selector.select_task(&task_refs);
+}
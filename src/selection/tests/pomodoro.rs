@@ -0,0 +1,92 @@
+use chrono::Duration;
+
+use crate::task::Category;
+
+use crate::selection::{Pomodoro, SelectionStrategy, SelectionContext};
+
+/// Make a context where `tasks_completed` tasks were completed in the `minutes` minutes of work
+/// since the last break.
+fn context_since_break(tasks_completed: u32, minutes: i64) -> SelectionContext {
+    let mut context = SelectionContext::empty();
+    context.break_stats.tasks_completed = tasks_completed;
+    context.break_stats.last_break = context.now - Duration::minutes(minutes);
+    context.work_since_break = Duration::minutes(minutes);
+
+    context
+}
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+}
+
+#[test]
+/// Right after a break, the break probability is the base probability.
+fn test_pomodoro_after_break() {
+    let mut selector = Pomodoro::new(0.25, 4, Some(Duration::minutes(100)));
+    selector.set_context(&context_since_break(0, 0));
+
+    assert_close(selector.break_probability(), 0.25);
+}
+
+#[test]
+/// The break probability ramps up linearly with work time since the last break.
+fn test_pomodoro_ramp() {
+    let mut selector = Pomodoro::new(0.25, 4, Some(Duration::minutes(100)));
+
+    selector.set_context(&context_since_break(0, 50));
+    assert_close(selector.break_probability(), 0.625);
+
+    selector.set_context(&context_since_break(3, 75));
+    assert_close(selector.break_probability(), 0.8125);
+}
+
+#[test]
+/// A break is forced after enough time has been worked.
+fn test_pomodoro_forced_by_time() {
+    let mut selector = Pomodoro::new(0.25, 4, Some(Duration::minutes(100)));
+
+    selector.set_context(&context_since_break(0, 100));
+    assert_close(selector.break_probability(), 1.0);
+
+    selector.set_context(&context_since_break(0, 1000));
+    assert_close(selector.break_probability(), 1.0);
+    for _ in 0..100 {
+        assert_eq!(selector.select_category(), Category::Break);
+    }
+}
+
+#[test]
+/// Time spent idle after the last break doesn't count towards the work time, so coming back after
+/// being away longer than the work time doesn't force a break.
+fn test_pomodoro_idle() {
+    let mut selector = Pomodoro::new(0.25, 4, Some(Duration::minutes(100)));
+
+    let mut context = context_since_break(1, 1000);
+    context.work_since_break = Duration::minutes(50);
+    selector.set_context(&context);
+    assert_close(selector.break_probability(), 0.625);
+}
+
+#[test]
+/// A break is forced after enough tasks have been completed.
+fn test_pomodoro_forced_by_tasks() {
+    let mut selector = Pomodoro::new(0.0, 4, Some(Duration::minutes(100)));
+
+    selector.set_context(&context_since_break(3, 0));
+    assert_close(selector.break_probability(), 0.0);
+
+    selector.set_context(&context_since_break(4, 0));
+    assert_close(selector.break_probability(), 1.0);
+    for _ in 0..100 {
+        assert_eq!(selector.select_category(), Category::Break);
+    }
+}
+
+#[test]
+/// With both limits disabled, the probability is always the base probability.
+fn test_pomodoro_disabled() {
+    let mut selector = Pomodoro::new(0.25, 0, None);
+
+    selector.set_context(&context_since_break(1000, 1000));
+    assert_close(selector.break_probability(), 0.25);
+}
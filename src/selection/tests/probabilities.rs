@@ -0,0 +1,94 @@
+use crate::task::Task;
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_3,
+    example_task_break_1, example_task_break_2, arb_task_list};
+
+use crate::selection::{WeightedRandom, Top, SelectionStrategy, selection_probabilities};
+
+/// Check that two floats are equal up to rounding error.
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+}
+
+#[test]
+fn test_probabilities_weighted_random_tasks() {
+    let selector = WeightedRandom::new(0.0);
+
+    // task 2: priority 12
+    // task 3: priority 2
+    let tasks = [example_task_2(), example_task_3()];
+    let task_refs: Vec<&Task> = tasks.iter().collect();
+
+    let probabilities = selector.task_probabilities(&task_refs);
+    assert_close(probabilities[0], 12.0/14.0);
+    assert_close(probabilities[1], 2.0/14.0);
+}
+
+#[test]
+fn test_probabilities_top_tasks() {
+    let selector = Top::new();
+
+    // task 1: priority 1
+    // task 2: priority 12
+    // task 3: priority 2
+    let tasks = [example_task_1(), example_task_2(), example_task_3()];
+    let task_refs: Vec<&Task> = tasks.iter().collect();
+
+    assert_eq!(selector.task_probabilities(&task_refs), vec![0.0, 1.0, 0.0]);
+    assert_eq!(selector.break_probability(), 0.0);
+}
+
+#[test]
+/// With both tasks and breaks, task probabilities are scaled by the category probabilities.
+fn test_probabilities_both_categories() {
+    let selector = WeightedRandom::new(0.25);
+
+    // task 1: priority 1, task 2: priority 12
+    // break 1: priority 1, break 2: priority 99
+    let tasks = vec![example_task_1(), example_task_2(), example_task_break_1(), example_task_break_2()];
+
+    let probabilities = selection_probabilities(&selector, &tasks);
+    assert_close(probabilities.break_probability, 0.25);
+
+    let expected = [0.75 * 1.0/13.0, 0.75 * 12.0/13.0, 0.25 * 1.0/100.0, 0.25 * 99.0/100.0];
+    for ((task, p), (original, expected_p)) in probabilities.tasks.iter().zip(tasks.iter().zip(expected.iter())) {
+        assert_eq!(task, original);
+        assert_close(*p, *expected_p);
+    }
+}
+
+#[test]
+/// With only one category, it is always chosen regardless of the break probability.
+fn test_probabilities_one_category() {
+    let selector = WeightedRandom::new(0.25);
+
+    let tasks = vec![example_task_1()];
+    let probabilities = selection_probabilities(&selector, &tasks);
+    assert_close(probabilities.break_probability, 0.0);
+    assert_close(probabilities.tasks[0].1, 1.0);
+
+    let breaks = vec![example_task_break_1()];
+    let probabilities = selection_probabilities(&selector, &breaks);
+    assert_close(probabilities.break_probability, 1.0);
+    assert_close(probabilities.tasks[0].1, 1.0);
+}
+
+#[test]
+fn test_probabilities_empty() {
+    let selector = WeightedRandom::new(0.25);
+
+    let probabilities = selection_probabilities(&selector, &[]);
+    assert!(probabilities.tasks.is_empty());
+}
+
+proptest! {
+    #[test]
+    /// The probabilities of all tasks always sum to 1.
+    fn test_probabilities_sum_to_one_arb(break_probability in 0.0..1.0f32, tasks in arb_task_list()) {
+        let selector = WeightedRandom::new(break_probability);
+
+        let probabilities = selection_probabilities(&selector, &tasks);
+        let total: f64 = probabilities.tasks.iter().map(|(_, p)| p).sum();
+
+        prop_assert!((total - 1.0).abs() < 1e-6, "Probabilities sum to {}", total);
+    }
+}
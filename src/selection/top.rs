@@ -32,4 +32,17 @@ impl SelectionStrategy for Top {
             .expect("Tasks slice cannot be empty due to above assertion")
             .0 // take the index
     }
+
+    fn break_probability(&self) -> f64 {
+        0.0
+    }
+
+    fn task_probabilities(&self, tasks: &[&Task]) -> Vec<f64> {
+        let mut probabilities = vec![0.0; tasks.len()];
+        if let Some((idx, _)) = tasks.iter().enumerate().max_by_key(|(_, t)| t.priority()) {
+            probabilities[idx] = 1.0;
+        }
+
+        probabilities
+    }
 }
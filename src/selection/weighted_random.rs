@@ -46,4 +46,8 @@ impl SelectionStrategy for WeightedRandom {
 
         dist.sample(&mut self.rng)
     }
+
+    fn break_probability(&self) -> f64 {
+        self.break_probability.into()
+    }
 }
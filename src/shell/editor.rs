@@ -0,0 +1,203 @@
+use unicode_width::UnicodeWidthStr;
+
+use crate::tui::Key;
+
+/// Suggests completions for the word being typed.
+pub trait Completer {
+    /// Return every completion for the last word of `line`, which is the text before the cursor.
+    /// The completions replace the whole word.
+    fn complete(&self, line: &str) -> Vec<String>;
+}
+
+/// What happened after a keypress.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditResult {
+    /// Keep editing.
+    Continue,
+    /// Enter was pressed; this is the line.
+    Submit(String),
+    /// Tab was pressed with more than one possible completion.
+    Candidates(Vec<String>),
+    /// Ctrl-C was pressed; the line was discarded.
+    Interrupt,
+    /// Ctrl-D was pressed on an empty line.
+    Eof,
+}
+
+/// A single-line editor with history, operating on keys read in raw mode.
+#[derive(Debug, Clone, Default)]
+pub struct LineEditor {
+    buffer: Vec<char>,
+    /// The position of the cursor in `buffer`, in chars.
+    cursor: usize,
+    history: Vec<String>,
+    /// The history entry being shown, or `None` if editing a new line.
+    history_index: Option<usize>,
+    /// The new line being edited before moving into the history, so it can be restored.
+    saved_line: String,
+}
+
+impl LineEditor {
+    pub fn new(history: Vec<String>) -> LineEditor {
+        LineEditor { history, ..LineEditor::default() }
+    }
+
+    pub fn line(&self) -> String {
+        self.buffer.iter().collect()
+    }
+
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Add a submitted line to the history, unless it repeats the previous one.
+    pub fn add_history(&mut self, line: &str) {
+        if self.history.last().map(String::as_str) != Some(line) {
+            self.history.push(line.to_string());
+        }
+    }
+
+    fn set_line(&mut self, line: &str) {
+        self.buffer = line.chars().collect();
+        self.cursor = self.buffer.len();
+    }
+
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.cursor = 0;
+        self.history_index = None;
+        self.saved_line.clear();
+    }
+
+    fn history_up(&mut self) {
+        let index = match self.history_index {
+            None if self.history.is_empty() => return,
+            None => {
+                self.saved_line = self.line();
+                self.history.len() - 1
+            }
+            Some(0) => return,
+            Some(i) => i - 1,
+        };
+        self.history_index = Some(index);
+        let line = self.history[index].clone();
+        self.set_line(&line);
+    }
+
+    fn history_down(&mut self) {
+        match self.history_index {
+            None => {}
+            Some(i) if i + 1 < self.history.len() => {
+                self.history_index = Some(i + 1);
+                let line = self.history[i + 1].clone();
+                self.set_line(&line);
+            }
+            Some(_) => {
+                self.history_index = None;
+                let line = self.saved_line.clone();
+                self.set_line(&line);
+            }
+        }
+    }
+
+    /// Replace the word before the cursor with the completion if there is exactly one, or with the
+    /// longest prefix shared by all of them. Returns the candidates if that doesn't change
+    /// anything.
+    fn complete(&mut self, completer: &dyn Completer) -> EditResult {
+        let before: String = self.buffer[..self.cursor].iter().collect();
+        let candidates = completer.complete(&before);
+        let word_start = self.buffer[..self.cursor].iter().rposition(|c| c.is_whitespace()).map_or(0, |i| i + 1);
+        let word: String = self.buffer[word_start..self.cursor].iter().collect();
+
+        let replacement = match candidates.as_slice() {
+            [] => return EditResult::Continue,
+            [only] => format!("{} ", only),
+            _ => {
+                let prefix = common_prefix(&candidates);
+                if prefix.chars().count() <= word.chars().count() {
+                    return EditResult::Candidates(candidates);
+                }
+                prefix
+            }
+        };
+
+        let after: Vec<char> = self.buffer[self.cursor..].to_vec();
+        self.buffer.truncate(word_start);
+        self.buffer.extend(replacement.chars());
+        self.cursor = self.buffer.len();
+        self.buffer.extend(after);
+
+        EditResult::Continue
+    }
+
+    /// Handle a keypress.
+    pub fn handle_key(&mut self, key: Key, completer: &dyn Completer) -> EditResult {
+        match key {
+            Key::Enter => {
+                let line = self.line();
+                self.reset();
+                return EditResult::Submit(line);
+            }
+            Key::CtrlC => {
+                self.reset();
+                return EditResult::Interrupt;
+            }
+            Key::CtrlD if self.buffer.is_empty() => return EditResult::Eof,
+            Key::CtrlD | Key::Delete if self.cursor < self.buffer.len() => {
+                self.buffer.remove(self.cursor);
+            }
+            Key::Tab => return self.complete(completer),
+            Key::Char(c) => {
+                self.buffer.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            Key::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.buffer.remove(self.cursor);
+            }
+            Key::Left => self.cursor = self.cursor.saturating_sub(1),
+            Key::Right => self.cursor = (self.cursor + 1).min(self.buffer.len()),
+            Key::Home => self.cursor = 0,
+            Key::End => self.cursor = self.buffer.len(),
+            Key::Up => self.history_up(),
+            Key::Down => self.history_down(),
+            _ => {}
+        }
+
+        EditResult::Continue
+    }
+
+    /// The escape sequence that redraws the current line after `prompt` and puts the cursor in
+    /// place.
+    pub fn redraw(&self, prompt: &str) -> String {
+        let before: String = self.buffer[..self.cursor].iter().collect();
+        let column = prompt.width() + before.width();
+
+        // go to the start of the line, clear it, draw it, then move the cursor to its column. a
+        // movement of 0 columns moves 1 on most terminals, so skip it.
+        let mut redraw = format!("\r\x1b[2K{}{}\r", prompt, self.line());
+        if column > 0 {
+            redraw.push_str(&format!("\x1b[{}C", column));
+        }
+        redraw
+    }
+}
+
+/// The longest prefix shared by all of `words`.
+fn common_prefix(words: &[String]) -> String {
+    let first = match words.first() {
+        Some(first) => first,
+        None => return String::new(),
+    };
+
+    let mut len = first.len();
+    for word in &words[1..] {
+        len = first.char_indices().zip(word.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8())
+            .min(len);
+    }
+
+    first[..len].to_string()
+}
@@ -0,0 +1,269 @@
+//! An interactive shell that runs taskerizer commands without starting a new process for each one.
+//! Each line is parsed with the same grammar as the command line and dispatched in its own
+//! transaction, so an error in one command doesn't undo the ones before it.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use failure::Error;
+use structopt::StructOpt;
+use structopt::clap::ErrorKind;
+
+use crate::commands::{TKZArgs, TKZCmd, task_id_candidates};
+use crate::config::Config;
+use crate::tui::RawMode;
+
+mod editor;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::editor::{Completer, EditResult, LineEditor};
+
+const PROMPT: &str = "tkzr> ";
+
+/// The file in the database directory that history is saved to.
+const HISTORY_FILE: &str = "shell_history";
+/// The number of lines of history that are saved.
+const HISTORY_LENGTH: usize = 1000;
+
+/// Commands that are handled by the shell itself.
+const BUILTINS: &[&str] = &["exit", "quit"];
+
+/// Split a line into words like a POSIX shell does, without expansions: whitespace separates
+/// words, single and double quotes group them, and a backslash escapes the next character outside
+/// single quotes.
+pub fn split_words(line: &str) -> Result<Vec<String>, Error> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(format_err!("Unterminated single quote.")),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c) => word.push(c),
+                            None => return Err(format_err!("Unterminated double quote.")),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(format_err!("Unterminated double quote.")),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if let Some(c) = chars.next() {
+                    word.push(c);
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+
+    if in_word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+/// Parse and run a single line, returning the rendered output. Help requested with `help` or
+/// `--help` is returned as output rather than an error.
+pub fn run_line(config: &Config, line: &str) -> Result<Vec<String>, Error> {
+    run_line_with_progress(config, line, &mut |_| ())
+}
+
+/// Like `run_line`, but output that is ready before the command finishes is rendered and passed to
+/// `progress`, as with `TKZCmd::dispatch_with_progress`.
+pub fn run_line_with_progress(config: &Config, line: &str, progress: &mut dyn FnMut(Vec<String>))
+    -> Result<Vec<String>, Error> {
+    let words = split_words(line)?;
+    let args = match TKZArgs::from_iter_safe(std::iter::once("tkzr".to_string()).chain(words)) {
+        Ok(args) => args,
+        Err(e) => match e.kind {
+            ErrorKind::HelpDisplayed | ErrorKind::VersionDisplayed => {
+                return Ok(e.message.lines().map(String::from).collect());
+            }
+            // clap starts its messages with "error: "
+            _ => return Err(format_err!("{}", e.message.trim_start_matches("error: "))),
+        },
+    };
+
+    if let TKZCmd::Shell = args.cmd() {
+        return Err(format_err!("Already in the shell."));
+    }
+
+    let renderer = args.format.renderer(args.color);
+    let output = args.cmd().dispatch_with_progress(config, &mut |output| progress(renderer.render(output)))?;
+    Ok(renderer.render(&output))
+}
+
+/// Completes subcommand names for the first word, and task UUIDs after the subcommands in
+/// `TKZCmd::TASK_ID_COMMANDS`.
+pub struct ShellCompleter<'c> {
+    pub config: &'c Config,
+}
+
+impl<'c> Completer for ShellCompleter<'c> {
+    fn complete(&self, line: &str) -> Vec<String> {
+        let (command, word) = match line.rfind(char::is_whitespace) {
+            None => ("", line),
+            Some(i) => (line[..i].trim(), &line[i + 1..]),
+        };
+
+        if command.is_empty() {
+            return TKZCmd::NAMES.iter().chain(BUILTINS)
+                .filter(|name| name.starts_with(word))
+                .map(|name| name.to_string())
+                .collect();
+        }
+
+        let command = command.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !TKZCmd::TASK_ID_COMMANDS.iter().any(|words| words.join(" ") == command) {
+            return Vec::new();
+        }
+
+        // errors are ignored since there is nowhere to display them while editing
+        self.task_ids(&command).unwrap_or_default().into_iter()
+            .filter(|id| id.starts_with(word))
+            .collect()
+    }
+}
+
+impl<'c> ShellCompleter<'c> {
+    /// The UUIDs of the tasks that can be given to `command`. The transaction is rolled back since
+    /// nothing is changed.
+    fn task_ids(&self, command: &str) -> Result<Vec<String>, Error> {
+        let mut db = self.config.db()?;
+        let tx = db.transaction()?;
+        let tasks = task_id_candidates(&tx, command)?;
+
+        Ok(tasks.iter().map(|task| task.uuid().to_string()).collect())
+    }
+}
+
+fn load_history(path: &Path) -> Vec<String> {
+    // a missing or unreadable history file just means there is no history yet
+    fs::read_to_string(path)
+        .map(|history| history.lines().map(String::from).collect())
+        .unwrap_or_default()
+}
+
+fn save_history(path: &Path, history: &[String]) -> Result<(), Error> {
+    let start = history.len().saturating_sub(HISTORY_LENGTH);
+    let mut contents = history[start..].join("\n");
+    contents.push('\n');
+
+    fs::write(path, contents)
+        .map_err(|e| format_err!("Could not save shell history to {}: {}", path.display(), e))
+}
+
+/// Read a line from the terminal with line editing. Returns `None` at the end of input.
+fn read_line(editor: &mut LineEditor, completer: &dyn Completer) -> Result<Option<String>, Error> {
+    let mut raw = RawMode::enable()?;
+    let stdout = io::stdout();
+
+    print!("{}", editor.redraw(PROMPT));
+    stdout.lock().flush()?;
+
+    loop {
+        let result = editor.handle_key(raw.read_key()?, completer);
+        let mut out = stdout.lock();
+        match result {
+            EditResult::Continue => {}
+            EditResult::Submit(line) => {
+                write!(out, "\r\n")?;
+                return Ok(Some(line));
+            }
+            EditResult::Candidates(candidates) => {
+                // raw mode doesn't translate \n into \r\n
+                write!(out, "\r\n{}\r\n", candidates.join("  "))?;
+            }
+            EditResult::Interrupt => write!(out, "^C\r\n")?,
+            EditResult::Eof => {
+                write!(out, "\r\n")?;
+                return Ok(None);
+            }
+        }
+        write!(out, "{}", editor.redraw(PROMPT))?;
+        out.flush()?;
+    }
+}
+
+/// Run the shell until the user exits or input ends. When stdin is not a terminal, lines are read
+/// without editing or history.
+pub fn run(config: &Config) -> Result<(), Error> {
+    let interactive = atty::is(atty::Stream::Stdin);
+    let history_path = config.db_path.join(HISTORY_FILE);
+    let mut editor = LineEditor::new(if interactive { load_history(&history_path) } else { Vec::new() });
+    let completer = ShellCompleter { config };
+
+    loop {
+        let line = if interactive {
+            read_line(&mut editor, &completer)?
+        }
+        else {
+            let mut line = String::new();
+            match io::stdin().read_line(&mut line)? {
+                0 => None,
+                _ => Some(line),
+            }
+        };
+        let line = match line {
+            Some(line) => line,
+            None => break,
+        };
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history(line);
+        if BUILTINS.contains(&line) {
+            break;
+        }
+
+        let mut progress = |lines: Vec<String>| for line in lines {
+            eprintln!("{}", line);
+        };
+        match run_line_with_progress(config, line, &mut progress) {
+            Ok(output) => {
+                for line in output {
+                    println!("{}", line);
+                }
+            }
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+
+    if interactive {
+        save_history(&history_path, editor.history())?;
+    }
+
+    Ok(())
+}
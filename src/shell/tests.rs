@@ -0,0 +1,168 @@
+use structopt::StructOpt;
+use structopt::clap::ErrorKind;
+
+use crate::commands::{TKZArgs, TKZCmd};
+use crate::config::Config;
+use crate::db::DBBackend;
+use crate::tui::Key;
+
+use super::{Completer, EditResult, LineEditor, ShellCompleter, split_words, run_line};
+
+/// Completes from a fixed list of words.
+struct WordCompleter(Vec<&'static str>);
+
+impl Completer for WordCompleter {
+    fn complete(&self, line: &str) -> Vec<String> {
+        let word = line.rsplit(' ').next().unwrap_or("");
+        self.0.iter().filter(|w| w.starts_with(word)).map(|w| w.to_string()).collect()
+    }
+}
+
+fn type_str(editor: &mut LineEditor, s: &str) {
+    let completer = WordCompleter(Vec::new());
+    for c in s.chars() {
+        assert_eq!(editor.handle_key(Key::Char(c), &completer), EditResult::Continue);
+    }
+}
+
+#[test]
+fn test_shell_split_words() {
+    assert_eq!(split_words("add 'a task' 3").unwrap(), vec!["add", "a task", "3"]);
+    assert_eq!(split_words("  add \"say \\\"hi\\\"\"  ").unwrap(), vec!["add", "say \"hi\""]);
+    assert_eq!(split_words("add it\\'s").unwrap(), vec!["add", "it's"]);
+    assert_eq!(split_words("add ''").unwrap(), vec!["add", ""]);
+    assert!(split_words("").unwrap().is_empty());
+    assert!(split_words("add 'oops").is_err());
+}
+
+#[test]
+fn test_shell_editor_edit() {
+    let completer = WordCompleter(Vec::new());
+    let mut editor = LineEditor::new(Vec::new());
+
+    type_str(&mut editor, "ad");
+    editor.handle_key(Key::Left, &completer);
+    editor.handle_key(Key::Left, &completer);
+    type_str(&mut editor, "x");
+    editor.handle_key(Key::Delete, &completer);
+    editor.handle_key(Key::End, &completer);
+    type_str(&mut editor, "dd");
+    editor.handle_key(Key::Backspace, &completer);
+
+    assert_eq!(editor.handle_key(Key::Enter, &completer), EditResult::Submit("xdd".to_string()));
+    assert_eq!(editor.line(), "");
+    assert_eq!(editor.handle_key(Key::CtrlD, &completer), EditResult::Eof);
+}
+
+#[test]
+/// Up and down move through the history and back to the line being edited.
+fn test_shell_editor_history() {
+    let completer = WordCompleter(Vec::new());
+    let mut editor = LineEditor::new(vec!["list".to_string()]);
+    editor.add_history("skip");
+    editor.add_history("skip");
+    assert_eq!(editor.history(), &["list".to_string(), "skip".to_string()]);
+
+    type_str(&mut editor, "cur");
+    editor.handle_key(Key::Up, &completer);
+    assert_eq!(editor.line(), "skip");
+    editor.handle_key(Key::Up, &completer);
+    editor.handle_key(Key::Up, &completer);
+    assert_eq!(editor.line(), "list");
+    editor.handle_key(Key::Down, &completer);
+    assert_eq!(editor.line(), "skip");
+    editor.handle_key(Key::Down, &completer);
+    assert_eq!(editor.line(), "cur");
+}
+
+#[test]
+fn test_shell_editor_complete() {
+    let completer = WordCompleter(vec!["simulate", "skip", "shell"]);
+    let mut editor = LineEditor::new(Vec::new());
+
+    // one candidate is completed with a space after it
+    type_str(&mut editor, "sk");
+    assert_eq!(editor.handle_key(Key::Tab, &completer), EditResult::Continue);
+    assert_eq!(editor.line(), "skip ");
+
+    // several candidates are listed if they don't share a longer prefix
+    editor.handle_key(Key::CtrlC, &completer);
+    type_str(&mut editor, "s");
+    assert_eq!(editor.handle_key(Key::Tab, &completer),
+               EditResult::Candidates(vec!["simulate".to_string(), "skip".to_string(), "shell".to_string()]));
+    assert_eq!(editor.line(), "s");
+
+    // the word is extended to the shared prefix
+    let completer = WordCompleter(vec!["abc-1", "abc-2"]);
+    type_str(&mut editor, " a");
+    editor.handle_key(Key::Tab, &completer);
+    assert_eq!(editor.line(), "s abc-");
+}
+
+#[test]
+fn test_shell_editor_redraw() {
+    let mut editor = LineEditor::new(Vec::new());
+    assert_eq!(editor.redraw(""), "\r\x1b[2K\r");
+
+    type_str(&mut editor, "ab");
+    editor.handle_key(Key::Left, &WordCompleter(Vec::new()));
+    assert_eq!(editor.redraw("> "), "\r\x1b[2K> ab\r\x1b[3C");
+}
+
+#[test]
+/// Every subcommand name offered for completion is a real subcommand.
+fn test_shell_command_names() {
+    for name in TKZCmd::NAMES {
+        let err = TKZArgs::from_iter_safe(&["tkzr", name, "--help"]).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::HelpDisplayed, "{} is not a subcommand", name);
+    }
+}
+
+#[test]
+fn test_shell_completer() {
+    let (_dir, config) = Config::test_config();
+    let completer = ShellCompleter { config: &config };
+
+    assert_eq!(completer.complete("re"), vec!["resume", "report", "restore", "remove"]);
+    assert_eq!(completer.complete("ex"), vec!["export", "exit"]);
+
+    run_line(&config, "add task").expect("Adding task failed");
+    run_line(&config, "add other").expect("Adding task failed");
+    run_line(&config, "complete").expect("Completing task failed");
+    let uuid = {
+        let mut db = config.db().unwrap();
+        let tx = db.transaction().unwrap();
+        tx.fetch_trash().unwrap()[0].task.uuid().to_string()
+    };
+
+    // only tasks in the trash can be restored
+    assert_eq!(completer.complete("trash restore "), vec![uuid.clone()]);
+    assert_eq!(completer.complete(&format!("trash  restore {}", &uuid[..4])), vec![uuid]);
+    assert!(completer.complete("trash restore zzz").is_empty());
+    assert!(completer.complete("current ").is_empty());
+}
+
+#[test]
+/// Each line runs in its own transaction, so a failing command doesn't undo earlier ones.
+fn test_shell_run_line() {
+    let (_dir, config) = Config::test_config();
+
+    let output = run_line(&config, "add 'first task' 2").expect("Adding task failed");
+    assert_eq!(output, vec!["Task \"first task\" added to task list."]);
+
+    assert!(run_line(&config, "add ''").is_err());
+    assert!(run_line(&config, "frobnicate").is_err());
+    assert!(run_line(&config, "shell").is_err());
+
+    let output = run_line(&config, "list --format tsv").expect("Listing tasks failed");
+    assert_eq!(output.len(), 1);
+    assert!(output[0].ends_with("\t2\ttask\tfirst task"));
+}
+
+#[test]
+fn test_shell_run_line_help() {
+    let (_dir, config) = Config::test_config();
+
+    let output = run_line(&config, "help").expect("Help failed");
+    assert!(output.iter().any(|line| line.contains("shell")));
+}
@@ -1,5 +1,5 @@
 use crate::task::Task;
-use crate::db::DBBackend;
+use crate::db::{DBBackend, RemovalSource};
 
 use failure::Error;
 use uuid::Uuid;
@@ -13,13 +13,13 @@ mod tests;
 #[cfg(test)]
 pub(crate) mod test_utils;
 
-/// Apply a sequence of `USetOp` U-Set operations to the database.  The result contains a
-/// `Vec<String>` which is text to return to the user - see `USetOp::apply_to_db` for more
-/// information.
-pub fn apply_all_uset_ops(tx: &impl DBBackend, operations: &[USetOp]) -> Result<Vec<String>, Error> {
+/// Apply a sequence of `USetOp` U-Set operations received from the replica `from` to the database.
+/// The result contains a `Vec<String>` which is text to return to the user - see
+/// `USetOp::apply_to_db` for more information.
+pub fn apply_all_uset_ops(tx: &impl DBBackend, from: &ReplicaUuid, operations: &[USetOp]) -> Result<Vec<String>, Error> {
     let mut output: Vec<String> = Vec::new();
     for op in operations {
-        let res = op.apply_to_db(tx);
+        let res = op.apply_to_db(tx, from);
         match res {
             Ok(v) => { output.extend(v) },
             Err(e) => {
@@ -30,6 +30,19 @@ pub fn apply_all_uset_ops(tx: &impl DBBackend, operations: &[USetOp]) -> Result<
     return Ok(output);
 }
 
+/// Store `operations` made on this replica to send to every known replica the next time it syncs.
+pub fn queue_for_all_replicas(tx: &impl DBBackend, operations: &[USetOp]) -> Result<(), Error> {
+    let replicas = tx.fetch_replicas()
+        .map_err(|e| format_err!("Could not get replicas from database. {}", e))?;
+    for (replica, _) in replicas {
+        for op in operations {
+            tx.store_uset_op_msg(&USetOpMsg { op: op.clone(), deliver_to: replica })
+                .map_err(|e| format_err!("Could not store operation for replica {}: {}", replica, e))?;
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum USetOp {
     Add (Task),
@@ -44,16 +57,16 @@ pub struct USetOpMsg {
 }
 
 impl USetOp {
-    /// Apply the U-Set operation to the database. Returns a Result containing text to print to the
-    /// user, including but not limited to a notification that the current task was removed while
-    /// applying the operation.
-    pub fn apply_to_db(&self, tx: &impl DBBackend) -> Result<Vec<String>, Error> {
+    /// Apply the U-Set operation received from the replica `from` to the database. Returns a Result
+    /// containing text to print to the user, including but not limited to a notification that the
+    /// current task was removed while applying the operation.
+    pub fn apply_to_db(&self, tx: &impl DBBackend, from: &ReplicaUuid) -> Result<Vec<String>, Error> {
         match self {
             USetOp::Add(task) => USetOp::apply_add_to_db(tx, task).map(|_| Vec::new()),
             USetOp::Remove(uuid) => {
                 // FIXME: there's probably a simpler way of doing this than the nested maps,
                 // probably using flat_map earlier.
-                USetOp::apply_remove_to_db(tx, uuid)
+                USetOp::apply_remove_to_db(tx, uuid, from)
                     .map(|opt_task| {
                         opt_task.iter().flat_map(|task| {
                             vec![format!("Current task removed during sync: {}", task.task())]
@@ -69,10 +82,10 @@ impl USetOp {
         tx.add_task(task)
     }
 
-    /// Apply a U-Set remove operation to the database. If the task to be removed was the current task, it
-    /// is returned so the user can be notified. If there is no task in the database with the given
-    /// UUID, nothing happens.
-    fn apply_remove_to_db(tx: &impl DBBackend, uuid: &ReplicaUuid) -> Result<Option<Task>, Error> {
-        tx.remove_task_by_uuid(uuid)
+    /// Apply a U-Set remove operation to the database, moving the task to the trash. If the task to
+    /// be removed was the current task, it is returned so the user can be notified. If there is no
+    /// task in the database with the given UUID, nothing happens.
+    fn apply_remove_to_db(tx: &impl DBBackend, uuid: &ReplicaUuid, from: &ReplicaUuid) -> Result<Option<Task>, Error> {
+        tx.remove_task_by_uuid(uuid, &RemovalSource::Replica(*from))
     }
 }
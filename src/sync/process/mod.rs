@@ -73,7 +73,7 @@ pub fn process_sync(tx: &mut impl DBBackend, incoming_replica: ReplicaUuid, inco
 
     // Apply incoming ops after getting existing messages.
     // move this into separate function
-    apply_all_uset_ops(tx, incoming_ops)
+    apply_all_uset_ops(tx, &incoming_replica, incoming_ops)
         .map_err(|e| format_err!("Failed to apply incoming operations from client {} while processing incoming sync: {}",
                                  incoming_replica, e))?;
     for (replica_id, _) in &replicas {
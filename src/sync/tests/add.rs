@@ -6,7 +6,7 @@ use crate::sync::USetOp;
 
 use crate::db::tests::open_test_db;
 use crate::task::test_utils::{example_task_1, example_task_break_1, example_task_2};
-use crate::sync::test_utils::uset_add_list_arb;
+use crate::sync::test_utils::{example_replica_1, uset_add_list_arb};
 
 #[test]
 /// Add via uset, check task is in list
@@ -18,7 +18,7 @@ fn test_uset_add_task_1() {
 
     // add example task
     let op = USetOp::Add(task.clone());
-    let res = op.apply_to_db(&tx);
+    let res = op.apply_to_db(&tx, &example_replica_1());
     assert!(res.is_ok(), "Could not apply add operation to db: {}", res.unwrap_err());
 
     // read tasks from db and check it's there
@@ -36,7 +36,7 @@ fn test_uset_add_break_1() {
 
     // add example task
     let op = USetOp::Add(task.clone());
-    let res = op.apply_to_db(&tx);
+    let res = op.apply_to_db(&tx, &example_replica_1());
     assert!(res.is_ok(), "Could not apply add operation to db: {}", res.unwrap_err());
 
     // read tasks from db and check it's there
@@ -55,7 +55,7 @@ fn test_uset_add_error_dup_uuid() {
     tx.add_task(&task).expect("Failed adding test");
 
     let op = USetOp::Add(task.clone());
-    let res = op.apply_to_db(&tx);
+    let res = op.apply_to_db(&tx, &example_replica_1());
     assert!(res.is_err(), "No error adding task with duplicate uuid to db");
 
     let err = res.unwrap_err();
@@ -72,7 +72,7 @@ proptest! {
 
         // apply all add ops
         for op in &ops {
-            let res = op.apply_to_db(&tx);
+            let res = op.apply_to_db(&tx, &example_replica_1());
             prop_assert!(res.is_ok(), "Could not apply add operation to db: {}", res.unwrap_err());
         }
 
@@ -4,7 +4,7 @@ use crate::sync::{apply_all_uset_ops, USetOp};
 
 use crate::db::tests::open_test_db;
 use crate::task::test_utils::{example_task_1, example_task_2, example_task_3};
-use crate::sync::test_utils::{example_remove_uset_op_2};
+use crate::sync::test_utils::{example_replica_1, example_remove_uset_op_2};
 
 // TODO: add proptests
 
@@ -15,7 +15,7 @@ fn test_uset_apply_all_empty() {
 
     let tasks: Vec<USetOp> = Vec::new();
 
-    let res = apply_all_uset_ops(&tx, &tasks);
+    let res = apply_all_uset_ops(&tx, &example_replica_1(), &tasks);
     assert!(res.is_ok(), "Error applying all uset ops: {}", res.unwrap_err());
 
     let messages = res.unwrap();
@@ -30,7 +30,7 @@ fn test_uset_apply_all_2() {
 
     let tasks = vec![USetOp::Add(example_task_1()), USetOp::Add(example_task_2())];
 
-    let res = apply_all_uset_ops(&tx, &tasks);
+    let res = apply_all_uset_ops(&tx, &example_replica_1(), &tasks);
     assert!(res.is_ok(), "Error applying all uset ops: {}", res.unwrap_err());
 
     let messages = res.unwrap();
@@ -54,7 +54,7 @@ fn test_uset_apply_all_duplicate_error() {
     // task_1 twice
     let tasks = vec![USetOp::Add(example_task_1()), USetOp::Add(example_task_1())];
    
-    let res = apply_all_uset_ops(&tx, &tasks);
+    let res = apply_all_uset_ops(&tx, &example_replica_1(), &tasks);
     assert!(res.is_err(), "No error when adding duplicate tasks with the same id");
     drop(tx);
 
@@ -77,7 +77,7 @@ fn test_uset_apply_all_duplicate_error_keeps_existing_task() {
     // task_1 twice
     let tasks = vec![USetOp::Add(example_task_3()), USetOp::Add(example_task_1()), USetOp::Add(example_task_1())];
 
-    let res = apply_all_uset_ops(&tx, &tasks);
+    let res = apply_all_uset_ops(&tx, &example_replica_1(), &tasks);
     assert!(res.is_err(), "No error when adding duplicate tasks with the same id");
     drop(tx);
 
@@ -95,7 +95,7 @@ fn test_uset_apply_all_remove_non_existant() {
     // add task_1, remove task2
     let tasks = vec![USetOp::Add(example_task_1()), example_remove_uset_op_2()];
 
-    let res = apply_all_uset_ops(&tx, &tasks);
+    let res = apply_all_uset_ops(&tx, &example_replica_1(), &tasks);
     assert!(res.is_ok(), "Unexpected error when removing non-existant task via uset op: {}");
     tx.finish().unwrap();
 
@@ -121,7 +121,7 @@ fn test_uset_apply_all_remove_current_task() {
     // remove task2, add task1
     let tasks = vec![example_remove_uset_op_2(), USetOp::Add(example_task_1())];
 
-    let res = apply_all_uset_ops(&tx, &tasks);
+    let res = apply_all_uset_ops(&tx, &example_replica_1(), &tasks);
     assert!(res.is_ok(), "Unexpected error when removing non-existant task via uset op: {}");
 
     let messages = res.unwrap();
@@ -2,13 +2,13 @@ use std::collections::HashSet;
 
 use uuid::Uuid;
 
-use crate::db::DBBackend;
+use crate::db::{DBBackend, RemovalSource};
 use crate::selection::{Top, WeightedRandom};
 use crate::sync::USetOp;
 
 use crate::db::tests::open_test_db;
 use crate::task::test_utils::{example_task_1, example_task_2};
-use crate::sync::test_utils::uset_add_list_arb;
+use crate::sync::test_utils::{example_replica_1, example_replica_2, uset_add_list_arb};
 
 
 // there isn't really much difference between these two tests because USetOp::Add is literally a
@@ -28,13 +28,30 @@ fn test_uset_remove_task_local_1() {
     tx.add_task(&task).expect("failed to add task");
 
     let op = USetOp::Remove(task.uuid().clone());
-    let res = op.apply_to_db(&tx);
+    let res = op.apply_to_db(&tx, &example_replica_1());
     assert!(res.is_ok(), "Could not remove task from database via USetOp: {}", res.unwrap_err());
 
     let db_tasks = tx.fetch_all_tasks().expect("Couldn't get tasks");
     assert!(db_tasks.is_empty(), "DB contained task after removing only task: {:?}", db_tasks);
 }
 
+#[test]
+/// Tasks removed via uset are moved to the trash with the replica the operation came from.
+fn test_uset_remove_task_trash() {
+    let mut db = open_test_db();
+    let tx = db.transaction().unwrap();
+
+    let task = example_task_1();
+    tx.add_task(&task).expect("Failed adding task");
+
+    USetOp::Remove(*task.uuid()).apply_to_db(&tx, &example_replica_2()).expect("Failed removing task via uset");
+
+    let trash = tx.fetch_trash().expect("Couldn't get trash");
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].task, task);
+    assert_eq!(trash[0].source, RemovalSource::Replica(example_replica_2()));
+}
+
 #[test]
 /// Add via uset, remove via uset
 fn test_uset_remove_task_remote_1() {
@@ -45,10 +62,10 @@ fn test_uset_remove_task_remote_1() {
 
     // add example task
     let add_op = USetOp::Add(task.clone());
-    add_op.apply_to_db(&tx).expect("Failed to add task via uset");
+    add_op.apply_to_db(&tx, &example_replica_1()).expect("Failed to add task via uset");
 
     let remove_op = USetOp::Remove(task.uuid().clone());
-    let res = remove_op.apply_to_db(&tx);
+    let res = remove_op.apply_to_db(&tx, &example_replica_1());
     assert!(res.is_ok(), "Could not remove task from database via USetOp: {}", res.unwrap_err());
 
     let report = res.unwrap();
@@ -70,7 +87,7 @@ fn test_uset_remove_current() {
     tx.add_task(&task).expect("Failed removing task");
     tx.select_current_task(&mut selector).expect("Failed choosing current task");
 
-    let res = USetOp::apply_remove_to_db(&tx, task.uuid());
+    let res = USetOp::apply_remove_to_db(&tx, task.uuid(), &example_replica_1());
     assert!(res.is_ok(), "Failed removing task via USetOp: {}", res.unwrap_err());
 
     let opt = res.unwrap();
@@ -94,7 +111,7 @@ fn test_uset_remove_non_current() {
     let task2 = example_task_2();
     tx.add_task(&task2).expect("Failed adding task");
 
-    let res = USetOp::apply_remove_to_db(&tx, task2.uuid());
+    let res = USetOp::apply_remove_to_db(&tx, task2.uuid(), &example_replica_1());
     assert!(res.is_ok(), "Failed removing task via USetOp: {}", res.unwrap_err());
 
     let opt = res.unwrap();
@@ -110,7 +127,7 @@ proptest! {
         let tx = db.transaction().unwrap();
 
         for op in &add_ops {
-            op.apply_to_db(&tx).expect("Failed applying add op to db");
+            op.apply_to_db(&tx, &example_replica_1()).expect("Failed applying add op to db");
         }
 
         let remove_ops: Vec<USetOp> = add_ops.into_iter().map(|op| op.into_remove()).collect();
@@ -120,7 +137,7 @@ proptest! {
 
         for op in &remove_ops {
             let remove_uuid = op.clone().unwrap_remove();
-            let res = USetOp::apply_remove_to_db(&tx, &remove_uuid);
+            let res = USetOp::apply_remove_to_db(&tx, &remove_uuid, &example_replica_1());
             uuids.remove(&remove_uuid);
             prop_assert!(res.is_ok(), "Error removing task via USetOp: {}", res.unwrap_err());
 
@@ -137,7 +154,7 @@ proptest! {
 
         for op in &remove_ops {
             let remove_uuid = op.clone().unwrap_remove();
-            let res = USetOp::apply_remove_to_db(&tx, &remove_uuid);
+            let res = USetOp::apply_remove_to_db(&tx, &remove_uuid, &example_replica_1());
             prop_assert!(res.is_ok(), "Error removing previously-removed task via USetOp: {}", res.unwrap_err());
 
             let opt = res.unwrap();
@@ -158,7 +175,7 @@ proptest! {
         let tx = db.transaction().unwrap();
 
         for op in &add_ops {
-            op.apply_to_db(&tx).expect("Failed applying add op to db");
+            op.apply_to_db(&tx, &example_replica_1()).expect("Failed applying add op to db");
         }
 
         let remove_ops: Vec<USetOp> = add_ops.into_iter().map(|op| op.into_remove()).collect();
@@ -173,7 +190,7 @@ proptest! {
             let db_current_task = tx.fetch_current_task().expect("Failed to fetch current task").unwrap();
 
             let remove_uuid = op.clone().unwrap_remove();
-            let res = USetOp::apply_remove_to_db(&tx, &remove_uuid);
+            let res = USetOp::apply_remove_to_db(&tx, &remove_uuid, &example_replica_1());
             uuids.remove(&remove_uuid);
             prop_assert!(res.is_ok(), "Error removing task via USetOp: {}", res.unwrap_err());
 
@@ -201,14 +218,14 @@ proptest! {
         let tx = db.transaction().unwrap();
 
         for op in &add_ops {
-            op.apply_to_db(&tx).expect("Failed applying add op to db");
+            op.apply_to_db(&tx, &example_replica_1()).expect("Failed applying add op to db");
         }
 
         let remove_ops: Vec<USetOp> = remove_ops.into_iter().map(|op| op.into_remove()).collect();
 
         for op in &remove_ops {
             let remove_uuid = op.clone().unwrap_remove();
-            let res = USetOp::apply_remove_to_db(&tx, &remove_uuid);
+            let res = USetOp::apply_remove_to_db(&tx, &remove_uuid, &example_replica_1());
             prop_assert!(res.is_ok(), "Error removing non-existant task via USetOp: {}", res.unwrap_err());
 
             let opt = res.unwrap();
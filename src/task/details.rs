@@ -0,0 +1,26 @@
+use chrono::{Duration, NaiveDate};
+
+/// Optional information about a task that isn't used to select it: tags, a due date, and an
+/// estimate of how long it will take. Details are stored separately from the task and are not
+/// synced.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TaskDetails {
+    /// Tags, sorted and without duplicates.
+    pub tags: Vec<String>,
+    pub due: Option<NaiveDate>,
+    pub estimate: Option<Duration>,
+}
+
+impl TaskDetails {
+    /// Add a tag, keeping the tags sorted and without duplicates.
+    pub fn add_tag(&mut self, tag: &str) {
+        if let Err(idx) = self.tags.binary_search_by(|t| t.as_str().cmp(tag)) {
+            self.tags.insert(idx, tag.to_string());
+        }
+    }
+
+    /// Whether there are no details.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.due.is_none() && self.estimate.is_none()
+    }
+}
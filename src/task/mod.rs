@@ -2,6 +2,9 @@ use failure::Error;
 
 use uuid::Uuid;
 
+mod details;
+pub use self::details::TaskDetails;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Category {
     Break,
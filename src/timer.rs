@@ -0,0 +1,116 @@
+use std::process::Command;
+
+use chrono::{DateTime, Duration, Utc};
+use failure::Error;
+use uuid::Uuid;
+
+use crate::task::Task;
+
+/// A running work timer for a task, stored in the database so that other invocations of tkzr can
+/// see it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerState {
+    /// The UUID of the task the timer was started for.
+    pub task_uuid: Uuid,
+    /// When the timer was started.
+    pub started: DateTime<Utc>,
+    /// When the timer ends.
+    pub ends: DateTime<Utc>,
+}
+
+impl TimerState {
+    /// Start a timer for `task` now, ending after `length`.
+    pub fn start(task: &Task, length: Duration) -> TimerState {
+        let started = Utc::now();
+        TimerState {
+            task_uuid: *task.uuid(),
+            started,
+            ends: started + length,
+        }
+    }
+
+    /// The time remaining on the timer as of `now`, or `None` if it has ended.
+    pub fn remaining(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let remaining = self.ends.signed_duration_since(now);
+        if remaining > Duration::zero() {
+            Some(remaining)
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// The longest duration `parse_duration` accepts, a year in seconds. Durations read from files
+/// are checked against it too, since larger numbers overflow `Duration`.
+pub const MAX_DURATION_SECONDS: i64 = 366 * 24 * 60 * 60;
+
+/// Parse a duration like "25m", "90s", or "1h". A number without a unit is in minutes.
+pub fn parse_duration(arg: &str) -> Result<Duration, Error> {
+    let (number, unit) = match arg.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) => arg.split_at(idx),
+        None => (arg, "m"),
+    };
+
+    let n: i64 = number.parse().map_err(|e| format_err!("Unable to parse duration \"{}\": {}", arg, e))?;
+    let unit_seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        _ => return Err(format_err!("Unknown unit in duration \"{}\": expected s, m, or h", arg)),
+    };
+
+    if n == 0 {
+        return Err(format_err!("Duration must be greater than 0."));
+    }
+    match n.checked_mul(unit_seconds) {
+        Some(seconds) if seconds <= MAX_DURATION_SECONDS => Ok(Duration::seconds(seconds)),
+        _ => Err(format_err!("Duration \"{}\" is too long: the longest is {}h.", arg, MAX_DURATION_SECONDS / 3600)),
+    }
+}
+
+/// A duration of `seconds`, if it is greater than 0 and at most `MAX_DURATION_SECONDS`. This is
+/// for durations read from files, like estimates.
+pub fn duration_from_seconds(seconds: i64) -> Option<Duration> {
+    if seconds > 0 && seconds <= MAX_DURATION_SECONDS {
+        Some(Duration::seconds(seconds))
+    }
+    else {
+        None
+    }
+}
+
+/// Format a duration as e.g. `1h 5m 30s`, omitting leading zero units.
+pub fn format_duration(duration: Duration) -> String {
+    let total = duration.num_seconds();
+    let (hours, minutes, seconds) = (total / 3600, (total % 3600) / 60, total % 60);
+
+    if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    }
+    else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    }
+    else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Run the user's notification command with `sh -c`, passing the task description and category in
+/// the `TKZR_TASK` and `TKZR_CATEGORY` environment variables. It is an error if the command cannot
+/// be run or exits unsuccessfully.
+pub fn run_notify_command(command: &str, task: &Task) -> Result<(), Error> {
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("TKZR_TASK", task.task())
+        .env("TKZR_CATEGORY", task.category_str())
+        .status()
+        .map_err(|e| format_err!("Could not run notification command \"{}\": {}", command, e))?;
+
+    if !status.success() {
+        return Err(format_err!("Notification command \"{}\" failed: {}", command, status));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::task::Category;
+
+/// A period of time spent on a task while it was the current task. Sessions keep a copy of the
+/// task's description and category so that they can be reported on after the task is completed and
+/// removed from the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    /// The UUID of the task.
+    pub task_uuid: Uuid,
+    /// The task's description.
+    pub task: String,
+    /// The task's category.
+    pub category: Category,
+    /// When the session started.
+    pub started: DateTime<Utc>,
+    /// When the session stopped, or `None` if it is still running.
+    pub stopped: Option<DateTime<Utc>>,
+}
+
+impl Session {
+    /// The length of the part of the session between `from` and `to`. A running session is treated
+    /// as if it stopped at `now`.
+    pub fn duration_within(&self, from: DateTime<Utc>, to: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+        let start = std::cmp::max(self.started, from);
+        let stop = std::cmp::min(self.stopped.unwrap_or(now), to);
+
+        std::cmp::max(stop.signed_duration_since(start), Duration::zero())
+    }
+}
+
+/// Total time spent per task, per tag and per category over a range of time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeReport {
+    /// Time per category, longest first. Categories with no time are omitted.
+    pub categories: Vec<(Category, Duration)>,
+    /// Time per tag, longest first. Time on a task with several tags counts towards each of them,
+    /// and tags with no time are omitted.
+    pub tags: Vec<(String, Duration)>,
+    /// Time per task description, longest first. Tasks with no time are omitted.
+    pub tasks: Vec<(String, Duration)>,
+    /// Total time across all sessions.
+    pub total: Duration,
+}
+
+impl TimeReport {
+    /// Total up the parts of `sessions` that fall between `from` and `to`, treating running
+    /// sessions as if they stopped at `now`. Sessions for the same task are combined by UUID, so
+    /// separate tasks with the same description are listed separately. `tags` are the tags of each
+    /// task by UUID; tasks that aren't in it have no tags.
+    pub fn new(sessions: &[Session], tags: &HashMap<Uuid, Vec<String>>, from: DateTime<Utc>, to: DateTime<Utc>, now: DateTime<Utc>)
+        -> TimeReport {
+        let (mut task_time, mut break_time) = (Duration::zero(), Duration::zero());
+        let mut tasks: HashMap<Uuid, (String, Duration)> = HashMap::new();
+        let mut tag_times: HashMap<&str, Duration> = HashMap::new();
+        let mut total = Duration::zero();
+
+        for session in sessions {
+            let duration = session.duration_within(from, to, now);
+            if duration == Duration::zero() {
+                continue;
+            }
+
+            match session.category {
+                Category::Task => task_time = task_time + duration,
+                Category::Break => break_time = break_time + duration,
+            }
+
+            let task_total = tasks.entry(session.task_uuid).or_insert_with(|| (session.task.clone(), Duration::zero()));
+            task_total.1 = task_total.1 + duration;
+
+            for tag in tags.get(&session.task_uuid).into_iter().flatten() {
+                let tag_total = tag_times.entry(tag).or_insert_with(Duration::zero);
+                *tag_total = *tag_total + duration;
+            }
+
+            total = total + duration;
+        }
+
+        let mut categories: Vec<(Category, Duration)> = vec![(Category::Task, task_time), (Category::Break, break_time)];
+        categories.retain(|c| c.1 > Duration::zero());
+        categories.sort_by_key(|c| std::cmp::Reverse(c.1));
+
+        let mut tasks: Vec<(String, Duration)> = tasks.into_values().collect();
+        // break ties by description so the output is stable
+        tasks.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let mut tags: Vec<(String, Duration)> = tag_times.into_iter().map(|(tag, time)| (tag.to_string(), time)).collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        TimeReport { categories, tags, tasks, total }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use uuid::Uuid;
+
+use crate::task::Category;
+
+use super::{Session, TimeReport};
+
+fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.ymd(2019, 6, 1).and_hms(hour, minute, 0)
+}
+
+fn session(uuid: Uuid, task: &str, category: Category, started: DateTime<Utc>, stopped: Option<DateTime<Utc>>) -> Session {
+    Session {
+        task_uuid: uuid,
+        task: task.to_string(),
+        category,
+        started,
+        stopped,
+    }
+}
+
+#[test]
+fn test_session_duration_within() {
+    let s = session(Uuid::new_v4(), "a", Category::Task, at(10, 0), Some(at(11, 0)));
+
+    // entirely inside the range
+    assert_eq!(s.duration_within(at(0, 0), at(23, 0), at(23, 0)), Duration::hours(1));
+    // clipped on both sides
+    assert_eq!(s.duration_within(at(10, 15), at(10, 45), at(23, 0)), Duration::minutes(30));
+    // entirely outside the range
+    assert_eq!(s.duration_within(at(12, 0), at(13, 0), at(23, 0)), Duration::zero());
+}
+
+#[test]
+/// A running session counts up to now.
+fn test_session_duration_running() {
+    let s = session(Uuid::new_v4(), "a", Category::Task, at(10, 0), None);
+
+    assert_eq!(s.duration_within(at(0, 0), at(23, 0), at(10, 20)), Duration::minutes(20));
+}
+
+#[test]
+fn test_time_report_totals() {
+    let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let sessions = vec![
+        session(a, "task a", Category::Task, at(9, 0), Some(at(9, 30))),
+        session(b, "break b", Category::Break, at(9, 30), Some(at(9, 40))),
+        session(a, "task a", Category::Task, at(9, 40), Some(at(10, 0))),
+        session(c, "task c", Category::Task, at(10, 0), Some(at(11, 0))),
+    ];
+
+    let report = TimeReport::new(&sessions, &HashMap::new(), at(0, 0), at(23, 0), at(23, 0));
+
+    assert_eq!(report.total, Duration::minutes(120));
+    assert_eq!(report.categories, vec![
+        (Category::Task, Duration::minutes(110)),
+        (Category::Break, Duration::minutes(10)),
+    ]);
+    assert_eq!(report.tasks, vec![
+        ("task c".to_string(), Duration::minutes(60)),
+        ("task a".to_string(), Duration::minutes(50)),
+        ("break b".to_string(), Duration::minutes(10)),
+    ]);
+    assert!(report.tags.is_empty());
+}
+
+#[test]
+/// Time on a task counts towards each of its tags, and untagged tasks aren't counted towards any.
+fn test_time_report_tags() {
+    let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let sessions = vec![
+        session(a, "task a", Category::Task, at(9, 0), Some(at(9, 30))),
+        session(b, "task b", Category::Task, at(9, 30), Some(at(9, 40))),
+        session(c, "task c", Category::Task, at(10, 0), Some(at(11, 0))),
+    ];
+    let mut tags = HashMap::new();
+    tags.insert(a, vec!["home".to_string(), "phone".to_string()]);
+    tags.insert(b, vec!["phone".to_string()]);
+
+    let report = TimeReport::new(&sessions, &tags, at(0, 0), at(23, 0), at(23, 0));
+
+    assert_eq!(report.total, Duration::minutes(100));
+    assert_eq!(report.tags, vec![
+        ("phone".to_string(), Duration::minutes(40)),
+        ("home".to_string(), Duration::minutes(30)),
+    ]);
+}
+
+#[test]
+fn test_time_report_empty() {
+    let sessions = vec![
+        session(Uuid::new_v4(), "task a", Category::Task, at(9, 0), Some(at(9, 30))),
+    ];
+
+    let report = TimeReport::new(&sessions, &HashMap::new(), at(12, 0), at(23, 0), at(23, 0));
+
+    assert_eq!(report.total, Duration::zero());
+    assert!(report.categories.is_empty());
+    assert!(report.tags.is_empty());
+    assert!(report.tasks.is_empty());
+}
@@ -0,0 +1,279 @@
+use ansi_term::{Colour, Style};
+
+use crate::render::truncate;
+use crate::task::Task;
+
+use super::terminal::Key;
+
+/// Lines drawn above the task list: the current task, its details, the key hints and a blank line,
+/// and the task list header.
+const HEADER_LINES: usize = 6;
+/// Lines drawn below the task list: a blank line, the status line and the help line.
+const FOOTER_LINES: usize = 3;
+
+/// Something the user asked to do to the database.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Complete,
+    Skip,
+    Break,
+    Add(Task),
+    Edit(Task),
+    Delete(Task),
+    Quit,
+}
+
+/// What the user is typing into the status line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Prompt {
+    /// The description of a new task, or of `editing`.
+    Description { editing: Option<Task>, reward: bool },
+    /// The priority of a new task, or of `editing`, after its description has been entered.
+    Priority { editing: Option<Task>, reward: bool, description: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mode {
+    Normal,
+    Input { prompt: Prompt, buffer: String },
+    ConfirmDelete(Task),
+}
+
+/// The state of the terminal UI. Keys are turned into `Action`s here, and the database is only
+/// touched by whoever performs them.
+#[derive(Debug, Clone)]
+pub struct App {
+    pub current: Option<Task>,
+    pub tasks: Vec<Task>,
+    /// The index of the selected task in `tasks`.
+    pub selected: usize,
+    /// The index of the first task shown in the list.
+    pub scroll: usize,
+    pub mode: Mode,
+    /// Shown in the status line until the next action.
+    pub message: Option<String>,
+}
+
+impl App {
+    pub fn new() -> App {
+        App {
+            current: None,
+            tasks: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            mode: Mode::Normal,
+            message: None,
+        }
+    }
+
+    /// Replace the tasks shown after the database changes, keeping the selection in bounds.
+    pub fn refresh(&mut self, current: Option<Task>, tasks: Vec<Task>) {
+        self.current = current;
+        self.tasks = tasks;
+        self.selected = self.selected.min(self.tasks.len().saturating_sub(1));
+    }
+
+    fn selected_task(&self) -> Option<&Task> {
+        self.tasks.get(self.selected)
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let last = self.tasks.len().saturating_sub(1) as isize;
+        self.selected = (self.selected as isize + offset).max(0).min(last) as usize;
+    }
+
+    /// Handle a keypress, returning the action to perform if there is one.
+    pub fn handle_key(&mut self, key: Key) -> Option<Action> {
+        if key == Key::CtrlC {
+            return Some(Action::Quit);
+        }
+
+        match self.mode.clone() {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::Input { prompt, buffer } => self.handle_input_key(key, prompt, buffer),
+            Mode::ConfirmDelete(task) => {
+                self.mode = Mode::Normal;
+                match key {
+                    Key::Char('y') => Some(Action::Delete(task)),
+                    _ => {
+                        self.message = Some("Delete cancelled.".to_string());
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: Key) -> Option<Action> {
+        self.message = None;
+
+        match key {
+            Key::Char('q') | Key::Escape => return Some(Action::Quit),
+            Key::Char('c') => return Some(Action::Complete),
+            Key::Char('s') => return Some(Action::Skip),
+            Key::Char('b') => return Some(Action::Break),
+            Key::Char('j') | Key::Down => self.move_selection(1),
+            Key::Char('k') | Key::Up => self.move_selection(-1),
+            Key::PageDown => self.move_selection(10),
+            Key::PageUp => self.move_selection(-10),
+            Key::Char('g') | Key::Home => self.selected = 0,
+            Key::Char('G') | Key::End => self.move_selection(isize::MAX / 2),
+            Key::Char('a') | Key::Char('A') => {
+                let prompt = Prompt::Description { editing: None, reward: key == Key::Char('A') };
+                self.mode = Mode::Input { prompt, buffer: String::new() };
+            }
+            Key::Char('e') => {
+                if let Some(task) = self.selected_task().cloned() {
+                    let prompt = Prompt::Description { reward: task.is_break(), editing: Some(task.clone()) };
+                    self.mode = Mode::Input { prompt, buffer: task.task().to_string() };
+                }
+            }
+            Key::Char('d') => {
+                if let Some(task) = self.selected_task().cloned() {
+                    self.mode = Mode::ConfirmDelete(task);
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    fn handle_input_key(&mut self, key: Key, prompt: Prompt, mut buffer: String) -> Option<Action> {
+        match key {
+            Key::Escape => {
+                self.mode = Mode::Normal;
+                return None;
+            }
+            Key::Backspace => {
+                buffer.pop();
+            }
+            Key::Char(c) => buffer.push(c),
+            Key::Enter => return self.submit_input(prompt, buffer),
+            _ => {}
+        }
+
+        self.mode = Mode::Input { prompt, buffer };
+        None
+    }
+
+    fn submit_input(&mut self, prompt: Prompt, buffer: String) -> Option<Action> {
+        match prompt {
+            Prompt::Description { editing, reward } => {
+                let description = buffer.trim().to_string();
+                if description.is_empty() {
+                    self.message = Some("Task description cannot be empty.".to_string());
+                    self.mode = Mode::Normal;
+                    return None;
+                }
+
+                let priority = editing.as_ref().map_or(1, Task::priority).to_string();
+                let prompt = Prompt::Priority { editing, reward, description };
+                self.mode = Mode::Input { prompt, buffer: priority };
+                None
+            }
+            Prompt::Priority { editing, reward, description } => {
+                let priority = match buffer.trim().parse::<u32>() {
+                    Ok(p) if p > 0 => p,
+                    _ => {
+                        // stay at the prompt so the user can fix it
+                        self.message = Some("Priority must be a number greater than 0.".to_string());
+                        self.mode = Mode::Input {
+                            prompt: Prompt::Priority { editing, reward, description },
+                            buffer,
+                        };
+                        return None;
+                    }
+                };
+                self.mode = Mode::Normal;
+
+                let action = match editing {
+                    Some(task) => Task::from_parts(description, priority, reward, *task.uuid()).map(Action::Edit),
+                    None => Task::new_from_parts(description, priority, reward).map(Action::Add),
+                };
+                match action {
+                    Ok(action) => Some(action),
+                    Err(e) => {
+                        self.message = Some(e.to_string());
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    /// Scroll the task list so that the selected task is visible in `rows` rows.
+    fn scroll_to_selection(&mut self, rows: usize) {
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        }
+        else if rows > 0 && self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows;
+        }
+    }
+
+    /// Draw the screen as `height` lines of at most `width` columns.
+    pub fn draw(&mut self, width: usize, height: usize) -> Vec<String> {
+        let rows = height.saturating_sub(HEADER_LINES + FOOTER_LINES);
+        self.scroll_to_selection(rows);
+
+        let mut lines = Vec::new();
+        match &self.current {
+            Some(task) => {
+                let style = if task.is_break() { Colour::Green.bold() } else { Style::new().bold() };
+                lines.push(style.paint(truncate(task.task(), width)).to_string());
+                lines.push(format!("{}, priority {}", task.category_str(), task.priority()));
+            }
+            None => {
+                lines.push(Style::new().bold().paint("No tasks.").to_string());
+                lines.push(String::new());
+            }
+        }
+        lines.push(String::new());
+        lines.push(truncate("[c] complete   [s] skip   [b] take a break", width));
+        lines.push(String::new());
+        lines.push(Style::new().dimmed().paint(truncate("  Pri  Task", width)).to_string());
+
+        let priority_width = self.tasks.iter().map(|task| task.priority().to_string().len()).max().unwrap_or(0).max(3);
+        for (i, task) in self.tasks.iter().enumerate().skip(self.scroll).take(rows) {
+            let marker = if self.current.as_ref().map(Task::uuid) == Some(task.uuid()) { "*" } else { " " };
+            let category = if task.is_break() { " (break)" } else { "" };
+            let row = format!("{} {:>width$}  {}{}", marker, task.priority(), task.task(), category, width = priority_width);
+            let row = truncate(&row, width);
+
+            lines.push(if i == self.selected { Style::new().reverse().paint(row).to_string() } else { row });
+        }
+
+        // pad the list so the footer stays at the bottom
+        while lines.len() < height.saturating_sub(FOOTER_LINES) {
+            lines.push(String::new());
+        }
+
+        lines.push(String::new());
+        lines.push(truncate(&self.status_line(), width));
+        lines.push(Style::new().dimmed().paint(truncate(
+            "[a] add  [A] add break  [e] edit  [d] delete  [j/k] move  [q] quit", width)).to_string());
+
+        lines
+    }
+
+    fn status_line(&self) -> String {
+        match &self.mode {
+            Mode::Input { prompt: Prompt::Description { editing: Some(_), .. }, buffer } => format!("Edit description: {}", buffer),
+            Mode::Input { prompt: Prompt::Description { reward: true, .. }, buffer } => format!("New break: {}", buffer),
+            Mode::Input { prompt: Prompt::Description { .. }, buffer } => format!("New task: {}", buffer),
+            Mode::Input { prompt: Prompt::Priority { .. }, buffer } => {
+                let message = self.message.as_ref().map(|m| format!("{} ", m)).unwrap_or_default();
+                format!("{}Priority: {}", message, buffer)
+            }
+            Mode::ConfirmDelete(task) => format!("Delete \"{}\"? [y/n]", task.task()),
+            Mode::Normal => self.message.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl Default for App {
+    fn default() -> App {
+        App::new()
+    }
+}
@@ -0,0 +1,100 @@
+//! A full-screen terminal UI showing the current task with single-key complete, skip and break,
+//! and a scrollable task list. Each action runs in its own transaction, through the same commands
+//! as the CLI.
+
+use failure::Error;
+
+use crate::commands::{Add, Break, CommandOutput, Complete, Edit, Remove, TKZCmd};
+use crate::config::Config;
+use crate::db::DBBackend;
+use crate::render::terminal::{stdout_is_terminal, terminal_size};
+use crate::task::Task;
+
+mod app;
+mod terminal;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::app::{Action, App, Mode, Prompt};
+pub use self::terminal::{Key, RawMode, RawTerminal, parse_key};
+
+/// Run the terminal UI until the user quits.
+pub fn run(config: &Config) -> Result<(), Error> {
+    if !stdout_is_terminal() {
+        return Err(format_err!("The terminal UI can only be run in a terminal."));
+    }
+
+    let mut app = App::new();
+    let (current, tasks) = load(config)?;
+    app.refresh(current, tasks);
+
+    let mut raw = RawTerminal::enter()?;
+    loop {
+        let (width, height) = terminal_size().unwrap_or((80, 24));
+        raw.draw(&app.draw(width, height))?;
+
+        let action = match app.handle_key(raw.read_key()?) {
+            Some(Action::Quit) => break,
+            Some(action) => action,
+            None => continue,
+        };
+
+        app.message = match perform(config, action) {
+            Ok(message) => message,
+            Err(e) => Some(format!("Error: {}", e)),
+        };
+        let (current, tasks) = load(config)?;
+        app.refresh(current, tasks);
+    }
+
+    Ok(())
+}
+
+/// Fetch the current task and all tasks, selecting a current task if there isn't one.
+pub fn load(config: &Config) -> Result<(Option<Task>, Vec<Task>), Error> {
+    let mut db = config.db()?;
+    let tx = db.transaction()?;
+
+    let mut current = tx.fetch_current_task()
+        .map_err(|e| format_err!("Could not get current task from database. {}", e))?;
+    if current.is_none() {
+        tx.select_current_task(&mut *config.selector())?;
+        current = tx.fetch_current_task()
+            .map_err(|e| format_err!("Could not get current task from database. {}", e))?;
+    }
+    let tasks = tx.fetch_all_tasks()
+        .map_err(|e| format_err!("Could not get tasks from database. {}", e))?;
+    tx.finish()?;
+
+    Ok((current, tasks))
+}
+
+/// Perform an action in its own transaction, returning a message to display.
+pub fn perform(config: &Config, action: Action) -> Result<Option<String>, Error> {
+    let cmd = match action {
+        Action::Complete => TKZCmd::Complete(Complete::default()),
+        Action::Skip => TKZCmd::Skip,
+        Action::Add(task) => TKZCmd::Add(Add { reward: task.is_break(), task: task.task().to_string(), priority: task.priority() }),
+        Action::Break => TKZCmd::Break(Break::default()),
+        Action::Edit(task) => TKZCmd::Edit(Edit {
+            id: task.uuid().to_string(),
+            task: Some(task.task().to_string()),
+            priority: Some(task.priority()),
+        }),
+        Action::Delete(task) => TKZCmd::Remove(Remove { id: task.uuid().to_string() }),
+        Action::Quit => return Ok(None),
+    };
+
+    let output = cmd.dispatch(config)?;
+    Ok(Some(status_message(&output)))
+}
+
+/// Fit command output on the status line.
+fn status_message(output: &CommandOutput) -> String {
+    output.lines().iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
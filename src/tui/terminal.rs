@@ -0,0 +1,144 @@
+use std::io::{self, Read, Write};
+
+use failure::Error;
+
+/// A key pressed by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Escape,
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    CtrlC,
+    CtrlD,
+    /// A key or escape sequence we don't handle.
+    Unknown,
+}
+
+/// Parse the bytes read from the terminal after a single keypress. Special keys are sent as escape
+/// sequences, which arrive together in one read.
+pub fn parse_key(bytes: &[u8]) -> Key {
+    match bytes {
+        [] => Key::Unknown,
+        [b'\r'] | [b'\n'] => Key::Enter,
+        [b'\t'] => Key::Tab,
+        [0x7f] | [0x08] => Key::Backspace,
+        [0x03] => Key::CtrlC,
+        [0x04] => Key::CtrlD,
+        [0x1b] => Key::Escape,
+        [0x1b, b'[', b'A'] | [0x1b, b'O', b'A'] => Key::Up,
+        [0x1b, b'[', b'B'] | [0x1b, b'O', b'B'] => Key::Down,
+        [0x1b, b'[', b'C'] | [0x1b, b'O', b'C'] => Key::Right,
+        [0x1b, b'[', b'D'] | [0x1b, b'O', b'D'] => Key::Left,
+        [0x1b, b'[', b'3', b'~'] => Key::Delete,
+        [0x1b, b'[', b'H'] | [0x1b, b'O', b'H'] | [0x1b, b'[', b'1', b'~'] => Key::Home,
+        [0x1b, b'[', b'F'] | [0x1b, b'O', b'F'] | [0x1b, b'[', b'4', b'~'] => Key::End,
+        [0x1b, b'[', b'5', b'~'] => Key::PageUp,
+        [0x1b, b'[', b'6', b'~'] => Key::PageDown,
+        [0x1b, ..] => Key::Unknown,
+        _ => match std::str::from_utf8(bytes).ok().and_then(|s| s.chars().next()) {
+            Some(c) if !c.is_control() => Key::Char(c),
+            _ => Key::Unknown,
+        },
+    }
+}
+
+/// Puts the terminal in raw mode while it is alive, so that keys are read as they are pressed
+/// without being echoed, and restores the terminal when it is dropped.
+pub struct RawMode {
+    #[cfg(unix)]
+    original: libc::termios,
+}
+
+impl RawMode {
+    #[cfg(unix)]
+    pub fn enable() -> Result<RawMode, Error> {
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        // safe because tcgetattr only writes to the termios we pass it
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return Err(format_err!("Could not get terminal attributes: {}", io::Error::last_os_error()));
+        }
+
+        let mut raw = original;
+        unsafe { libc::cfmakeraw(&mut raw) };
+        // block until at least one byte is available
+        raw.c_cc[libc::VMIN] = 1;
+        raw.c_cc[libc::VTIME] = 0;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return Err(format_err!("Could not put terminal in raw mode: {}", io::Error::last_os_error()));
+        }
+
+        Ok(RawMode { original })
+    }
+
+    #[cfg(not(unix))]
+    pub fn enable() -> Result<RawMode, Error> {
+        Err(format_err!("Raw terminal input is only supported on unix."))
+    }
+
+    /// Wait for the user to press a key.
+    pub fn read_key(&mut self) -> Result<Key, Error> {
+        let mut buf = [0u8; 16];
+        let n = io::stdin().read(&mut buf)?;
+        Ok(parse_key(&buf[..n]))
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original) };
+    }
+}
+
+/// Puts the terminal in raw mode and switches to the alternate screen while it is alive, and
+/// restores the terminal when it is dropped.
+pub struct RawTerminal {
+    raw: RawMode,
+}
+
+impl RawTerminal {
+    pub fn enter() -> Result<RawTerminal, Error> {
+        let terminal = RawTerminal { raw: RawMode::enable()? };
+        // alternate screen, hide cursor
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush()?;
+
+        Ok(terminal)
+    }
+
+    /// Clear the screen and draw `lines` from the top.
+    pub fn draw(&mut self, lines: &[String]) -> Result<(), Error> {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+
+        // raw mode doesn't translate \n into \r\n
+        write!(out, "\x1b[H\x1b[2J{}", lines.join("\r\n"))?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Wait for the user to press a key.
+    pub fn read_key(&mut self) -> Result<Key, Error> {
+        self.raw.read_key()
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        // show cursor, leave alternate screen. raw mode is disabled after this when `raw` is
+        // dropped.
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+    }
+}
@@ -0,0 +1,198 @@
+use crate::config::Config;
+use crate::task::Task;
+use crate::task::test_utils::{example_task_1, example_task_2, example_task_break_1};
+
+use super::{Action, App, Key, Mode, parse_key, load, perform};
+
+fn type_str(app: &mut App, s: &str) {
+    for c in s.chars() {
+        assert_eq!(app.handle_key(Key::Char(c)), None);
+    }
+}
+
+fn example_app() -> App {
+    let mut app = App::new();
+    app.refresh(Some(example_task_2()), vec![example_task_1(), example_task_2(), example_task_break_1()]);
+    app
+}
+
+#[test]
+fn test_tui_parse_key() {
+    assert_eq!(parse_key(b"c"), Key::Char('c'));
+    assert_eq!(parse_key("é".as_bytes()), Key::Char('é'));
+    assert_eq!(parse_key(b"\r"), Key::Enter);
+    assert_eq!(parse_key(&[0x7f]), Key::Backspace);
+    assert_eq!(parse_key(&[0x03]), Key::CtrlC);
+    assert_eq!(parse_key(&[0x1b]), Key::Escape);
+    assert_eq!(parse_key(b"\x1b[A"), Key::Up);
+    assert_eq!(parse_key(b"\x1b[B"), Key::Down);
+    assert_eq!(parse_key(b"\x1b[6~"), Key::PageDown);
+    assert_eq!(parse_key(b"\x1b[99~"), Key::Unknown);
+}
+
+#[test]
+fn test_tui_single_key_actions() {
+    let mut app = example_app();
+
+    assert_eq!(app.handle_key(Key::Char('c')), Some(Action::Complete));
+    assert_eq!(app.handle_key(Key::Char('s')), Some(Action::Skip));
+    assert_eq!(app.handle_key(Key::Char('b')), Some(Action::Break));
+    assert_eq!(app.handle_key(Key::Char('q')), Some(Action::Quit));
+    assert_eq!(app.handle_key(Key::CtrlC), Some(Action::Quit));
+}
+
+#[test]
+/// The selection moves with the arrow keys and stays within the list.
+fn test_tui_move_selection() {
+    let mut app = example_app();
+
+    app.handle_key(Key::Up);
+    assert_eq!(app.selected, 0);
+    app.handle_key(Key::Down);
+    app.handle_key(Key::Char('j'));
+    app.handle_key(Key::Char('j'));
+    assert_eq!(app.selected, 2);
+    app.handle_key(Key::Char('k'));
+    assert_eq!(app.selected, 1);
+    app.handle_key(Key::Char('G'));
+    assert_eq!(app.selected, 2);
+    app.handle_key(Key::Home);
+    assert_eq!(app.selected, 0);
+
+    // removing tasks keeps the selection in bounds
+    app.handle_key(Key::End);
+    app.refresh(None, vec![example_task_1()]);
+    assert_eq!(app.selected, 0);
+}
+
+#[test]
+fn test_tui_add() {
+    let mut app = example_app();
+
+    assert_eq!(app.handle_key(Key::Char('A')), None);
+    type_str(&mut app, "walkk");
+    app.handle_key(Key::Backspace);
+    assert_eq!(app.handle_key(Key::Enter), None);
+
+    // the priority defaults to 1
+    app.handle_key(Key::Backspace);
+    type_str(&mut app, "5");
+    let action = app.handle_key(Key::Enter);
+
+    match action {
+        Some(Action::Add(task)) => {
+            assert_eq!(task.task(), "walk");
+            assert_eq!(task.priority(), 5);
+            assert!(task.is_break());
+        }
+        other => panic!("Expected add action, got {:?}", other),
+    }
+    assert_eq!(app.mode, Mode::Normal);
+}
+
+#[test]
+/// An invalid priority stays at the prompt, and escape cancels it.
+fn test_tui_add_invalid_priority() {
+    let mut app = example_app();
+
+    app.handle_key(Key::Char('a'));
+    type_str(&mut app, "task");
+    app.handle_key(Key::Enter);
+    app.handle_key(Key::Backspace);
+    type_str(&mut app, "x");
+
+    assert_eq!(app.handle_key(Key::Enter), None);
+    assert!(app.message.is_some());
+    assert!(matches!(app.mode, Mode::Input { .. }));
+
+    assert_eq!(app.handle_key(Key::Escape), None);
+    assert_eq!(app.mode, Mode::Normal);
+}
+
+#[test]
+/// Editing starts with the task's description and priority and keeps its UUID.
+fn test_tui_edit() {
+    let mut app = example_app();
+
+    app.handle_key(Key::Down);
+    app.handle_key(Key::Char('e'));
+    type_str(&mut app, "!");
+    app.handle_key(Key::Enter);
+    let action = app.handle_key(Key::Enter);
+
+    let task = example_task_2();
+    let expected = Task::from_parts(format!("{}!", task.task()), task.priority(), false, *task.uuid()).unwrap();
+    assert_eq!(action, Some(Action::Edit(expected)));
+}
+
+#[test]
+fn test_tui_delete_confirm() {
+    let mut app = example_app();
+
+    app.handle_key(Key::Char('d'));
+    assert_eq!(app.handle_key(Key::Char('n')), None);
+    assert_eq!(app.mode, Mode::Normal);
+
+    app.handle_key(Key::Char('d'));
+    assert_eq!(app.handle_key(Key::Char('y')), Some(Action::Delete(example_task_1())));
+}
+
+#[test]
+/// The screen fills the terminal, shows the current task first and scrolls to the selected task.
+fn test_tui_draw() {
+    let mut app = example_app();
+
+    let lines = app.draw(80, 24);
+    assert_eq!(lines.len(), 24);
+    assert!(lines[0].contains(example_task_2().task()));
+
+    // room for one task in the list
+    app.handle_key(Key::End);
+    let lines = app.draw(80, 10);
+    assert_eq!(app.scroll, 2);
+    // the current task and key hints are drawn above the list
+    assert!(lines[6].contains(example_task_break_1().task()));
+    assert_eq!(lines[7], "");
+}
+
+#[test]
+/// Actions performed through the terminal UI change the database.
+fn test_tui_perform() {
+    let (_dir, config) = Config::test_config();
+
+    let task = example_task_1();
+    perform(&config, Action::Add(task.clone())).expect("Adding task failed");
+    let (current, tasks) = load(&config).expect("Loading tasks failed");
+    assert_eq!(current.as_ref().map(Task::task), Some(task.task()));
+    assert_eq!(tasks.len(), 1);
+
+    let added = tasks[0].clone();
+    let edited = Task::from_parts("edited".to_string(), 3, false, *added.uuid()).unwrap();
+    perform(&config, Action::Edit(edited.clone())).expect("Editing task failed");
+    let (current, tasks) = load(&config).expect("Loading tasks failed");
+    assert_eq!(current, Some(edited.clone()));
+    assert_eq!(tasks, vec![edited.clone()]);
+
+    let message = perform(&config, Action::Complete).expect("Completing task failed");
+    assert_eq!(message, Some("Task \"edited\" completed.".to_string()));
+    let (current, tasks) = load(&config).expect("Loading tasks failed");
+    assert_eq!(current, None);
+    assert!(tasks.is_empty());
+
+    // the task no longer exists
+    assert!(perform(&config, Action::Edit(edited)).is_err());
+}
+
+#[test]
+fn test_tui_perform_delete() {
+    let (_dir, config) = Config::test_config();
+
+    perform(&config, Action::Add(example_task_1())).expect("Adding task failed");
+    perform(&config, Action::Add(example_task_2())).expect("Adding task failed");
+    let (_, tasks) = load(&config).expect("Loading tasks failed");
+
+    perform(&config, Action::Delete(tasks[0].clone())).expect("Deleting task failed");
+    let (current, remaining) = load(&config).expect("Loading tasks failed");
+    assert_eq!(remaining, vec![tasks[1].clone()]);
+    assert_eq!(current, Some(tasks[1].clone()));
+}
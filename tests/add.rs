@@ -3,6 +3,7 @@ use taskerizer_prototype as tkzr;
 mod test_utils;
 
 use self::tkzr::commands::{TKZArgs, TKZCmd, Add};
+use self::tkzr::render::{ColorChoice, OutputFormat};
 
 // TODO some task example data and test code here is shared with the inner unit tests, maybe find a way to dedup
 
@@ -13,6 +14,8 @@ fn test_cmd_add_empty_task() {
     // -- do add command with empty task
     let task = String::new();
     let args = TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
         cmd: Some(TKZCmd::Add( Add {
             reward: false,
             priority: 1,
@@ -34,6 +37,8 @@ fn test_cmd_add_priority_0() {
     // -- do add command with priority 0
     let task = "test".to_string();
     let args = TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
         cmd: Some(TKZCmd::Add( Add {
             reward: false,
             priority: 0,
@@ -60,7 +65,7 @@ fn test_cmd_add() {
     assert!(res.is_ok(), "Add command failed: {}", res.unwrap_err());
 
     // -- check output has our task
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
     let expected = vec![
         format!("Task \"{}\" added to task list.", "hello this is a task"),
     ];
@@ -79,7 +84,7 @@ fn test_cmd_add_two() {
     assert!(res.is_ok(), "Add command failed: {}", res.unwrap_err());
 
     // -- check output has our first task
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
     let expected = vec![
         format!("Task \"{}\" added to task list.", "hello this is a task"),
     ];
@@ -94,7 +99,7 @@ fn test_cmd_add_two() {
     assert!(res.is_ok(), "Add command failed: {}", res.unwrap_err());
 
     // -- check output has our second task
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
     let expected = vec![
         format!("Task \"{}\" added to task list.", "yo this is a break"),
     ];
@@ -0,0 +1,59 @@
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::TKZArgs;
+use taskerizer_prototype::config::Config;
+
+mod test_utils;
+
+fn run(cfg: &Config, argv: &[&str]) -> Result<Vec<String>, String> {
+    let args = TKZArgs::from_iter_safe(argv).expect("Parsing args failed");
+    args.cmd().dispatch(cfg).map(|output| output.lines()).map_err(|e| e.to_string())
+}
+
+fn list(cfg: &Config) -> Vec<String> {
+    test_utils::example_list().cmd().dispatch(cfg).expect("Listing tasks failed").lines()
+}
+
+#[test]
+/// A backup can be restored over a database that changed after it was made, and can't overwrite an
+/// existing file.
+fn test_cmd_backup_restore() {
+    let (dir, cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "add", "first task", "2"]).unwrap();
+
+    let path = dir.path().join("backup.db");
+    let path = path.to_str().unwrap();
+    let output = run(&cfg, &["tkzr", "backup", path]).unwrap();
+    assert_eq!(output, vec![format!("Backed up database to {}.", path)]);
+
+    let err = run(&cfg, &["tkzr", "backup", path]).unwrap_err();
+    assert!(err.contains("already exists"), "{}", err);
+
+    run(&cfg, &["tkzr", "add", "second task", "3"]).unwrap();
+
+    let output = run(&cfg, &["tkzr", "restore", path]).unwrap();
+    assert_eq!(output.len(), 1);
+    assert!(output[0].starts_with(&format!("Restored database from {}, created ", path)), "{:?}", output);
+
+    assert_eq!(list(&cfg), vec![
+        "Priority \t Task".to_string(),
+        "   2 \t first task".to_string(),
+    ]);
+}
+
+#[test]
+/// Restoring a file that isn't a backup fails and leaves the database unchanged.
+fn test_cmd_restore_invalid() {
+    let (dir, cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "add", "first task", "2"]).unwrap();
+
+    let path = dir.path().join("notes.txt");
+    std::fs::write(&path, "these are some notes that are definitely not a sqlite database").unwrap();
+    let err = run(&cfg, &["tkzr", "restore", path.to_str().unwrap()]).unwrap_err();
+    assert!(err.contains("not a taskerizer database"), "{}", err);
+
+    assert_eq!(list(&cfg), vec![
+        "Priority \t Task".to_string(),
+        "   2 \t first task".to_string(),
+    ]);
+}
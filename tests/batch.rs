@@ -0,0 +1,95 @@
+mod test_utils;
+
+use std::fs;
+
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::{TKZArgs, CommandOutput};
+
+fn run_batch(cfg: &taskerizer_prototype::config::Config, commands: &str, continue_on_error: bool)
+    -> Result<CommandOutput, failure::Error> {
+    let path = cfg.db_path.join("commands.txt");
+    fs::write(&path, commands).expect("Failed to write commands");
+
+    let path = path.to_str().unwrap();
+    let argv = if continue_on_error {
+        vec!["tkzr", "batch", "--continue-on-error", path]
+    }
+    else {
+        vec!["tkzr", "batch", path]
+    };
+    let args = TKZArgs::from_iter_safe(&argv).expect("Parsing args failed");
+    args.cmd().dispatch(cfg)
+}
+
+fn list(cfg: &taskerizer_prototype::config::Config) -> Vec<String> {
+    test_utils::example_list().cmd().dispatch(cfg).expect("Listing tasks failed").lines()
+}
+
+#[test]
+fn test_cmd_batch() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let commands = "\
+# comments and blank lines are skipped
+
+add 'first task' 2
+[\"add\", \"--break\", \"a break\", \"3\"]
+simulate -n 10
+";
+    let output = run_batch(&cfg, commands, false).expect("Batch failed").lines();
+    assert_eq!(output[0], "Task \"first task\" added to task list.");
+    assert_eq!(output[1], "Task \"a break\" added to task list.");
+    assert_eq!(output[2], "Simulated 10 selections.");
+
+    assert_eq!(list(&cfg), vec![
+        "Priority \t Task".to_string(),
+        "   2 \t first task".to_string(),
+        "   3 \t a break".to_string(),
+    ]);
+}
+
+#[test]
+/// An error rolls back every command in the batch.
+fn test_cmd_batch_rollback() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let commands = "add 'first task'\nadd 'bad priority' 0\nadd 'third task'\n";
+    let res = run_batch(&cfg, commands, false);
+
+    let err = res.expect_err("Batch with an invalid command succeeded");
+    assert!(err.to_string().starts_with("Error on line 2 (add 'bad priority' 0)"), "Incorrect error: {}", err);
+    assert_eq!(list(&cfg), vec!["Priority \t Task".to_string()]);
+}
+
+#[test]
+fn test_cmd_batch_continue_on_error() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let commands = "add 'first task'\nadd 'bad priority' 0\nshell\nadd 'third task'\n";
+    let output = run_batch(&cfg, commands, true).expect("Batch failed");
+
+    match &output {
+        CommandOutput::Sequence(outputs) => {
+            assert_eq!(outputs.len(), 4);
+            match &outputs[1] {
+                CommandOutput::Failed { line, command, .. } => {
+                    assert_eq!(*line, 2);
+                    assert_eq!(command, "add 'bad priority' 0");
+                }
+                other => panic!("Expected failure, got {:?}", other),
+            }
+            match &outputs[2] {
+                CommandOutput::Failed { error, .. } => assert_eq!(error, "This command can't be run in a batch."),
+                other => panic!("Expected failure, got {:?}", other),
+            }
+        }
+        other => panic!("Expected sequence, got {:?}", other),
+    }
+
+    assert_eq!(list(&cfg), vec![
+        "Priority \t Task".to_string(),
+        "   1 \t first task".to_string(),
+        "   1 \t third task".to_string(),
+    ]);
+}
@@ -1,5 +1,7 @@
 mod test_utils;
 
+use taskerizer_prototype::config::StrategyKind;
+
 // -- TODO test top parameter
 
 #[test]
@@ -15,7 +17,7 @@ fn test_cmd_complete_empty() {
     assert!(res.is_ok(), "Completing task failed: {}", res.unwrap_err());
 
     // -- check output says no task was found
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     let expected = vec!["No tasks."];
 
@@ -39,7 +41,7 @@ fn test_cmd_complete_1() {
     assert!(res.is_ok(), "Completing task failed: {}", res.unwrap_err());
 
     // -- check output is the task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     // TODO implement to_string (or format_*, see todo.txt) for Task and fix it here
     let expected = vec![
@@ -66,7 +68,7 @@ fn test_cmd_complete_2() {
     assert!(res.is_ok(), "Completing task failed: {}", res.unwrap_err());
 
     // -- check output is the task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     // TODO implement to_string (or format_*, see todo.txt) for Task and fix it here
     let expected = vec![
@@ -96,7 +98,7 @@ fn test_cmd_complete_3() {
     assert!(res.is_ok(), "Completing task failed: {}", res.unwrap_err());
 
     // -- check output contains the first task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     let expected = vec![
         format!("Task \"{}\" completed.\n", "yo this is a break"),
@@ -122,7 +124,7 @@ fn test_cmd_complete_4() {
     assert!(res.is_ok(), "Completing task failed: {}", res.unwrap_err());
 
     // -- check output contains the first task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     let expected = vec![
         format!("Task \"{}\" completed.\n", "yo this is a break"),
@@ -139,7 +141,7 @@ fn test_cmd_complete_4() {
     let res = args.cmd().dispatch(&cfg);
 
     // -- check output contains the first task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     let expected = vec![
         format!("Task \"{}\" completed.\n", "hello this is a task"),
@@ -149,3 +151,51 @@ fn test_cmd_complete_4() {
 
 
 }
+
+#[test]
+/// With the pomodoro strategy set to force a break after every task, completing a task always
+/// selects a break next.
+fn test_cmd_complete_pomodoro_break() {
+    let (_dir, mut cfg) = test_utils::temp_config();
+    cfg.break_cutoff = 0.0;
+    cfg.selection.strategy = StrategyKind::Pomodoro;
+    cfg.selection.pomodoro_tasks_per_break = 1;
+    cfg.selection.pomodoro_work_minutes = 0;
+
+    // -- add a task first so it is the current task, then a break and another task
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+    let args = test_utils::example_add_cmd_break1();
+    args.cmd().dispatch(&cfg).expect("Adding break failed");
+    let args = test_utils::example_add_cmd_task2();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    // -- complete the task
+    let args = test_utils::example_complete();
+    args.cmd().dispatch(&cfg).expect("Completing task failed");
+
+    // -- check the break was selected
+    let args = test_utils::example_current();
+    let output = args.cmd().dispatch(&cfg).expect("Getting current task failed").lines();
+
+    let expected = vec![
+        format!("{}\n", "yo this is a break"),
+        "Category: Break".to_string(),
+        "Priority: 2".to_string(),
+    ];
+    assert_eq!(output, expected);
+
+    // -- complete the break, and the remaining task is selected since the break probability is 0
+    let args = test_utils::example_complete();
+    args.cmd().dispatch(&cfg).expect("Completing break failed");
+
+    let args = test_utils::example_current();
+    let output = args.cmd().dispatch(&cfg).expect("Getting current task failed").lines();
+
+    let expected = vec![
+        format!("{}\n", "hello this is also a task"),
+        "Category: Task".to_string(),
+        "Priority: 9".to_string(),
+    ];
+    assert_eq!(output, expected);
+}
@@ -15,7 +15,7 @@ fn test_cmd_current_empty() {
     assert!(res.is_ok(), "Getting current task failed: {}", res.unwrap_err());
 
     // -- check output says no task was found
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     let expected = vec!["No tasks."];
 
@@ -39,7 +39,7 @@ fn test_cmd_current_1() {
     assert!(res.is_ok(), "Getting current task failed: {}", res.unwrap_err());
 
     // -- check output is the task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     // TODO implement to_string (or format_*, see todo.txt) for Task and fix it here
     let expected = vec![
@@ -68,7 +68,7 @@ fn test_cmd_current_2() {
     assert!(res.is_ok(), "Getting current task failed: {}", res.unwrap_err());
 
     // -- check output is the task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     // TODO implement to_string (or format_*, see todo.txt) for Task and fix it here
     let expected = vec![
@@ -97,7 +97,7 @@ fn test_cmd_current_3_interspersed() {
     assert!(res.is_ok(), "Getting current task failed: {}", res.unwrap_err());
 
     // -- check output is the task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     // TODO implement to_string (or format_*, see todo.txt) for Task and fix it here
     let expected = vec![
@@ -125,7 +125,7 @@ fn test_cmd_current_3_interspersed() {
     assert!(res.is_ok(), "Getting current task failed: {}", res.unwrap_err());
 
     // -- check output continues to be the first task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     let expected = vec![
         format!("{}\n", "yo this is a break"),
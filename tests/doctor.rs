@@ -0,0 +1,38 @@
+use rusqlite::{Connection, NO_PARAMS};
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::TKZArgs;
+use taskerizer_prototype::config::Config;
+
+mod test_utils;
+
+fn run(cfg: &Config, argv: &[&str]) -> Vec<String> {
+    let args = TKZArgs::from_iter_safe(argv).expect("Parsing args failed");
+    args.cmd().dispatch(cfg).expect("Command failed").lines()
+}
+
+#[test]
+/// `doctor` reports a missing current task without changing the database, and `doctor --fix`
+/// selects one.
+fn test_cmd_doctor_fix() {
+    let (dir, cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "add", "first task", "2"]);
+    assert_eq!(run(&cfg, &["tkzr", "doctor"]), vec!["No problems found.".to_string()]);
+
+    Connection::open(dir.path().join("tkzr_sqlite3.db")).unwrap()
+        .execute("DELETE FROM current", NO_PARAMS).unwrap();
+
+    let expected = vec![
+        "Found 1 problems, 1 can be fixed with `doctor --fix`:".to_string(),
+        "  there are 1 tasks but no current task (fixable)".to_string(),
+    ];
+    assert_eq!(run(&cfg, &["tkzr", "doctor"]), expected);
+    assert_eq!(run(&cfg, &["tkzr", "doctor"]), expected);
+
+    assert_eq!(run(&cfg, &["tkzr", "doctor", "--fix"]), vec![
+        "Found 1 problems and fixed 1:".to_string(),
+        "  there are 1 tasks but no current task (fixed)".to_string(),
+    ]);
+    assert_eq!(run(&cfg, &["tkzr", "doctor"]), vec!["No problems found.".to_string()]);
+    assert_eq!(run(&cfg, &["tkzr", "current"])[0], "first task\n");
+}
@@ -0,0 +1,84 @@
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::TKZArgs;
+use taskerizer_prototype::config::Config;
+
+mod test_utils;
+
+fn run(cfg: &Config, argv: &[&str]) -> Vec<String> {
+    let args = TKZArgs::from_iter_safe(argv).expect("Parsing args failed");
+    args.cmd().dispatch(cfg).expect("Command failed").lines()
+}
+
+/// The UUID of the only task on the task list.
+fn only_task_id(cfg: &Config) -> String {
+    let export = run(cfg, &["tkzr", "export"]).join("\n");
+    let export: serde_json::Value = serde_json::from_str(&export).expect("Export was not JSON");
+    export["tasks"][0]["uuid"].as_str().expect("Exported task has no uuid").to_string()
+}
+
+#[test]
+/// Editing a task changes its description and priority, and keeps its details.
+fn test_cmd_edit() {
+    let (_dir, cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "q", "call dentist +phone est:10m"]);
+    let id = only_task_id(&cfg);
+
+    let output = run(&cfg, &["tkzr", "edit", &id[..8], "--task", "call the dentist", "--priority", "3"]);
+    assert_eq!(output, vec!["Task \"call the dentist\" updated.".to_string()]);
+
+    let output = run(&cfg, &["tkzr", "current"]);
+    assert_eq!(output[0], "call the dentist\n");
+    assert_eq!(output[2], "Priority: 3");
+
+    let export = run(&cfg, &["tkzr", "export"]).join("\n");
+    assert!(export.contains("\"phone\""), "Editing lost the tags: {}", export);
+    assert!(export.contains("\"estimate_seconds\": 600"), "Editing lost the estimate: {}", export);
+
+    // the priority isn't changed unless it's given
+    run(&cfg, &["tkzr", "edit", &id, "--task", "dentist"]);
+    assert_eq!(run(&cfg, &["tkzr", "current"])[2], "Priority: 3");
+}
+
+#[test]
+fn test_cmd_edit_unknown_id() {
+    let (_dir, cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "add", "only task"]);
+
+    let args = TKZArgs::from_iter_safe(&["tkzr", "edit", "nonexistent", "--task", "other"]).unwrap();
+    let err = args.cmd().dispatch(&cfg).expect_err("Editing a nonexistent task succeeded");
+    assert!(err.to_string().contains("no task on the task list"), "Incorrect error: {}", err);
+}
+
+#[test]
+/// Removing the current task moves it to the trash without completing it, and selects another.
+fn test_cmd_remove() {
+    let (_dir, cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "add", "only task"]);
+    let id = only_task_id(&cfg);
+
+    let output = run(&cfg, &["tkzr", "remove", &id[..8]]);
+    assert_eq!(output, vec!["Task \"only task\" moved to the trash.".to_string()]);
+    assert_eq!(run(&cfg, &["tkzr", "current"]), vec!["No tasks.".to_string()]);
+
+    let trash = run(&cfg, &["tkzr", "trash", "list"]);
+    assert_eq!(trash.len(), 2);
+    assert!(trash[1].contains("local"), "Removed task wasn't removed locally: {}", trash[1]);
+
+    run(&cfg, &["tkzr", "undo"]);
+    assert_eq!(run(&cfg, &["tkzr", "current"])[0], "only task\n");
+}
+
+#[test]
+/// An empty id doesn't match every task, even when there is only one.
+fn test_cmd_edit_remove_empty_id() {
+    let (_dir, cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "add", "only task"]);
+
+    for argv in &[&["tkzr", "edit", "", "--task", "other"][..], &["tkzr", "remove", " "][..]] {
+        let args = TKZArgs::from_iter_safe(*argv).unwrap();
+        let err = args.cmd().dispatch(&cfg).expect_err("An empty id matched a task");
+        assert!(err.to_string().contains("empty"), "Incorrect error: {}", err);
+    }
+    assert_eq!(run(&cfg, &["tkzr", "current"])[0], "only task\n");
+}
@@ -0,0 +1,196 @@
+use std::fs;
+
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::TKZArgs;
+use taskerizer_prototype::config::Config;
+
+mod test_utils;
+
+fn run(cfg: &Config, argv: &[&str]) -> Vec<String> {
+    let args = TKZArgs::from_iter_safe(argv).expect("Parsing args failed");
+    args.cmd().dispatch(cfg).expect("Command failed").lines()
+}
+
+fn list(cfg: &Config) -> Vec<String> {
+    test_utils::example_list().cmd().dispatch(cfg).expect("Listing tasks failed").lines()
+}
+
+#[test]
+/// Tasks exported from one database can be imported into another, and importing again updates
+/// them instead of adding them twice.
+fn test_cmd_export_import() {
+    let (dir, cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "add", "first task", "2"]);
+    run(&cfg, &["tkzr", "add", "--break", "a break", "3"]);
+
+    let path = dir.path().join("export.json");
+    let path = path.to_str().unwrap();
+    let output = run(&cfg, &["tkzr", "export", path]);
+    assert_eq!(output, vec![format!("Exported 2 tasks to {}.", path)]);
+
+    let json = fs::read_to_string(path).expect("Export was not written");
+    assert!(json.contains("\"version\": 1"), "{}", json);
+
+    let (_other_dir, other_cfg) = test_utils::temp_config();
+    run(&other_cfg, &["tkzr", "add", "already here", "1"]);
+
+    let output = run(&other_cfg, &["tkzr", "import", path]);
+    assert_eq!(output, vec![
+        "Imported 2 tasks (2 added, 0 updated), 0 replicas and 0 unsynced operations.".to_string(),
+    ]);
+    assert_eq!(list(&other_cfg), vec![
+        "Priority \t Task".to_string(),
+        "   1 \t already here".to_string(),
+        "   2 \t first task".to_string(),
+        "   3 \t a break".to_string(),
+    ]);
+
+    let output = run(&other_cfg, &["tkzr", "import", path]);
+    assert_eq!(output, vec![
+        "Imported 2 tasks (0 added, 2 updated), 0 replicas and 0 unsynced operations.".to_string(),
+    ]);
+
+    run(&other_cfg, &["tkzr", "import", "--replace", path]);
+    assert_eq!(list(&other_cfg), list(&cfg));
+}
+
+#[test]
+/// A document with an invalid task is rejected without changing the database.
+fn test_cmd_import_invalid() {
+    let (dir, cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "add", "first task", "2"]);
+
+    let path = dir.path().join("export.json");
+    fs::write(&path, r#"{"version": 1, "tasks": [
+        {"task": "bad task", "priority": 0, "reward": false, "uuid": "00000000-0000-0000-0000-000000000001"}
+    ], "current": null}"#).unwrap();
+
+    let args = TKZArgs::from_iter_safe(&["tkzr", "import", "--replace", path.to_str().unwrap()])
+        .expect("Parsing args failed");
+    let err = args.cmd().dispatch(&cfg).unwrap_err();
+    assert!(err.to_string().starts_with("Invalid task 00000000-0000-0000-0000-000000000001"), "{}", err);
+
+    assert_eq!(list(&cfg), vec![
+        "Priority \t Task".to_string(),
+        "   2 \t first task".to_string(),
+    ]);
+}
+
+#[test]
+/// todo.txt and Markdown files are read and written based on their extension, and keep task ids.
+fn test_cmd_export_import_todotxt() {
+    let (dir, cfg) = test_utils::temp_config();
+    let path = dir.path().join("todo.txt");
+    fs::write(&path, "\
+(A) call mom +family
+x 2019-06-01 already done
+stretch @break
+").unwrap();
+    let path = path.to_str().unwrap();
+
+    let output = run(&cfg, &["tkzr", "import", path]);
+    assert_eq!(output, vec![
+        "Imported 2 tasks (2 added, 0 updated), 0 replicas and 0 unsynced operations.".to_string(),
+    ]);
+    assert_eq!(list(&cfg), vec![
+        "Priority \t Task".to_string(),
+        "  26 \t call mom".to_string(),
+        "   1 \t stretch".to_string(),
+    ]);
+
+    let md_path = dir.path().join("tasks.md");
+    let md_path = md_path.to_str().unwrap();
+    run(&cfg, &["tkzr", "export", md_path]);
+    let markdown = fs::read_to_string(md_path).unwrap();
+    let lines: Vec<_> = markdown.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines.iter().any(|line| line.starts_with("- [ ] (A) call mom +family id:")), "{}", markdown);
+    assert!(lines.iter().any(|line| line.starts_with("- [ ] stretch @break id:")), "{}", markdown);
+
+    // the ids in the export match the tasks, so importing it again only updates them
+    let output = run(&cfg, &["tkzr", "import", md_path]);
+    assert_eq!(output, vec![
+        "Imported 2 tasks (0 added, 2 updated), 0 replicas and 0 unsynced operations.".to_string(),
+    ]);
+
+    let output = run(&cfg, &["tkzr", "export", "--as", "todotxt"]);
+    assert_eq!(output.len(), 2);
+    assert!(output.iter().any(|line| line.starts_with("(A) call mom +family id:")), "{:?}", output);
+}
+
+#[test]
+fn test_cmd_import_dry_run() {
+    let (dir, cfg) = test_utils::temp_config();
+    let path = dir.path().join("list.md");
+    fs::write(&path, "# Today\n\n- [ ] (A) call mom\n- [x] already done\n- [ ] water plants\n").unwrap();
+
+    let output = run(&cfg, &["tkzr", "import", "--dry-run", path.to_str().unwrap()]);
+    assert_eq!(output, vec![
+        "Dry run: 2 tasks would be imported (2 added, 0 updated), 0 replicas and 0 unsynced operations.".to_string(),
+        "Priority \t Task".to_string(),
+        "  26 \t call mom".to_string(),
+        "   1 \t water plants".to_string(),
+    ]);
+    assert_eq!(list(&cfg), vec!["Priority \t Task".to_string()]);
+}
+
+#[test]
+/// `--ical` writes and reads VTODOs, keeping task ids as UIDs.
+fn test_cmd_export_import_ical() {
+    let (dir, cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "add", "first task", "2"]);
+    run(&cfg, &["tkzr", "add", "second task", "3"]);
+
+    let path = dir.path().join("tasks.calendar");
+    let path = path.to_str().unwrap();
+    run(&cfg, &["tkzr", "export", "--ical", path]);
+    let calendar = fs::read_to_string(path).unwrap();
+    assert_eq!(calendar.matches("BEGIN:VTODO").count(), 2);
+    assert!(calendar.contains("SUMMARY:second task\r\nPRIORITY:7\r\n"), "{}", calendar);
+
+    let (_other_dir, other_cfg) = test_utils::temp_config();
+    let output = run(&other_cfg, &["tkzr", "import", "--ical", path]);
+    assert_eq!(output, vec![
+        "Imported 2 tasks (2 added, 0 updated), 0 replicas and 0 unsynced operations.".to_string(),
+    ]);
+    assert_eq!(list(&other_cfg), list(&cfg));
+
+    let output = run(&other_cfg, &["tkzr", "import", "--ical", path]);
+    assert_eq!(output, vec![
+        "Imported 2 tasks (0 added, 2 updated), 0 replicas and 0 unsynced operations.".to_string(),
+    ]);
+
+    assert!(TKZArgs::from_iter_safe(&["tkzr", "export", "--ical", "--as", "json"]).is_err());
+}
+
+#[test]
+/// Taskwarrior exports keep their UUIDs and report the tasks that were skipped.
+fn test_cmd_import_taskwarrior() {
+    let (dir, cfg) = test_utils::temp_config();
+    let path = dir.path().join("taskwarrior.json");
+    fs::write(&path, r#"[
+{"id":1,"description":"call mom","priority":"M","status":"pending","tags":["family"],"urgency":5.8,"uuid":"00000000-0000-0000-0000-000000000001"},
+{"id":2,"description":"water plants","status":"pending","urgency":2.1,"uuid":"00000000-0000-0000-0000-000000000002"},
+{"id":0,"description":"done already","status":"completed","urgency":0,"uuid":"00000000-0000-0000-0000-000000000003"}
+]"#).unwrap();
+    let path = path.to_str().unwrap();
+
+    let output = run(&cfg, &["tkzr", "import", "--taskwarrior", "--weight-from", "urgency", "--scale", "2", path]);
+    assert_eq!(output, vec![
+        "Imported 2 tasks (2 added, 0 updated), 0 replicas and 0 unsynced operations.".to_string(),
+        "Skipped completed task \"done already\".".to_string(),
+    ]);
+    assert_eq!(list(&cfg), vec![
+        "Priority \t Task".to_string(),
+        "   4 \t water plants".to_string(),
+        "  12 \t call mom".to_string(),
+    ]);
+
+    let ids = run(&cfg, &["tkzr", "export", "--as", "todotxt"]);
+    assert!(ids.iter().any(|line| line.ends_with("id:00000000-0000-0000-0000-000000000001")), "{:?}", ids);
+
+    assert!(TKZArgs::from_iter_safe(&["tkzr", "import", "--scale", "2", path]).is_err());
+    assert!(TKZArgs::from_iter_safe(&["tkzr", "import", "--taskwarrior", "--scale", "0", path]).is_err());
+    assert!(TKZArgs::from_iter_safe(&["tkzr", "import", "--taskwarrior", "--ical", path]).is_err());
+}
@@ -0,0 +1,41 @@
+mod test_utils;
+
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::TKZArgs;
+use taskerizer_prototype::render::{ColorChoice, OutputFormat};
+
+#[test]
+fn test_cmd_format_parse() {
+    let args = TKZArgs::from_iter_safe(&["tkzr", "list"]).expect("Parsing args failed");
+    assert_eq!(args.format, OutputFormat::Plain);
+
+    // the format can be given before or after the subcommand
+    let args = TKZArgs::from_iter_safe(&["tkzr", "--format", "json", "list"]).expect("Parsing args failed");
+    assert_eq!(args.format, OutputFormat::Json);
+    let args = TKZArgs::from_iter_safe(&["tkzr", "list", "--format", "tsv"]).expect("Parsing args failed");
+    assert_eq!(args.format, OutputFormat::Tsv);
+    let args = TKZArgs::from_iter_safe(&["tkzr", "list", "--format", "color"]).expect("Parsing args failed");
+    assert_eq!(args.format, OutputFormat::Color);
+
+    assert!(TKZArgs::from_iter_safe(&["tkzr", "--format", "xml"]).is_err());
+}
+
+#[test]
+fn test_cmd_format_json_list() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    let args = test_utils::example_list();
+    let output = args.cmd().dispatch(&cfg).expect("Listing tasks failed");
+    let rendered = OutputFormat::Json.renderer(ColorChoice::Never).render(&output);
+    assert_eq!(rendered.len(), 1);
+
+    let value: serde_json::Value = serde_json::from_str(&rendered[0]).expect("Output was not valid json");
+    assert_eq!(value["type"], "task_list");
+    assert_eq!(value["tasks"][0]["task"], "hello this is a task");
+    assert_eq!(value["tasks"][0]["priority"], 1);
+    assert_eq!(value["tasks"][0]["category"], "task");
+}
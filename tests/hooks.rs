@@ -0,0 +1,149 @@
+mod test_utils;
+
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::TKZArgs;
+use taskerizer_prototype::config::Config;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+/// Write an executable shell script named `name` into `dir`.
+fn write_hook(dir: &Path, name: &str, script: &str) {
+    fs::create_dir_all(dir).expect("Failed to create hooks dir");
+    let path = dir.join(name);
+    fs::write(&path, format!("#!/bin/sh\n{}\n", script)).expect("Failed to write hook");
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).expect("Failed to make hook executable");
+}
+
+#[test]
+/// Hook output is appended to the command output.
+fn test_cmd_hooks_output() {
+    let (dir, mut cfg) = test_utils::temp_config();
+    let hooks_dir = dir.path().join("hooks");
+    cfg.hooks_path = Some(hooks_dir.clone());
+
+    write_hook(&hooks_dir, "on-add", "echo added");
+    write_hook(&hooks_dir, "on-select", "echo selected");
+
+    let args = test_utils::example_add_cmd_task1();
+    let output = args.cmd().dispatch(&cfg).expect("Adding task failed").lines();
+
+    assert_eq!(output, vec![
+        format!("Task \"{}\" added to task list.", "hello this is a task"),
+        "added".to_string(),
+        "selected".to_string(),
+    ]);
+}
+
+#[test]
+/// A failing hook aborts the command, so nothing is changed.
+fn test_cmd_hooks_abort() {
+    let (dir, mut cfg) = test_utils::temp_config();
+    let hooks_dir = dir.path().join("hooks");
+    cfg.hooks_path = Some(hooks_dir.clone());
+
+    write_hook(&hooks_dir, "on-add", "exit 1");
+
+    let args = test_utils::example_add_cmd_task1();
+    let res = args.cmd().dispatch(&cfg);
+    assert!(res.is_err(), "Command succeeded with failing hook");
+
+    let args = test_utils::example_current();
+    let output = args.cmd().dispatch(&cfg).expect("Getting current task failed").lines();
+    assert_eq!(output, vec!["No tasks."]);
+}
+
+#[test]
+fn test_cmd_hooks_complete() {
+    let (dir, mut cfg) = test_utils::temp_config();
+    let hooks_dir = dir.path().join("hooks");
+    cfg.hooks_path = Some(hooks_dir.clone());
+
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    write_hook(&hooks_dir, "on-complete", "echo completed");
+
+    let args = test_utils::example_complete();
+    let output = args.cmd().dispatch(&cfg).expect("Completing task failed").lines();
+    assert_eq!(output, vec![
+        format!("Task \"{}\" completed.\n", "hello this is a task"),
+        "completed".to_string(),
+    ]);
+}
+
+fn run(cfg: &Config, argv: &[&str]) -> Vec<String> {
+    let args = TKZArgs::from_iter_safe(argv).expect("Parsing args failed");
+    args.cmd().dispatch(cfg).expect("Command failed").lines()
+}
+
+#[test]
+/// Skipping the only task still runs the skip hook, and selects the same task again.
+fn test_cmd_hooks_skip_one_task() {
+    let (dir, mut cfg) = test_utils::temp_config();
+    let hooks_dir = dir.path().join("hooks");
+    cfg.hooks_path = Some(hooks_dir.clone());
+
+    run(&cfg, &["tkzr", "add", "only task"]);
+
+    write_hook(&hooks_dir, "on-skip", "echo skipped");
+    write_hook(&hooks_dir, "on-select", "echo selected");
+    write_hook(&hooks_dir, "on-complete", "echo completed");
+
+    assert_eq!(run(&cfg, &["tkzr", "skip"]), vec![
+        "Current task is now \"only task\".\n".to_string(),
+        "skipped".to_string(),
+        "selected".to_string(),
+    ]);
+}
+
+#[test]
+/// Removing a task, or undoing the command that added it, doesn't run the complete hook.
+fn test_cmd_hooks_remove_not_complete() {
+    let (dir, mut cfg) = test_utils::temp_config();
+    let hooks_dir = dir.path().join("hooks");
+    cfg.hooks_path = Some(hooks_dir.clone());
+
+    run(&cfg, &["tkzr", "add", "first task"]);
+    run(&cfg, &["tkzr", "add", "second task"]);
+    run(&cfg, &["tkzr", "add", "third task"]);
+
+    write_hook(&hooks_dir, "on-complete", "echo completed");
+    write_hook(&hooks_dir, "on-skip", "echo skipped");
+
+    let output = run(&cfg, &["tkzr", "search", "first", "--pick", "1", "--remove"]);
+    assert!(!output.contains(&"completed".to_string()), "Removing ran the complete hook: {:?}", output);
+    assert!(!output.contains(&"skipped".to_string()), "Removing ran the skip hook: {:?}", output);
+
+    let output = run(&cfg, &["tkzr", "undo", "2"]);
+    assert!(!output.contains(&"completed".to_string()), "Undoing an add ran the complete hook: {:?}", output);
+    assert!(!output.contains(&"skipped".to_string()), "Undoing ran the skip hook: {:?}", output);
+}
+
+#[test]
+/// Importing runs the add hook for the tasks the import added, including ones it replaced with
+/// `--replace`, but not for the ones it only updated.
+fn test_cmd_hooks_import() {
+    let (dir, mut cfg) = test_utils::temp_config();
+    let hooks_dir = dir.path().join("hooks");
+    cfg.hooks_path = Some(hooks_dir.clone());
+
+    write_hook(&hooks_dir, "on-add", "echo added");
+
+    let path = dir.path().join("export.json");
+    fs::write(&path, r#"{"version": 1, "tasks": [
+        {"task": "imported task", "priority": 2, "reward": false, "uuid": "00000000-0000-0000-0000-000000000001"}
+    ], "current": null}"#).unwrap();
+    let path = path.to_str().unwrap();
+
+    let output = run(&cfg, &["tkzr", "import", path]);
+    assert_eq!(output.last().map(String::as_str), Some("added"), "Incorrect output: {:?}", output);
+
+    let output = run(&cfg, &["tkzr", "import", path]);
+    assert!(!output.contains(&"added".to_string()), "Updating a task ran the add hook: {:?}", output);
+
+    let output = run(&cfg, &["tkzr", "import", "--replace", path]);
+    assert_eq!(output.last().map(String::as_str), Some("added"), "Incorrect output: {:?}", output);
+}
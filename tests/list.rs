@@ -1,9 +1,5 @@
-use taskerizer_prototype as tkzr;
-
 mod test_utils;
 
-use self::tkzr::commands::{TKZArgs, TKZCmd};
-
 #[test]
 fn test_cmd_list() {
     let (_dir, cfg) = test_utils::temp_config();
@@ -15,16 +11,14 @@ fn test_cmd_list() {
 
     // -- do list command with same db that we just did add on
 
-    let args = TKZArgs {
-        cmd: Some(TKZCmd::List)
-    };
+    let args = test_utils::example_list();
     let res = args.cmd().dispatch(&cfg);
 
     // -- check success
     assert!(res.is_ok(), "List command failed: {}", res.unwrap_err());
 
     // -- check output has exactly the task we previously added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
     let expected = vec![
         "Priority \t Task".to_string(),
         "   1 \t hello this is a task".to_string(),
@@ -49,16 +43,14 @@ fn test_cmd_list_two() {
 
     // -- do list command with same db that we just did adds to
 
-    let args = TKZArgs {
-        cmd: Some(TKZCmd::List)
-    };
+    let args = test_utils::example_list();
     let res = args.cmd().dispatch(&cfg);
 
     // -- check success
     assert!(res.is_ok(), "List command failed: {}", res.unwrap_err());
 
     // -- assert output has both tasks we previously added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
     let expected = vec![
         "Priority \t Task".to_string(),
         "   1 \t hello this is a task".to_string(),
@@ -93,16 +85,14 @@ fn test_cmd_list_four() {
 
     // -- do list command with same db that we just did adds to
 
-    let args = TKZArgs {
-        cmd: Some(TKZCmd::List)
-    };
+    let args = test_utils::example_list();
     let res = args.cmd().dispatch(&cfg);
 
     // -- check success
     assert!(res.is_ok(), "List command failed: {}", res.unwrap_err());
 
     // -- assert output has all tasks we previously added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
     let expected = vec![
         "Priority \t Task".to_string(),
         "   1 \t hello this is a task".to_string(),
@@ -114,3 +104,28 @@ fn test_cmd_list_four() {
 
 }
 // TODO test failure modes
+
+#[test]
+/// With the default weighted random strategy, the effective weight is the priority.
+fn test_cmd_list_effective() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = test_utils::example_add_cmd_task2();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    let args = test_utils::example_add_cmd_break1();
+    args.cmd().dispatch(&cfg).expect("Adding break failed");
+
+    let args = test_utils::example_list_effective();
+    let res = args.cmd().dispatch(&cfg);
+
+    assert!(res.is_ok(), "List command failed: {}", res.unwrap_err());
+
+    let output = res.unwrap().lines();
+    let expected = vec![
+        "Priority \t Effective \t Task".to_string(),
+        "   9 \t      9.00 \t hello this is also a task".to_string(),
+        "   2 \t      2.00 \t yo this is a break".to_string(),
+    ];
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,39 @@
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::TKZArgs;
+
+mod test_utils;
+
+#[test]
+/// Quick add without a terminal adds the task without asking, and shows how it was parsed.
+fn test_cmd_quick_add() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = TKZArgs::from_iter_safe(&["tkzr", "q", "call dentist !5 #break +phone est:10m"])
+        .expect("Parsing args failed");
+    let output = args.cmd().dispatch(&cfg).expect("Quick add failed").lines();
+    assert_eq!(output, vec![
+        "Task \"call dentist\" added to task list (priority 5, break, tags: phone, estimate 10m 0s).".to_string(),
+    ]);
+
+    let output = test_utils::example_list().cmd().dispatch(&cfg).expect("Listing tasks failed").lines();
+    assert_eq!(output, vec![
+        "Priority \t Task".to_string(),
+        "   5 \t call dentist".to_string(),
+    ]);
+}
+
+#[test]
+fn test_cmd_quick_add_dry_run() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = TKZArgs::from_iter_safe(&["tkzr", "q", "-n", "water", "plants", "!2"])
+        .expect("Parsing args failed");
+    let output = args.cmd().dispatch(&cfg).expect("Quick add failed").lines();
+    assert_eq!(output, vec![
+        "Dry run: task \"water plants\" would be added to task list (priority 2, task).".to_string(),
+    ]);
+
+    let output = test_utils::example_list().cmd().dispatch(&cfg).expect("Listing tasks failed").lines();
+    assert_eq!(output, vec!["Priority \t Task".to_string()]);
+}
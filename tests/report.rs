@@ -0,0 +1,97 @@
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::TKZArgs;
+
+mod test_utils;
+
+#[test]
+fn test_cmd_report_empty() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = test_utils::example_report(None, None);
+    let output = args.cmd().dispatch(&cfg).expect("Report failed").lines();
+
+    assert_eq!(output, vec!["No time tracked."]);
+}
+
+#[test]
+fn test_cmd_report_tracked() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+
+    let args = test_utils::example_report(None, None);
+    let output = args.cmd().dispatch(&cfg).expect("Report failed").lines();
+
+    assert_eq!(output.len(), 5, "Unexpected output: {:?}", output);
+    assert!(output[0].starts_with("Total: "));
+    assert!(output[2].ends_with("\t Task"));
+    assert!(output[4].ends_with("\t hello this is a task"));
+}
+
+#[test]
+/// Time on a tagged task is totalled per tag too, including after the task is completed.
+fn test_cmd_report_tags() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = TKZArgs::from_iter_safe(&["tkzr", "q", "call dentist +phone"]).expect("Parsing args failed");
+    args.cmd().dispatch(&cfg).expect("Quick add failed");
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    test_utils::example_complete().cmd().dispatch(&cfg).expect("Completing task failed");
+
+    let args = test_utils::example_report(None, None);
+    let output = args.cmd().dispatch(&cfg).expect("Report failed").lines();
+
+    assert_eq!(output.len(), 7, "Unexpected output: {:?}", output);
+    assert!(output[3].ends_with("\t Tag"));
+    assert!(output[4].ends_with("\t +phone"));
+    assert!(output[6].ends_with("\t call dentist"));
+}
+
+#[test]
+/// Dates outside of the range are not counted.
+fn test_cmd_report_range() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    let from = chrono::NaiveDate::from_ymd(2000, 1, 1);
+    let to = chrono::NaiveDate::from_ymd(2000, 1, 31);
+    let args = test_utils::example_report(Some(from), Some(to));
+    let output = args.cmd().dispatch(&cfg).expect("Report failed").lines();
+
+    assert_eq!(output, vec!["No time tracked."]);
+
+    // a single day is fine, but an end before the start is an error
+    let args = test_utils::example_report(Some(from), Some(from));
+    args.cmd().dispatch(&cfg).expect("Report failed");
+    let args = test_utils::example_report(Some(to), Some(from));
+    assert!(args.cmd().dispatch(&cfg).is_err(), "Report with --from after --to succeeded");
+}
+
+#[test]
+fn test_cmd_pause_resume() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let output = test_utils::example_pause().cmd().dispatch(&cfg).expect("Pausing failed").lines();
+    assert_eq!(output, vec!["No tasks."]);
+
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    let output = test_utils::example_resume().cmd().dispatch(&cfg).expect("Resuming failed").lines();
+    assert_eq!(output, vec!["Time tracking is not paused."]);
+
+    let output = test_utils::example_pause().cmd().dispatch(&cfg).expect("Pausing failed").lines();
+    assert_eq!(output, vec!["Paused time tracking on \"hello this is a task\"."]);
+
+    let output = test_utils::example_pause().cmd().dispatch(&cfg).expect("Pausing failed").lines();
+    assert_eq!(output, vec!["Time tracking is already paused."]);
+
+    let output = test_utils::example_resume().cmd().dispatch(&cfg).expect("Resuming failed").lines();
+    assert_eq!(output, vec!["Resumed time tracking on \"hello this is a task\"."]);
+}
@@ -0,0 +1,66 @@
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::TKZArgs;
+use taskerizer_prototype::config::Config;
+
+mod test_utils;
+
+fn run(cfg: &Config, argv: &[&str]) -> Vec<String> {
+    let args = TKZArgs::from_iter_safe(argv).expect("Parsing args failed");
+    args.cmd().dispatch(cfg).expect("Command failed").lines()
+}
+
+#[test]
+/// Search results are ranked, include completed tasks, and can be picked and changed.
+fn test_cmd_search() {
+    let (_dir, cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "add", "call dentist", "2"]);
+    run(&cfg, &["tkzr", "add", "call the bank about the card", "3"]);
+    run(&cfg, &["tkzr", "add", "water plants", "1"]);
+
+    assert_eq!(run(&cfg, &["tkzr", "search", "dentist"]).len(), 2);
+    assert_eq!(run(&cfg, &["tkzr", "search", "nothing"]), vec!["No tasks found.".to_string()]);
+
+    let results = run(&cfg, &["tkzr", "search", "cal"]);
+    assert_eq!(results.len(), 3);
+    assert!(results[1].ends_with("call dentist"), "Shorter match wasn't ranked first: {:?}", results);
+
+    assert_eq!(run(&cfg, &["tkzr", "search", "cal", "--pick", "1", "--current"]),
+               vec!["Current task is now \"call dentist\".".to_string()]);
+    assert_eq!(run(&cfg, &["tkzr", "current"])[0].trim(), "call dentist");
+
+    assert_eq!(run(&cfg, &["tkzr", "search", "bank", "--pick", "1", "--edit", "call the bank"]),
+               vec!["Task \"call the bank\" updated.".to_string()]);
+    assert_eq!(run(&cfg, &["tkzr", "search", "card"]), vec!["No tasks found.".to_string()]);
+
+    // completed tasks are found in the trash, and the change is undone like other commands
+    run(&cfg, &["tkzr", "complete"]);
+    let results = run(&cfg, &["tkzr", "search", "dentist"]);
+    assert!(results[1].ends_with("call dentist (in trash: completed)"), "Completed task not found: {:?}", results);
+
+    assert_eq!(run(&cfg, &["tkzr", "search", "plants", "--pick", "1", "--remove"]),
+               vec!["Task \"water plants\" moved to the trash.".to_string()]);
+    assert_eq!(run(&cfg, &["tkzr", "undo"])[0], "Undid search.");
+    assert!(!run(&cfg, &["tkzr", "search", "plants"])[1].contains("in trash"));
+}
+
+#[test]
+/// Completed tasks are found by the note they were completed with, and the note is undone along
+/// with the completion.
+fn test_cmd_search_completion_note() {
+    let (_dir, cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "add", "call dentist", "2"]);
+
+    let args = TKZArgs::from_iter_safe(&["tkzr", "complete", "--note", " "]).expect("Parsing args failed");
+    assert!(args.cmd().dispatch(&cfg).is_err(), "Blank note was accepted");
+    assert_eq!(run(&cfg, &["tkzr", "search", "dentist"]).len(), 2);
+
+    assert!(run(&cfg, &["tkzr", "complete", "-n", "appointment moved to friday"])[0].starts_with("Task \"call dentist\" completed."));
+    let results = run(&cfg, &["tkzr", "search", "friday"]);
+    assert!(results[1].ends_with("call dentist (appointment moved to friday) (in trash: completed)"),
+            "Completion note not found: {:?}", results);
+    assert!(run(&cfg, &["tkzr", "trash", "list"])[1].ends_with("call dentist (appointment moved to friday)"));
+
+    run(&cfg, &["tkzr", "undo"]);
+    assert_eq!(run(&cfg, &["tkzr", "search", "friday"]), vec!["No tasks found.".to_string()]);
+}
@@ -0,0 +1,109 @@
+mod test_utils;
+
+use taskerizer_prototype::config::StrategyKind;
+
+#[test]
+fn test_cmd_simulate_empty() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = test_utils::example_simulate(100, None);
+    let res = args.cmd().dispatch(&cfg);
+
+    assert!(res.is_ok(), "Simulate command failed: {}", res.unwrap_err());
+
+    let output = res.unwrap().lines();
+    let expected = vec!["No tasks."];
+    assert_eq!(output, expected);
+}
+
+#[test]
+/// With a single task, it is always selected.
+fn test_cmd_simulate_1() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    let args = test_utils::example_simulate(100, Some(3));
+    let res = args.cmd().dispatch(&cfg);
+
+    assert!(res.is_ok(), "Simulate command failed: {}", res.unwrap_err());
+
+    let output = res.unwrap().lines();
+    let expected = vec![
+        "Simulated 100 selections.".to_string(),
+        "Categories: Task 100 (100.00%), Break 0 (0.00%)\n".to_string(),
+        "Count \t Percent \t Category \t Task".to_string(),
+        "  100 \t 100.00% \t Task     \t hello this is a task".to_string(),
+        "".to_string(),
+        "Simulated completion order:".to_string(),
+        "   1. Task: hello this is a task".to_string(),
+    ];
+    assert_eq!(output, expected);
+}
+
+#[test]
+/// Simulate with tasks and breaks, check that the counts add up and that every task is listed.
+fn test_cmd_simulate_counts() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    for args in &[test_utils::example_add_cmd_task1(), test_utils::example_add_cmd_task2(),
+                  test_utils::example_add_cmd_break1(), test_utils::example_add_cmd_break2()] {
+        args.cmd().dispatch(&cfg).expect("Adding task failed");
+    }
+
+    let args = test_utils::example_simulate(1000, Some(10));
+    let output = args.cmd().dispatch(&cfg).expect("Simulate command failed").lines();
+
+    // 3 header lines, 4 tasks, blank line and completion header, 4 completions
+    assert_eq!(output.len(), 3 + 4 + 2 + 4, "Incorrect output: {:?}", output);
+
+    let total: usize = output[3..7].iter()
+        .map(|line| line.split('\t').next().unwrap().trim().parse::<usize>().expect("Count was not a number"))
+        .sum();
+    assert_eq!(total, 1000);
+}
+
+#[test]
+/// Simulating does not change the current task.
+fn test_cmd_simulate_read_only() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    let args = test_utils::example_current();
+    let before = args.cmd().dispatch(&cfg).expect("Current command failed");
+
+    let args = test_utils::example_simulate(100, Some(1));
+    args.cmd().dispatch(&cfg).expect("Simulate command failed");
+
+    let args = test_utils::example_current();
+    let after = args.cmd().dispatch(&cfg).expect("Current command failed");
+
+    assert_eq!(before, after);
+}
+
+#[test]
+/// Simulated completions count towards the next break, so with the pomodoro strategy forcing a
+/// break after every task, tasks and breaks alternate.
+fn test_cmd_simulate_completions_context() {
+    let (_dir, mut cfg) = test_utils::temp_config();
+    cfg.break_cutoff = 0.0;
+    cfg.selection.strategy = StrategyKind::Pomodoro;
+    cfg.selection.pomodoro_tasks_per_break = 1;
+    cfg.selection.pomodoro_work_minutes = 0;
+
+    for args in &[test_utils::example_add_cmd_task1(), test_utils::example_add_cmd_task2(),
+                  test_utils::example_add_cmd_break1(), test_utils::example_add_cmd_break2()] {
+        args.cmd().dispatch(&cfg).expect("Adding task failed");
+    }
+
+    let args = test_utils::example_simulate(10, Some(4));
+    let output = args.cmd().dispatch(&cfg).expect("Simulate command failed").lines();
+
+    let order: Vec<&str> = output[output.len() - 4..].iter()
+        .map(|line| line.trim_start().split_once(' ').unwrap().1.split(':').next().unwrap())
+        .collect();
+    assert_eq!(order, vec!["Task", "Break", "Task", "Break"], "Incorrect output: {:?}", output);
+}
@@ -1,3 +1,10 @@
+use std::fs;
+
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::TKZArgs;
+use taskerizer_prototype::config::StrategyKind;
+
 mod test_utils;
 
 // -- TODO test top parameter
@@ -15,7 +22,7 @@ fn test_cmd_skip_empty() {
     assert!(res.is_ok(), "Skipping task failed: {}", res.unwrap_err());
 
     // -- check output says no task was found
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     let expected = vec!["No tasks."];
 
@@ -40,7 +47,7 @@ fn test_cmd_skip_1() {
     assert!(res.is_ok(), "Skipping task failed: {}", res.unwrap_err());
 
     // -- check output is the task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     let expected = vec![
         format!("Current task is now \"{}\".\n", "hello this is a task"),
@@ -67,7 +74,7 @@ fn test_cmd_skip_2() {
     assert!(res.is_ok(), "Skipping task failed: {}", res.unwrap_err());
 
     // -- check output is the task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     let expected = vec![
         format!("Current task is now \"{}\".\n", "yo this is a break"),
@@ -98,7 +105,7 @@ fn test_cmd_skip_3() {
     assert!(res.is_ok(), "Skipping task failed: {}", res.unwrap_err());
 
     // -- check output contains the second task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     let expected = vec![
         format!("Current task is now \"{}\".\n", "hello this is a task"),
@@ -127,7 +134,7 @@ fn test_cmd_skip_4() {
     assert!(res.is_ok(), "Skipping task failed: {}", res.unwrap_err());
 
     // -- check output contains the second task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     let expected = vec![
         format!("Current task is now \"{}\".\n", "hello this is also a task"),
@@ -141,7 +148,7 @@ fn test_cmd_skip_4() {
     let res = args.cmd().dispatch(&cfg);
 
     // -- check output contains the first task we added
-    let output = res.unwrap();
+    let output = res.unwrap().lines();
 
     let expected = vec![
         format!("Current task is now \"{}\".\n", "hello this is a task"),
@@ -149,3 +156,93 @@ fn test_cmd_skip_4() {
 
     assert_eq!(output, expected);
 }
+
+#[test]
+/// Add three tasks and use the no-repeat strategy excluding the last two selections, then skip
+/// repeatedly and check that we cycle through all three tasks without repeats.
+fn test_cmd_skip_no_repeat() {
+    let (_dir, mut cfg) = test_utils::temp_config();
+    cfg.selection.strategy = StrategyKind::NoRepeat;
+    cfg.selection.no_repeat_window = 2;
+    cfg.selection.no_repeat_decay = 0.0;
+
+    // -- do three add commands
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+    let args = test_utils::example_add_cmd_task2();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+    let args = test_utils::example_add_cmd_task3();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    // -- skip a bunch of times, recording the current task
+    let mut outputs = Vec::new();
+    for _ in 0..10 {
+        let args = test_utils::example_skip();
+        let output = args.cmd().dispatch(&cfg).expect("Skipping task failed").lines();
+        outputs.push(output);
+    }
+
+    // -- check every three consecutive current tasks are different
+    for window in outputs.windows(3) {
+        assert_ne!(window[0], window[1]);
+        assert_ne!(window[1], window[2]);
+        assert_ne!(window[0], window[2]);
+    }
+}
+
+#[test]
+/// Skipping a task keeps the tags and estimate it was added with.
+fn test_cmd_skip_keeps_details() {
+    let (dir, cfg) = test_utils::temp_config();
+
+    let args = TKZArgs::from_iter_safe(&["tkzr", "q", "call dentist +phone est:10m"]).expect("Parsing args failed");
+    args.cmd().dispatch(&cfg).expect("Quick add failed");
+    test_utils::example_skip().cmd().dispatch(&cfg).expect("Skipping task failed");
+
+    let path = dir.path().join("export.json");
+    let args = TKZArgs::from_iter_safe(&["tkzr", "export", path.to_str().unwrap()]).expect("Parsing args failed");
+    args.cmd().dispatch(&cfg).expect("Exporting failed");
+
+    let json = fs::read_to_string(&path).expect("Export was not written");
+    assert!(json.contains("\"phone\""), "Tags were lost by skip: {}", json);
+    assert!(json.contains("\"estimate_seconds\": 600"), "Estimate was lost by skip: {}", json);
+}
+
+#[test]
+/// `break` skips the current task and selects a break, keeping the skipped task.
+fn test_cmd_break() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = TKZArgs::from_iter_safe(&["tkzr", "break"]).expect("Parsing args failed");
+    assert_eq!(args.cmd().dispatch(&cfg).expect("Taking a break failed").lines(), vec!["No tasks."]);
+
+    test_utils::example_add_cmd_task1().cmd().dispatch(&cfg).expect("Adding task failed");
+    let args = TKZArgs::from_iter_safe(&["tkzr", "add", "--break", "stretch"]).expect("Parsing args failed");
+    args.cmd().dispatch(&cfg).expect("Adding break failed");
+
+    let args = TKZArgs::from_iter_safe(&["tkzr", "break"]).expect("Parsing args failed");
+    let output = args.cmd().dispatch(&cfg).expect("Taking a break failed").lines();
+    assert_eq!(output, vec!["Current task is now \"stretch\".\n".to_string()]);
+
+    let output = test_utils::example_list().cmd().dispatch(&cfg).expect("Listing tasks failed").lines();
+    assert_eq!(output.len(), 3, "Skipped task was not kept: {:?}", output);
+}
+
+#[test]
+/// `break -p` chooses a break with the given probability, so with 0 another task is chosen.
+fn test_cmd_break_probability() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    test_utils::example_add_cmd_task1().cmd().dispatch(&cfg).expect("Adding task failed");
+    test_utils::example_add_cmd_task2().cmd().dispatch(&cfg).expect("Adding task failed");
+    let args = TKZArgs::from_iter_safe(&["tkzr", "add", "--break", "stretch"]).expect("Parsing args failed");
+    args.cmd().dispatch(&cfg).expect("Adding break failed");
+
+    let args = TKZArgs::from_iter_safe(&["tkzr", "break", "-p", "0"]).expect("Parsing args failed");
+    let output = args.cmd().dispatch(&cfg).expect("Taking a break failed").lines();
+    assert_eq!(output, vec!["Current task is now \"hello this is also a task\".\n".to_string()]);
+
+    for p in &["1", "1.5", "-0.5", "NaN", "often"] {
+        assert!(TKZArgs::from_iter_safe(&["tkzr", "break", "--probability", p]).is_err(), "Probability {} was accepted", p);
+    }
+}
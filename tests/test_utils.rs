@@ -7,9 +7,10 @@ use taskerizer_prototype as tkzr;
 use tempfile::{tempdir, TempDir};
 
 use self::tkzr::commands::{TKZArgs, TKZCmd};
-use self::tkzr::commands::{Add, Current};
+use self::tkzr::render::{ColorChoice, OutputFormat};
+use self::tkzr::commands::{Add, Complete, Current, List, Report, Simulate, Timer};
 
-use self::tkzr::config::Config;
+use self::tkzr::config::{Config, SelectionConfig, TimerConfig, BackupConfig, TrashConfig};
 
 /// Create a test config with the database in a temporary directory. We return the TempDir because
 /// it is deleted when it is dropped.
@@ -22,6 +23,11 @@ pub fn temp_config() -> (TempDir, Config) {
     let cfg = Config {
         db_path: db_path,
         break_cutoff: 0.33,
+        hooks_path: None,
+        selection: SelectionConfig::default(),
+        timer: TimerConfig::default(),
+        backup: BackupConfig::default(),
+        trash: TrashConfig::default(),
     };
 
     (test_dir, cfg)
@@ -30,6 +36,8 @@ pub fn temp_config() -> (TempDir, Config) {
 pub fn example_add_cmd_task1() -> TKZArgs {
     let task = "hello this is a task".to_string();
     TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
         cmd: Some(TKZCmd::Add( Add {
             reward: false,
             priority: 1,
@@ -41,6 +49,8 @@ pub fn example_add_cmd_task1() -> TKZArgs {
 pub fn example_add_cmd_task2() -> TKZArgs {
     let task = "hello this is also a task".to_string();
     TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
         cmd: Some(TKZCmd::Add( Add {
             reward: false,
             priority: 9,
@@ -49,9 +59,24 @@ pub fn example_add_cmd_task2() -> TKZArgs {
     }  
 }
 
+pub fn example_add_cmd_task3() -> TKZArgs {
+    let task = "yet another task".to_string();
+    TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
+        cmd: Some(TKZCmd::Add( Add {
+            reward: false,
+            priority: 3,
+            task: task,
+        }))
+    }
+}
+
 pub fn example_add_cmd_break1() -> TKZArgs {
     let task = "yo this is a break".to_string();
     TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
         cmd: Some(TKZCmd::Add( Add {
             reward: true,
             priority: 2,
@@ -63,6 +88,8 @@ pub fn example_add_cmd_break1() -> TKZArgs {
 pub fn example_add_cmd_break2() -> TKZArgs {
     let task = "ayyy this is another break".to_string();
     TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
         cmd: Some(TKZCmd::Add( Add {
             reward: true,
             priority: 4,
@@ -73,6 +100,8 @@ pub fn example_add_cmd_break2() -> TKZArgs {
 
 pub fn example_current() -> TKZArgs {
     TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
         cmd: Some(TKZCmd::Current( Current {
             top: false
         })),
@@ -81,20 +110,106 @@ pub fn example_current() -> TKZArgs {
 
 pub fn example_current_top() -> TKZArgs {
     TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
         cmd: Some(TKZCmd::Current( Current {
             top: true
         })),
     }
 }
 
+pub fn example_list() -> TKZArgs {
+    TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
+        cmd: Some(TKZCmd::List( List {
+            effective: false
+        })),
+    }
+}
+
+pub fn example_list_effective() -> TKZArgs {
+    TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
+        cmd: Some(TKZCmd::List( List {
+            effective: true
+        })),
+    }
+}
+
 pub fn example_complete() -> TKZArgs {
     TKZArgs {
-        cmd: Some(TKZCmd::Complete),
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
+        cmd: Some(TKZCmd::Complete(Complete::default())),
+    }
+}
+
+pub fn example_simulate(n: usize, completions: Option<usize>) -> TKZArgs {
+    TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
+        cmd: Some(TKZCmd::Simulate( Simulate {
+            n,
+            completions,
+        })),
+    }
+}
+
+pub fn example_why() -> TKZArgs {
+    TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
+        cmd: Some(TKZCmd::Why),
     }
 }
 
 pub fn example_skip() -> TKZArgs {
     TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
         cmd: Some(TKZCmd::Skip),
     }
 }
+
+/// A timer `millis` milliseconds long, which can't be given on the command line, so that tests
+/// don't wait long.
+pub fn example_timer(millis: i64, select_break: bool) -> TKZArgs {
+    TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
+        cmd: Some(TKZCmd::Timer( Timer {
+            length: chrono::Duration::milliseconds(millis),
+            max: None,
+            select_break,
+        })),
+    }
+}
+
+pub fn example_pause() -> TKZArgs {
+    TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
+        cmd: Some(TKZCmd::Pause),
+    }
+}
+
+pub fn example_resume() -> TKZArgs {
+    TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
+        cmd: Some(TKZCmd::Resume),
+    }
+}
+
+pub fn example_report(from: Option<chrono::NaiveDate>, to: Option<chrono::NaiveDate>) -> TKZArgs {
+    TKZArgs {
+        format: OutputFormat::Plain,
+        color: ColorChoice::Auto,
+        cmd: Some(TKZCmd::Report( Report {
+            from,
+            to,
+        })),
+    }
+}
@@ -0,0 +1,157 @@
+mod test_utils;
+
+use std::thread;
+use std::time::Duration;
+
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::{CommandOutput, TKZArgs, TKZCmd};
+
+#[test]
+fn test_cmd_timer_empty() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = test_utils::example_timer(50, false);
+    let output = args.cmd().dispatch(&cfg).expect("Running timer failed").lines();
+
+    assert_eq!(output, vec!["No tasks."]);
+}
+
+#[test]
+fn test_cmd_timer_finishes() {
+    let (dir, mut cfg) = test_utils::temp_config();
+
+    let notified = dir.path().join("notified");
+    cfg.timer.notify_command = format!("printf '%s' \"$TKZR_TASK\" > '{}'", notified.display());
+
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    let args = test_utils::example_timer(50, false);
+    let output = args.cmd().dispatch(&cfg).expect("Running timer failed").lines();
+
+    assert_eq!(output, vec![format!("Timer for \"{}\" finished.", "hello this is a task")]);
+
+    // -- check notification command was run with the task
+    let contents = std::fs::read_to_string(&notified).expect("Notification command did not run");
+    assert_eq!(contents, "hello this is a task");
+}
+
+#[test]
+/// A failing notification command is reported but doesn't fail the timer.
+fn test_cmd_timer_notify_fails() {
+    let (_dir, mut cfg) = test_utils::temp_config();
+    cfg.timer.notify_command = "exit 1".to_string();
+
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    let args = test_utils::example_timer(50, false);
+    let output = args.cmd().dispatch(&cfg).expect("Running timer failed").lines();
+
+    assert_eq!(output.len(), 2);
+    assert!(output[1].starts_with("Notification failed"), "Unexpected output: {:?}", output);
+}
+
+#[test]
+/// With --break, a break is selected when the timer ends.
+fn test_cmd_timer_select_break() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+    let args = test_utils::example_add_cmd_break1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    // make sure the task is current
+    let mut current = args_current(&cfg);
+    while current != "hello this is a task\n" {
+        test_utils::example_skip().cmd().dispatch(&cfg).expect("Skipping task failed");
+        current = args_current(&cfg);
+    }
+
+    let args = test_utils::example_timer(50, true);
+    let output = args.cmd().dispatch(&cfg).expect("Running timer failed").lines();
+
+    assert_eq!(output, vec![
+        format!("Timer for \"{}\" finished.", "hello this is a task"),
+        format!("Current task is now \"{}\".", "yo this is a break"),
+    ]);
+    assert_eq!(args_current(&cfg), "yo this is a break\n");
+}
+
+#[test]
+/// While the timer is running, `current` shows the remaining time.
+fn test_cmd_timer_current_remaining() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task failed");
+
+    let timer_cfg = cfg.clone();
+    let handle = thread::spawn(move || {
+        let args = test_utils::example_timer(600, false);
+        args.cmd().dispatch(&timer_cfg).expect("Running timer failed")
+    });
+
+    thread::sleep(Duration::from_millis(100));
+    let output = test_utils::example_current().cmd().dispatch(&cfg).expect("Getting current task failed").lines();
+    assert_eq!(output.len(), 4);
+    assert!(output[3].starts_with("Timer: ") && output[3].ends_with(" remaining"),
+        "Unexpected output: {:?}", output);
+
+    handle.join().expect("Timer thread panicked");
+
+    // -- after the timer finishes there is no timer line
+    let output = test_utils::example_current().cmd().dispatch(&cfg).expect("Getting current task failed").lines();
+    assert_eq!(output.len(), 3);
+}
+
+#[test]
+fn test_cmd_timer_parse_length() {
+    let args = TKZArgs::from_iter_safe(&["tkzr", "timer", "90s", "--max", "2h"]).expect("Parsing args failed");
+    match args.cmd() {
+        TKZCmd::Timer(timer) => {
+            assert_eq!(timer.length, chrono::Duration::seconds(90));
+            assert_eq!(timer.max, Some(chrono::Duration::hours(2)));
+        }
+        cmd => panic!("Parsed wrong command: {:?}", cmd),
+    }
+
+    let args = TKZArgs::from_iter_safe(&["tkzr", "timer"]).expect("Parsing args failed");
+    match args.cmd() {
+        TKZCmd::Timer(timer) => assert_eq!(timer.length, chrono::Duration::minutes(25)),
+        cmd => panic!("Parsed wrong command: {:?}", cmd),
+    }
+
+    assert!(TKZArgs::from_iter_safe(&["tkzr", "timer", "5d"]).is_err());
+    assert!(TKZArgs::from_iter_safe(&["tkzr", "timer", "0"]).is_err());
+    // too long for a Duration, or overflowing when converted to seconds
+    assert!(TKZArgs::from_iter_safe(&["tkzr", "timer", "99999999999999h"]).is_err());
+    assert!(TKZArgs::from_iter_safe(&["tkzr", "timer", "9223372036854775807m"]).is_err());
+    assert!(TKZArgs::from_iter_safe(&["tkzr", "timer", "8785h"]).is_err());
+    assert!(TKZArgs::from_iter_safe(&["tkzr", "timer", "8784h"]).is_ok());
+}
+
+#[test]
+/// The start of the timer is passed to the progress callback, not returned or printed.
+fn test_cmd_timer_progress() {
+    let (_dir, cfg) = test_utils::temp_config();
+    test_utils::example_add_cmd_task1().cmd().dispatch(&cfg).expect("Adding task failed");
+
+    let mut started = Vec::new();
+    let output = test_utils::example_timer(50, false).cmd()
+        .dispatch_with_progress(&cfg, &mut |output| started.push(output.lines()))
+        .expect("Running timer failed");
+
+    assert_eq!(started, vec![vec!["Timer started for \"hello this is a task\": 0s.".to_string()]]);
+    match output {
+        CommandOutput::TimerFinished { .. } => {}
+        output => panic!("Unexpected output: {:?}", output),
+    }
+}
+
+fn args_current(cfg: &taskerizer_prototype::config::Config) -> String {
+    let output = test_utils::example_current().cmd().dispatch(cfg).expect("Getting current task failed").lines();
+    output[0].clone()
+}
@@ -0,0 +1,74 @@
+use rusqlite::{Connection, NO_PARAMS};
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::{CommandOutput, TKZArgs};
+use taskerizer_prototype::config::Config;
+
+mod test_utils;
+
+fn dispatch(cfg: &Config, argv: &[&str]) -> CommandOutput {
+    let args = TKZArgs::from_iter_safe(argv).expect("Parsing args failed");
+    args.cmd().dispatch(cfg).expect("Command failed")
+}
+
+fn run(cfg: &Config, argv: &[&str]) -> Vec<String> {
+    dispatch(cfg, argv).lines()
+}
+
+/// The descriptions of the tasks in the trash, most recently removed first.
+fn trash(cfg: &Config) -> Vec<String> {
+    match dispatch(cfg, &["tkzr", "trash", "list"]) {
+        CommandOutput::Trash(trash) => trash.into_iter().map(|trashed| trashed.task.task().to_string()).collect(),
+        output => panic!("Unexpected output from trash list: {:?}", output),
+    }
+}
+
+#[test]
+/// Completed tasks can be restored from the trash by the start of their UUID.
+fn test_cmd_trash_restore() {
+    let (_dir, cfg) = test_utils::temp_config();
+    assert_eq!(run(&cfg, &["tkzr", "trash", "list"]), vec!["The trash is empty.".to_string()]);
+
+    run(&cfg, &["tkzr", "add", "only task", "2"]);
+    run(&cfg, &["tkzr", "complete"]);
+    assert_eq!(trash(&cfg), vec!["only task".to_string()]);
+
+    let listed = run(&cfg, &["tkzr", "trash", "list"]);
+    assert_eq!(listed.len(), 2);
+    assert!(listed[1].contains("completed"), "Trash list doesn't show the removal source: {}", listed[1]);
+    let id = listed[1].split_whitespace().next().unwrap().to_string();
+
+    let args = TKZArgs::from_iter_safe(&["tkzr", "trash", "restore", "not-an-id"]).unwrap();
+    assert!(args.cmd().dispatch(&cfg).is_err());
+
+    assert_eq!(run(&cfg, &["tkzr", "trash", "restore", &id]), vec!["Task \"only task\" restored from the trash.".to_string()]);
+    assert!(trash(&cfg).is_empty());
+    assert_eq!(run(&cfg, &["tkzr", "current"])[0].trim(), "only task");
+
+    // undoing the restore puts it back in the trash
+    assert_eq!(run(&cfg, &["tkzr", "undo"])[0], "Undid trash restore.");
+    assert_eq!(trash(&cfg), vec!["only task".to_string()]);
+
+    assert_eq!(run(&cfg, &["tkzr", "trash", "empty"]), vec!["Removed 1 tasks from the trash.".to_string()]);
+    assert!(trash(&cfg).is_empty());
+}
+
+#[test]
+/// Tasks are removed from the trash after the retention period when the database is opened, unless
+/// it is 0.
+fn test_cmd_trash_retention() {
+    let (dir, mut cfg) = test_utils::temp_config();
+    run(&cfg, &["tkzr", "add", "old task", "2"]);
+    run(&cfg, &["tkzr", "complete"]);
+
+    let conn = Connection::open(dir.path().join("tkzr_sqlite3.db")).expect("Failed to open database");
+    conn.execute("UPDATE trash SET date_removed = '2000-01-01T00:00:00+00:00'", NO_PARAMS)
+        .expect("Failed to change removal date");
+    drop(conn);
+
+    cfg.trash.retention_days = 0;
+    assert_eq!(trash(&cfg), vec!["old task".to_string()]);
+
+    cfg.trash.retention_days = 30;
+    assert!(trash(&cfg).is_empty());
+}
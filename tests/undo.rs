@@ -0,0 +1,49 @@
+use structopt::StructOpt;
+
+use taskerizer_prototype::commands::TKZArgs;
+use taskerizer_prototype::config::Config;
+
+mod test_utils;
+
+fn run(cfg: &Config, argv: &[&str]) -> Vec<String> {
+    let args = TKZArgs::from_iter_safe(argv).expect("Parsing args failed");
+    args.cmd().dispatch(cfg).expect("Command failed").lines()
+}
+
+fn list(cfg: &Config) -> Vec<String> {
+    test_utils::example_list().cmd().dispatch(cfg).expect("Listing tasks failed").lines()
+}
+
+#[test]
+/// Undoing a complete brings the task back as the current task, and undoing adds removes them.
+fn test_cmd_undo_complete() {
+    let (_dir, cfg) = test_utils::temp_config();
+    assert_eq!(run(&cfg, &["tkzr", "undo"]), vec!["Nothing to undo.".to_string()]);
+
+    run(&cfg, &["tkzr", "add", "first task", "2"]);
+    run(&cfg, &["tkzr", "add", "second task", "3"]);
+    let current = run(&cfg, &["tkzr", "current"])[0].trim().to_string();
+
+    run(&cfg, &["tkzr", "complete"]);
+    assert_eq!(list(&cfg).len(), 2);
+
+    assert_eq!(run(&cfg, &["tkzr", "undo"]), vec![
+        "Undid complete.".to_string(),
+        format!("Current task: {}", current),
+    ]);
+    assert_eq!(list(&cfg), vec![
+        "Priority \t Task".to_string(),
+        "   2 \t first task".to_string(),
+        "   3 \t second task".to_string(),
+    ]);
+
+    // list and current aren't recorded
+    assert_eq!(run(&cfg, &["tkzr", "undo", "2"])[0], "Undid add, add.");
+    assert_eq!(list(&cfg), vec!["Priority \t Task".to_string()]);
+    assert_eq!(run(&cfg, &["tkzr", "undo"]), vec!["Nothing to undo.".to_string()]);
+}
+
+#[test]
+fn test_cmd_undo_zero() {
+    assert!(TKZArgs::from_iter_safe(&["tkzr", "undo", "0"]).is_err());
+}
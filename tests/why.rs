@@ -0,0 +1,50 @@
+mod test_utils;
+
+#[test]
+fn test_cmd_why_empty() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    let args = test_utils::example_why();
+    let res = args.cmd().dispatch(&cfg);
+
+    assert!(res.is_ok(), "Why command failed: {}", res.unwrap_err());
+
+    let output = res.unwrap().lines();
+    let expected = vec!["No tasks."];
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_cmd_why() {
+    let (_dir, cfg) = test_utils::temp_config();
+
+    // -- add two tasks and a break
+
+    let args = test_utils::example_add_cmd_task1();
+    args.cmd().dispatch(&cfg).expect("Adding task 1 failed");
+
+    let args = test_utils::example_add_cmd_task2();
+    args.cmd().dispatch(&cfg).expect("Adding task 2 failed");
+
+    let args = test_utils::example_add_cmd_break1();
+    args.cmd().dispatch(&cfg).expect("Adding break 1 failed");
+
+    // -- do why command
+
+    let args = test_utils::example_why();
+    let res = args.cmd().dispatch(&cfg);
+
+    assert!(res.is_ok(), "Why command failed: {}", res.unwrap_err());
+
+    // temp_config's break probability is 0.33
+    // task 1 has priority 1 and task 2 has priority 9
+    let output = res.unwrap().lines();
+    let expected = vec![
+        "Category probabilities: Task 67.00%, Break 33.00%\n".to_string(),
+        "Probability \t Category \t Task".to_string(),
+        "      6.70% \t Task     \t hello this is a task".to_string(),
+        "     60.30% \t Task     \t hello this is also a task".to_string(),
+        "     33.00% \t Break    \t yo this is a break".to_string(),
+    ];
+    assert_eq!(output, expected);
+}